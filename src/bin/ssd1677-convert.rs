@@ -0,0 +1,232 @@
+//! Convert images into packed BW/RED framebuffers
+//!
+//! ```text
+//! ssd1677-convert --size <WxH> [OPTIONS] <IMAGE>
+//!
+//! Options:
+//!   --size <WxH>       Panel size in native orientation, e.g. 800x480
+//!   --rotation <DEG>   Rotation the buffers are displayed with: 0, 90, 180, 270 (default 0)
+//!   --mirror <AXES>    Mirroring the buffers are drawn with: horizontal, vertical or both
+//!   --dither <METHOD>  threshold, floyd-steinberg, atkinson or ordered (default floyd-steinberg)
+//!   --threshold <N>    Luminance below which pixels are black, 0-255 (default 128)
+//!   --red <N>          Emit a RED plane; pixels whose red exceeds green and blue by N are red
+//!   --resize           Scale the image to the drawing size instead of requiring it
+//!   --rle              Run-length encode the planes for `Display::update_compressed`
+//!   --name <NAME>      Constant name prefix (default: derived from the image file name)
+//!   --output <PATH>    Write Rust source (.rs) or raw planes (.bin) instead of Rust to stdout
+//!   --preview <PATH>   Also save the converted image (.pbm/.pgm/.ppm/.png)
+//! ```
+//!
+//! The image must match the drawing size for the rotation (width x height for
+//! 0/180, height x width for 90/270) unless `--resize` is given. Raw output
+//! writes `<stem>_black.bin` and, with `--red`, `<stem>_red.bin`.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use ssd1677::convert::{Converter, Dither, Image, Planes};
+use ssd1677::import::{const_name, to_rust_const};
+use ssd1677::rle;
+use ssd1677::snapshot::Snapshot;
+use ssd1677::{Dimensions, Mirror, Rotation};
+
+const USAGE: &str = "usage: ssd1677-convert --size WxH [--rotation 0|90|180|270] [--mirror AXES] [--dither METHOD] [--threshold N] [--red N] [--resize] [--rle] [--name NAME] [--output PATH] [--preview PATH] <IMAGE>";
+
+/// Panel width and height in native orientation
+type Size = (u16, u16);
+
+#[derive(Default)]
+struct Options {
+    path: Option<String>,
+    size: Option<Size>,
+    rotation: Rotation,
+    mirror: Mirror,
+    dither: Dither,
+    threshold: Option<u8>,
+    red: Option<u8>,
+    resize: bool,
+    rle: bool,
+    name: Option<String>,
+    output: Option<String>,
+    preview: Option<String>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => options.size = Some(size(&value(&mut args, &arg)?)?),
+            "--rotation" => options.rotation = rotation(&value(&mut args, &arg)?)?,
+            "--mirror" => options.mirror = mirror(&value(&mut args, &arg)?)?,
+            "--dither" => options.dither = dither(&value(&mut args, &arg)?)?,
+            "--threshold" => options.threshold = Some(level(&value(&mut args, &arg)?)?),
+            "--red" => options.red = Some(level(&value(&mut args, &arg)?)?),
+            "--resize" => options.resize = true,
+            "--rle" => options.rle = true,
+            "--name" => options.name = Some(value(&mut args, &arg)?),
+            "--output" => options.output = Some(value(&mut args, &arg)?),
+            "--preview" => options.preview = Some(value(&mut args, &arg)?),
+            "-h" | "--help" => return Err(String::from(USAGE)),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}\n{USAGE}")),
+            _ => options.path = Some(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("{flag} requires a value"))
+}
+
+fn size(text: &str) -> Result<Size, String> {
+    let parsed = text
+        .split_once(['x', 'X'])
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)));
+    parsed.ok_or_else(|| format!("invalid size {text}, expected WxH"))
+}
+
+fn rotation(text: &str) -> Result<Rotation, String> {
+    match text {
+        "0" => Ok(Rotation::Rotate0),
+        "90" => Ok(Rotation::Rotate90),
+        "180" => Ok(Rotation::Rotate180),
+        "270" => Ok(Rotation::Rotate270),
+        _ => Err(format!(
+            "invalid rotation {text}, expected 0, 90, 180 or 270"
+        )),
+    }
+}
+
+fn mirror(text: &str) -> Result<Mirror, String> {
+    match text {
+        "none" => Ok(Mirror::None),
+        "horizontal" | "h" => Ok(Mirror::Horizontal),
+        "vertical" | "v" => Ok(Mirror::Vertical),
+        "both" => Ok(Mirror::Both),
+        _ => Err(format!(
+            "invalid mirror {text}, expected horizontal, vertical or both"
+        )),
+    }
+}
+
+fn dither(text: &str) -> Result<Dither, String> {
+    Dither::from_name(text).ok_or_else(|| format!("unknown dither method {text}"))
+}
+
+fn level(text: &str) -> Result<u8, String> {
+    text.parse()
+        .map_err(|_| format!("invalid level {text}, expected 0-255"))
+}
+
+fn render(options: &Options, path: &str, name: &str, planes: &Planes) -> String {
+    let (width, height) = options.size.unwrap_or_default();
+    let mut out = format!(
+        "// Generated by ssd1677-convert from {path}\n// Panel {width}x{height}, {:?}, {:?}, {:?}\n",
+        options.rotation, options.mirror, options.dither
+    );
+    if options.rle {
+        out.push_str("// Run-length encoded for Display::update_compressed\n");
+    }
+    out.push('\n');
+    out.push_str(&to_rust_const(
+        &format!("{name}_BLACK"),
+        &planes.black,
+        None,
+    ));
+    if options.red.is_some() {
+        out.push('\n');
+        out.push_str(&to_rust_const(&format!("{name}_RED"), &planes.red, None));
+    }
+    out
+}
+
+fn write_planes(options: &Options, output: &Path, planes: &Planes) -> Result<(), String> {
+    let stem = output.with_extension("");
+    let stem = stem.to_string_lossy();
+    let black = format!("{stem}_black.bin");
+    std::fs::write(&black, &planes.black).map_err(|e| format!("{black}: {e}"))?;
+    if options.red.is_some() {
+        let red = format!("{stem}_red.bin");
+        std::fs::write(&red, &planes.red).map_err(|e| format!("{red}: {e}"))?;
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), String> {
+    let options = parse_args()?;
+    let path = options.path.clone().ok_or_else(|| String::from(USAGE))?;
+    let (width, height) = options
+        .size
+        .ok_or_else(|| format!("--size is required\n{USAGE}"))?;
+    let dimensions = Dimensions::new(height, width)
+        .map_err(|e| format!("invalid size {width}x{height}: {e}"))?;
+
+    let converter = Converter::new(dimensions)
+        .rotation(options.rotation)
+        .mirror(options.mirror)
+        .dither(options.dither)
+        .threshold(options.threshold.unwrap_or(128))
+        .red_threshold(options.red);
+    let mut image = Image::load(&path).map_err(|e| format!("{path}: {e}"))?;
+    if options.resize {
+        let (width, height) = converter.size();
+        image = image.resize(width, height);
+    }
+    let planes = converter
+        .convert(&image)
+        .map_err(|e| format!("{path}: {e}"))?;
+
+    if let Some(preview) = &options.preview {
+        Snapshot::from_buffers_mirrored(
+            &planes.black,
+            &planes.red,
+            dimensions,
+            options.rotation,
+            options.mirror,
+        )
+        .and_then(|snapshot| snapshot.save(preview))
+        .map_err(|e| format!("{preview}: {e}"))?;
+    }
+
+    let planes = if options.rle {
+        Planes {
+            black: rle::encode(&planes.black),
+            red: rle::encode(&planes.red),
+        }
+    } else {
+        planes
+    };
+
+    let name = options.name.clone().unwrap_or_else(|| {
+        const_name(
+            Path::new(&path)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("image"),
+        )
+    });
+    match options.output.as_deref().map(Path::new) {
+        Some(output) if output.extension().is_some_and(|ext| ext == "bin") => {
+            write_planes(&options, output, &planes)
+        }
+        Some(output) => std::fs::write(output, render(&options, &path, &name, &planes))
+            .map_err(|e| format!("{}: {e}", output.display())),
+        None => std::io::stdout()
+            .lock()
+            .write_all(render(&options, &path, &name, &planes).as_bytes())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            let _ = writeln!(std::io::stderr(), "{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Convert vendor waveforms into Rust LUT constants
+//!
+//! ```text
+//! ssd1677-lut [OPTIONS] <FILE>
+//!
+//! Options:
+//!   --array <NAME>   Only convert the C array with this name
+//!   --otp            Treat FILE as a binary OTP dump instead of C source
+//!   --offset <N>     Skip N bytes before each LUT (C arrays) or the first LUT (OTP)
+//!   --count <N>      Number of consecutive LUTs to read from an OTP dump (default 1)
+//!   --name <NAME>    Constant name (default: derived from the array or file name)
+//!   --typed          Annotate each constant with its decoded waveform
+//! ```
+//!
+//! `--typed` annotates 112-byte LUTs and 105-byte short LUTs (after `--offset`);
+//! arrays of any other length are emitted verbatim.
+
+use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
+
+use ssd1677::import::{
+    CArray, const_name, find_array, parse_c_arrays, parse_otp_dump, to_rust_const,
+};
+use ssd1677::lut::{LUT_SHORT_SIZE, LUT_SIZE};
+use ssd1677::waveform::{LutVoltages, Waveform};
+
+const USAGE: &str = "usage: ssd1677-lut [--array NAME] [--otp] [--offset N] [--count N] [--name NAME] [--typed] <FILE>";
+
+/// Rendered constants, one per converted LUT
+type Items = Vec<String>;
+
+#[derive(Default)]
+struct Options {
+    path: Option<String>,
+    array: Option<String>,
+    otp: bool,
+    offset: usize,
+    count: usize,
+    name: Option<String>,
+    typed: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        count: 1,
+        ..Options::default()
+    };
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--array" => options.array = Some(value(&mut args, &arg)?),
+            "--otp" => options.otp = true,
+            "--offset" => options.offset = number(&value(&mut args, &arg)?)?,
+            "--count" => options.count = number(&value(&mut args, &arg)?)?,
+            "--name" => options.name = Some(value(&mut args, &arg)?),
+            "--typed" => options.typed = true,
+            "-h" | "--help" => return Err(String::from(USAGE)),
+            _ if arg.starts_with("--") => return Err(format!("unknown option {arg}\n{USAGE}")),
+            _ => options.path = Some(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("{flag} requires a value"))
+}
+
+fn number(text: &str) -> Result<usize, String> {
+    let parsed = text
+        .strip_prefix("0x")
+        .map_or_else(|| text.parse(), |hex| usize::from_str_radix(hex, 16));
+    parsed.map_err(|_| format!("invalid number {text}"))
+}
+
+fn render(name: &str, bytes: &[u8], typed: bool) -> String {
+    let waveform = if typed { decode(bytes) } else { None };
+    to_rust_const(name, bytes, waveform.as_ref())
+}
+
+/// Decode a full LUT, or a short LUT whose voltages are programmed separately
+fn decode(bytes: &[u8]) -> Option<Waveform> {
+    if let Ok(lut) = <&[u8; LUT_SIZE]>::try_from(bytes) {
+        return Some(Waveform::from_bytes(lut));
+    }
+    <&[u8; LUT_SHORT_SIZE]>::try_from(bytes)
+        .ok()
+        .map(|lut| Waveform::from_short_bytes(lut, LutVoltages::default()))
+}
+
+fn convert_c(options: &Options, path: &str) -> Result<Items, String> {
+    let source = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let arrays = parse_c_arrays(&source).map_err(|e| format!("{path}: {e}"))?;
+    let selected: Vec<&CArray> = match &options.array {
+        Some(name) => vec![find_array(&arrays, name).map_err(|e| e.to_string())?],
+        None => arrays.iter().collect(),
+    };
+
+    Ok(selected
+        .iter()
+        .map(|array| {
+            let name = match (&options.name, selected.len()) {
+                (Some(name), 1) => name.clone(),
+                _ => const_name(&array.name),
+            };
+            let bytes = array.bytes.get(options.offset..).unwrap_or_default();
+            render(&name, bytes, options.typed)
+        })
+        .collect())
+}
+
+fn convert_otp(options: &Options, path: &str) -> Result<Items, String> {
+    let dump = std::fs::read(path).map_err(|e| format!("{path}: {e}"))?;
+    let luts =
+        parse_otp_dump(&dump, options.offset, options.count).map_err(|e| format!("{path}: {e}"))?;
+    let base = options.name.clone().unwrap_or_else(|| {
+        const_name(
+            Path::new(path)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("otp"),
+        )
+    });
+
+    Ok(luts
+        .iter()
+        .enumerate()
+        .map(|(index, lut)| {
+            let name = if luts.len() == 1 {
+                base.clone()
+            } else {
+                format!("{base}_{index}")
+            };
+            render(&name, lut, options.typed)
+        })
+        .collect())
+}
+
+fn run() -> Result<(), String> {
+    let options = parse_args()?;
+    let path = options.path.clone().ok_or_else(|| String::from(USAGE))?;
+    let items = if options.otp {
+        convert_otp(&options, &path)?
+    } else {
+        convert_c(&options, &path)?
+    };
+
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "// Generated by ssd1677-lut from {path}").map_err(|e| e.to_string())?;
+    for item in items {
+        writeln!(out).map_err(|e| e.to_string())?;
+        out.write_all(item.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            let _ = writeln!(std::io::stderr(), "{message}");
+            ExitCode::FAILURE
+        }
+    }
+}
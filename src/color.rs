@@ -30,6 +30,16 @@
 //! let red_bw = Color::Red.bw_byte();       // 0xFF
 //! let red_red = Color::Red.red_byte();     // 0xFF
 //! ```
+//!
+//! ## Conversions
+//!
+//! With the `graphics` feature, [`Color`] converts from and to
+//! embedded-graphics' `BinaryColor`, `Rgb565` and `Rgb888`, so drawables
+//! typed on those colors can target a `GraphicDisplay` through
+//! `color_converted()`. RGB colors are mapped by [`Color::from_rgb`].
+
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::pixelcolor::{BinaryColor, Rgb565, Rgb888, RgbColor};
 
 /// Colors supported by SSD1677 (tri-color displays)
 ///
@@ -49,6 +59,24 @@ impl embedded_graphics_core::prelude::PixelColor for Color {
     type Raw = embedded_graphics_core::pixelcolor::raw::RawU8;
 }
 
+/// Amount by which red must exceed green and blue for [`Color::from_rgb`]
+/// to pick red
+const RED_HUE_MARGIN: u8 = 96;
+
+/// Luminance below which [`Color::from_rgb`] picks black
+pub(crate) const BLACK_LUMINANCE: u8 = 128;
+
+/// Rec. 601 luminance of an RGB color
+pub(crate) const fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+/// Amount by which the red channel exceeds both green and blue
+pub(crate) const fn red_excess(r: u8, g: u8, b: u8) -> u8 {
+    let other = if g > b { g } else { b };
+    r.saturating_sub(other)
+}
+
 impl Color {
     /// Get the byte value for black/white buffer
     ///
@@ -97,4 +125,113 @@ impl Color {
             Self::Red => 0xFF,
         }
     }
+
+    /// Compose the color of a pixel from its BW and RED bits
+    ///
+    /// A set RED bit wins regardless of the BW bit, matching how the panel
+    /// renders it.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ssd1677::Color;
+    ///
+    /// assert_eq!(Color::from_bits(false, false), Color::Black);
+    /// assert_eq!(Color::from_bits(true, false), Color::White);
+    /// assert_eq!(Color::from_bits(true, true), Color::Red);
+    /// ```
+    pub const fn from_bits(bw: bool, red: bool) -> Self {
+        match (bw, red) {
+            (_, true) => Self::Red,
+            (true, false) => Self::White,
+            (false, false) => Self::Black,
+        }
+    }
+
+    /// Closest panel color to an RGB color
+    ///
+    /// Colors whose red channel exceeds both green and blue by at least 96
+    /// are red; everything else is black or white depending on whether its
+    /// (Rec. 601) luminance is below or above half scale. This is a plain
+    /// threshold; the `convert` module (`std` feature) dithers photos.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use ssd1677::Color;
+    ///
+    /// assert_eq!(Color::from_rgb(200, 30, 40), Color::Red);
+    /// assert_eq!(Color::from_rgb(90, 90, 90), Color::Black);
+    /// assert_eq!(Color::from_rgb(255, 200, 200), Color::White);
+    /// ```
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        if red_excess(r, g, b) >= RED_HUE_MARGIN {
+            return Self::Red;
+        }
+        if luminance(r, g, b) < BLACK_LUMINANCE {
+            Self::Black
+        } else {
+            Self::White
+        }
+    }
+}
+
+/// `On` is ink (black), `Off` is paper (white)
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for Color {
+    fn from(color: BinaryColor) -> Self {
+        match color {
+            BinaryColor::On => Self::Black,
+            BinaryColor::Off => Self::White,
+        }
+    }
+}
+
+/// Black and red are ink (`On`), white is paper (`Off`)
+#[cfg(feature = "graphics")]
+impl From<Color> for BinaryColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black | Color::Red => Self::On,
+            Color::White => Self::Off,
+        }
+    }
+}
+
+/// See [`Color::from_rgb`]
+#[cfg(feature = "graphics")]
+impl From<Rgb888> for Color {
+    fn from(color: Rgb888) -> Self {
+        Self::from_rgb(color.r(), color.g(), color.b())
+    }
+}
+
+/// See [`Color::from_rgb`]; channels are scaled to 8 bits first
+#[cfg(feature = "graphics")]
+impl From<Rgb565> for Color {
+    fn from(color: Rgb565) -> Self {
+        Self::from(Rgb888::from(color))
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Color> for Rgb888 {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => Self::BLACK,
+            Color::White => Self::WHITE,
+            Color::Red => Self::RED,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<Color> for Rgb565 {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => Self::BLACK,
+            Color::White => Self::WHITE,
+            Color::Red => Self::RED,
+        }
+    }
 }
@@ -70,6 +70,19 @@ pub const BOOSTER_SOFT_START: u8 = 0x0C;
 /// Requires 3 bytes: [rows-1 (LSB), rows-1 (MSB), scanning mode]
 pub const DRIVER_OUTPUT_CONTROL: u8 = 0x01;
 
+/// Gate scanning bit TB of DRIVER_OUTPUT_CONTROL
+///
+/// Scans the gates from the last to the first, flipping the image along the
+/// panel's Y axis.
+pub const GATE_SCAN_REVERSE: u8 = 0x01;
+
+/// Gate scan start position command (0x0F)
+///
+/// Selects the gate that RAM row 0 is driven on; the following rows continue
+/// from there and wrap around to gate 0, shifting the image vertically.
+/// Requires 2 bytes: [position (LSB), position (MSB)]
+pub const GATE_SCAN_START: u8 = 0x0F;
+
 /// Border waveform control command (0x3C)
 ///
 /// Controls the border color and transition behavior.
@@ -155,17 +168,43 @@ pub const DISPLAY_UPDATE_CTRL1: u8 = 0x21;
 ///
 /// Controls the display update sequence (power on/off, load LUT, etc).
 /// Values are panel-specific; prefer configuring them via [`crate::Config`].
-/// Requires 1 byte with bit flags:
-/// - 0x01: Enable clock
-/// - 0x02: Enable analog
-/// - 0x04: Load temperature value
-/// - 0x08: Load LUT
-/// - 0x10: Initial display (disable bypass)
-/// - 0x20: Pattern display (refresh)
-/// - 0x40: Disable analog
-/// - 0x80: Disable clock
+/// Requires 1 byte with bit flags, executed from MSB to LSB:
+/// - 0x80: Enable clock
+/// - 0x40: Enable analog
+/// - 0x20: Load temperature value
+/// - 0x10: Load LUT from OTP
+/// - 0x08: Use Display Mode 2 (otherwise Display Mode 1)
+/// - 0x04: Display (run the waveform)
+/// - 0x02: Disable analog
+/// - 0x01: Disable clock
 pub const DISPLAY_UPDATE_CTRL2: u8 = 0x22;
 
+/// DISPLAY_UPDATE_CTRL2 bit: enable clock
+pub const CTRL2_ENABLE_CLOCK: u8 = 0x80;
+
+/// DISPLAY_UPDATE_CTRL2 bit: enable analog
+pub const CTRL2_ENABLE_ANALOG: u8 = 0x40;
+
+/// DISPLAY_UPDATE_CTRL2 bit: load temperature value
+pub const CTRL2_LOAD_TEMPERATURE: u8 = 0x20;
+
+/// DISPLAY_UPDATE_CTRL2 bit: load LUT from OTP
+///
+/// Replaces whatever was last written with [`WRITE_LUT`].
+pub const CTRL2_LOAD_LUT: u8 = 0x10;
+
+/// DISPLAY_UPDATE_CTRL2 bit: select Display Mode 2 waveform
+pub const CTRL2_DISPLAY_MODE_2: u8 = 0x08;
+
+/// DISPLAY_UPDATE_CTRL2 bit: display (run the waveform)
+pub const CTRL2_DISPLAY: u8 = 0x04;
+
+/// DISPLAY_UPDATE_CTRL2 bit: disable analog
+pub const CTRL2_DISABLE_ANALOG: u8 = 0x02;
+
+/// DISPLAY_UPDATE_CTRL2 bit: disable clock
+pub const CTRL2_DISABLE_CLOCK: u8 = 0x01;
+
 /// Master activation command (0x20)
 ///
 /// Triggers the display update sequence. BUSY goes high during update.
@@ -202,6 +241,12 @@ pub const GATE_VOLTAGE: u8 = 0x03;
 /// Requires 3 bytes.
 pub const SOURCE_VOLTAGE: u8 = 0x04;
 
+/// Power-on reset value for [`GATE_VOLTAGE`] (VGH = 20 V)
+pub const GATE_VOLTAGE_DEFAULT: u8 = 0x00;
+
+/// Power-on reset values for [`SOURCE_VOLTAGE`] (VSH1 = 15 V, VSH2 = 5 V, VSL = -15 V)
+pub const SOURCE_VOLTAGE_DEFAULT: [u8; 3] = [0x41, 0xA8, 0x32];
+
 /// Write VCOM command (0x2C)
 ///
 /// Sets the VCOM voltage for common electrode.
@@ -221,3 +266,45 @@ pub const WRITE_TEMP: u8 = 0x1A;
 /// Enters ultra-low power mode. Only soft reset can wake.
 /// Requires 1 byte: 0x01 = enter deep sleep
 pub const DEEP_SLEEP: u8 = 0x10;
+
+/// Name of a command byte as used in this module
+///
+/// Returns `None` for commands the driver does not define. Useful when
+/// logging or decoding a captured command stream.
+///
+/// ```
+/// use ssd1677::command::{command_name, SET_RAM_Y_RANGE};
+///
+/// assert_eq!(command_name(SET_RAM_Y_RANGE), Some("SET_RAM_Y_RANGE"));
+/// assert_eq!(command_name(0xFF), None);
+/// ```
+pub fn command_name(command: u8) -> Option<&'static str> {
+    let name = match command {
+        SOFT_RESET => "SOFT_RESET",
+        BOOSTER_SOFT_START => "BOOSTER_SOFT_START",
+        DRIVER_OUTPUT_CONTROL => "DRIVER_OUTPUT_CONTROL",
+        GATE_SCAN_START => "GATE_SCAN_START",
+        BORDER_WAVEFORM => "BORDER_WAVEFORM",
+        TEMP_SENSOR_CONTROL => "TEMP_SENSOR_CONTROL",
+        DATA_ENTRY_MODE => "DATA_ENTRY_MODE",
+        SET_RAM_X_RANGE => "SET_RAM_X_RANGE",
+        SET_RAM_Y_RANGE => "SET_RAM_Y_RANGE",
+        SET_RAM_X_COUNTER => "SET_RAM_X_COUNTER",
+        SET_RAM_Y_COUNTER => "SET_RAM_Y_COUNTER",
+        WRITE_RAM_BW => "WRITE_RAM_BW",
+        WRITE_RAM_RED => "WRITE_RAM_RED",
+        AUTO_WRITE_BW_RAM => "AUTO_WRITE_BW_RAM",
+        AUTO_WRITE_RED_RAM => "AUTO_WRITE_RED_RAM",
+        DISPLAY_UPDATE_CTRL1 => "DISPLAY_UPDATE_CTRL1",
+        DISPLAY_UPDATE_CTRL2 => "DISPLAY_UPDATE_CTRL2",
+        MASTER_ACTIVATION => "MASTER_ACTIVATION",
+        WRITE_LUT => "WRITE_LUT",
+        GATE_VOLTAGE => "GATE_VOLTAGE",
+        SOURCE_VOLTAGE => "SOURCE_VOLTAGE",
+        WRITE_VCOM => "WRITE_VCOM",
+        WRITE_TEMP => "WRITE_TEMP",
+        DEEP_SLEEP => "DEEP_SLEEP",
+        _ => return None,
+    };
+    Some(name)
+}
@@ -1,6 +1,8 @@
 //! Display configuration types and builder
 
+use crate::command::GATE_SCAN_REVERSE;
 pub use crate::error::{BuilderError, MAX_GATE_OUTPUTS, MAX_SOURCE_OUTPUTS};
+use crate::lut::LutSet;
 
 /// Display dimensions
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -50,6 +52,57 @@ pub enum Rotation {
     Rotate270,
 }
 
+/// Mirroring of the drawing, applied before [`Rotation`]
+///
+/// Axes are those of the drawing as seen by the viewer, so
+/// [`Mirror::Horizontal`] swaps left and right whatever the rotation.
+/// Together with the four rotations this covers all eight orientations;
+/// [`Mirror::Both`] is the same as an extra 180° rotation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Mirror {
+    /// No mirroring
+    #[default]
+    None,
+    /// Swap left and right
+    Horizontal,
+    /// Swap top and bottom
+    Vertical,
+    /// Swap both
+    Both,
+}
+
+impl Mirror {
+    /// Mirror with the given axes flipped
+    pub const fn from_axes(horizontal: bool, vertical: bool) -> Self {
+        match (horizontal, vertical) {
+            (false, false) => Self::None,
+            (true, false) => Self::Horizontal,
+            (false, true) => Self::Vertical,
+            (true, true) => Self::Both,
+        }
+    }
+
+    /// Whether left and right are swapped
+    pub const fn horizontal(self) -> bool {
+        matches!(self, Self::Horizontal | Self::Both)
+    }
+
+    /// Whether top and bottom are swapped
+    pub const fn vertical(self) -> bool {
+        matches!(self, Self::Vertical | Self::Both)
+    }
+
+    /// Apply `other` after `self`
+    ///
+    /// Flipping an axis twice cancels out.
+    pub const fn then(self, other: Self) -> Self {
+        Self::from_axes(
+            self.horizontal() != other.horizontal(),
+            self.vertical() != other.vertical(),
+        )
+    }
+}
+
 /// RAM X address unit
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub enum RamXAddressing {
@@ -60,6 +113,22 @@ pub enum RamXAddressing {
     Bytes,
 }
 
+/// Validation applied to caller-supplied LUTs before they are sent
+///
+/// Used by [`Display::update_with_custom_lut`](crate::display::Display::update_with_custom_lut)
+/// and [`Display::update_region_with_custom_lut`](crate::display::Display::update_region_with_custom_lut).
+/// See [`Waveform::analyze`](crate::waveform::Waveform::analyze) for the checks performed.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LutCheck {
+    /// Send LUTs unchecked
+    #[default]
+    Off,
+    /// Log issues as warnings but send the LUT anyway
+    Warn,
+    /// Log warnings and refuse LUTs with errors
+    Reject,
+}
+
 /// Display configuration
 ///
 /// This struct holds all configurable parameters for the SSD1677 controller.
@@ -70,6 +139,11 @@ pub struct Config {
     pub dimensions: Dimensions,
     /// Display rotation
     pub rotation: Rotation,
+    /// Mirroring of the drawing, applied before rotation
+    pub mirror: Mirror,
+    /// Let the controller perform the part of `mirror` it can (see
+    /// [`Builder::hardware_mirror`])
+    pub hardware_mirror: bool,
     /// Booster soft-start settings (5 bytes for command 0x0C)
     pub booster_soft_start: [u8; 5],
     /// Gate scanning direction byte
@@ -100,6 +174,12 @@ pub struct Config {
     pub clear_red_value: u8,
     /// Temperature sensor control
     pub temp_sensor_control: u8,
+    /// Validation applied to custom LUTs
+    pub lut_check: LutCheck,
+    /// Temperature-indexed LUTs for partial refresh (falls back to `LUT_PARTIAL`)
+    pub partial_lut_set: Option<LutSet>,
+    /// Temperature-indexed LUTs for fast refresh (falls back to `LUT_FAST`)
+    pub fast_lut_set: Option<LutSet>,
 }
 
 impl Config {
@@ -113,6 +193,46 @@ impl Config {
             },
         }
     }
+
+    /// Drawing-axis mirror equivalent to flipping the panel's rows
+    fn gate_axis_mirror(&self) -> Mirror {
+        match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => Mirror::Vertical,
+            Rotation::Rotate90 | Rotation::Rotate270 => Mirror::Horizontal,
+        }
+    }
+
+    /// Whether the controller reverses the gate scanning order for `mirror`
+    pub fn gate_scan_reversed(&self) -> bool {
+        let flips_gate_axis = match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => self.mirror.vertical(),
+            Rotation::Rotate90 | Rotation::Rotate270 => self.mirror.horizontal(),
+        };
+        self.hardware_mirror && flips_gate_axis
+    }
+
+    /// Mirroring left for software after the controller's share
+    ///
+    /// This is what buffers must be drawn with; it equals `mirror` unless
+    /// [`Config::gate_scan_reversed`].
+    pub fn software_mirror(&self) -> Mirror {
+        if self.gate_scan_reversed() {
+            self.mirror.then(self.gate_axis_mirror())
+        } else {
+            self.mirror
+        }
+    }
+
+    /// Gate scanning byte sent with DRIVER_OUTPUT_CONTROL
+    ///
+    /// `gate_scanning`, with the TB bit toggled when the controller mirrors.
+    pub fn driver_gate_scanning(&self) -> u8 {
+        if self.gate_scan_reversed() {
+            self.gate_scanning ^ GATE_SCAN_REVERSE
+        } else {
+            self.gate_scanning
+        }
+    }
 }
 
 /// Builder for constructing display configuration
@@ -138,6 +258,10 @@ pub struct Builder {
     dimensions: Option<Dimensions>,
     /// Display rotation
     rotation: Rotation,
+    /// Mirroring of the drawing
+    mirror: Mirror,
+    /// Mirror along the gate axis in hardware
+    hardware_mirror: bool,
     /// Booster soft-start settings (5 bytes for command 0x0C)
     booster_soft_start: [u8; 5],
     /// Gate scanning direction byte
@@ -168,6 +292,12 @@ pub struct Builder {
     clear_red_value: u8,
     /// Temperature sensor control
     temp_sensor_control: u8,
+    /// Validation applied to custom LUTs
+    lut_check: LutCheck,
+    /// Temperature-indexed LUTs for partial refresh
+    partial_lut_set: Option<LutSet>,
+    /// Temperature-indexed LUTs for fast refresh
+    fast_lut_set: Option<LutSet>,
 }
 
 impl Default for Builder {
@@ -175,6 +305,8 @@ impl Default for Builder {
         Self {
             dimensions: None,
             rotation: Rotation::Rotate0,
+            mirror: Mirror::None,
+            hardware_mirror: false,
             // Default booster soft-start sequence (panel-specific, override as needed)
             booster_soft_start: [0xAE, 0xC7, 0xC3, 0xC0, 0x40],
             // Default gate scanning (panel-specific, override as needed)
@@ -201,6 +333,11 @@ impl Default for Builder {
             clear_red_value: 0x00,
             // Default: internal temperature sensor
             temp_sensor_control: 0x80,
+            // Default: custom LUTs are sent as-is
+            lut_check: LutCheck::Off,
+            // Default: built-in LUTs regardless of temperature
+            partial_lut_set: None,
+            fast_lut_set: None,
         }
     }
 }
@@ -223,6 +360,30 @@ impl Builder {
         self
     }
 
+    /// Mirror the drawing horizontally and/or vertically
+    ///
+    /// Applied in drawing coordinates before the rotation, so it works the
+    /// same for every [`Rotation`].
+    pub fn mirror(mut self, mirror: Mirror) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Have the controller mirror the image where it can
+    ///
+    /// The SSD1677 can reverse the gate scanning order, which flips the
+    /// panel's Y axis (rows) without any work on the MCU. When enabled and
+    /// `mirror` includes a flip along that axis (vertical for
+    /// [`Rotation::Rotate0`]/[`Rotation::Rotate180`], horizontal for
+    /// [`Rotation::Rotate90`]/[`Rotation::Rotate270`]), the TB bit of the
+    /// gate scanning byte is toggled and only the rest of the mirroring is
+    /// done in software. The controller has no equivalent for the source
+    /// (X) axis, so that flip always stays in software.
+    pub fn hardware_mirror(mut self, enabled: bool) -> Self {
+        self.hardware_mirror = enabled;
+        self
+    }
+
     /// Set booster soft-start parameters
     pub fn booster_soft_start(mut self, values: [u8; 5]) -> Self {
         self.booster_soft_start = values;
@@ -315,6 +476,24 @@ impl Builder {
         self
     }
 
+    /// Set how custom LUTs are validated before use
+    pub fn lut_check(mut self, value: LutCheck) -> Self {
+        self.lut_check = value;
+        self
+    }
+
+    /// Set temperature-indexed LUTs for partial refresh
+    pub fn partial_lut_set(mut self, set: LutSet) -> Self {
+        self.partial_lut_set = Some(set);
+        self
+    }
+
+    /// Set temperature-indexed LUTs for fast refresh
+    pub fn fast_lut_set(mut self, set: LutSet) -> Self {
+        self.fast_lut_set = Some(set);
+        self
+    }
+
     /// Build the configuration
     ///
     /// # Errors
@@ -324,6 +503,8 @@ impl Builder {
         Ok(Config {
             dimensions: self.dimensions.ok_or(BuilderError::MissingDimensions)?,
             rotation: self.rotation,
+            mirror: self.mirror,
+            hardware_mirror: self.hardware_mirror,
             booster_soft_start: self.booster_soft_start,
             gate_scanning: self.gate_scanning,
             border_waveform: self.border_waveform,
@@ -339,6 +520,9 @@ impl Builder {
             clear_bw_value: self.clear_bw_value,
             clear_red_value: self.clear_red_value,
             temp_sensor_control: self.temp_sensor_control,
+            lut_check: self.lut_check,
+            partial_lut_set: self.partial_lut_set,
+            fast_lut_set: self.fast_lut_set,
         })
     }
 }
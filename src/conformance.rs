@@ -0,0 +1,696 @@
+//! Protocol conformance harness (requires `alloc` feature)
+//!
+//! Drives a [`Display`] through a [`Recorder`] wrapped around the software
+//! [`Emulator`] and checks that each public operation emits the command
+//! sequence the SSD1677 datasheet requires for the given [`Config`]. Expected
+//! sequences are derived from the configuration independently of the driver,
+//! so forks that change the driver or ship their own `Config` presets can
+//! run the same checks:
+//!
+//! ```
+//! use ssd1677::conformance::Harness;
+//! use ssd1677::{Builder, Dimensions};
+//!
+//! let preset = Builder::new()
+//!     .dimensions(Dimensions::new(480, 800).unwrap())
+//!     .data_entry_mode(0x01)
+//!     .ram_y_inverted(true)
+//!     .build()
+//!     .unwrap();
+//! if let Err(failure) = Harness::new(preset).run_all() {
+//!     panic!("{}", failure);
+//! }
+//! ```
+//!
+//! ## Scenarios
+//!
+//! - [`Harness::check_reset`]: hardware reset, soft reset and initialization
+//! - [`Harness::check_refresh_mode`]: full-screen update in each
+//!   [`RefreshMode`], from power-off and again with the display on
+//! - [`Harness::check_region_update`]: windowed update of a [`Region`]
+//! - [`Harness::check_deep_sleep`]: deep sleep with and without power-down
+//!
+//! [`data_entry_matrix`] expands a preset into every `data_entry_mode` and
+//! `ram_y_inverted` combination for RAM window checks.
+//!
+//! [`Display`]: crate::display::Display
+//! [`Recorder`]: crate::trace::Recorder
+//! [`Emulator`]: crate::emulator::Emulator
+//! [`Config`]: crate::config::Config
+//! [`Harness::check_reset`]: crate::conformance::Harness::check_reset
+//! [`Harness::check_refresh_mode`]: crate::conformance::Harness::check_refresh_mode
+//! [`Harness::check_region_update`]: crate::conformance::Harness::check_region_update
+//! [`Harness::check_deep_sleep`]: crate::conformance::Harness::check_deep_sleep
+//! [`RefreshMode`]: crate::display::RefreshMode
+//! [`Region`]: crate::display::Region
+//! [`data_entry_matrix`]: crate::conformance::data_entry_matrix
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use embedded_hal::delay::DelayNs;
+
+use crate::command::*;
+use crate::config::{Config, RamXAddressing};
+use crate::display::{DeepSleepMode, Display, OtpMode, RefreshMode, Region, UpdateRegion};
+use crate::emulator::Emulator;
+use crate::lut::{LUT_FAST, LUT_PARTIAL};
+use crate::trace::{Recorder, Trace, Transaction, TransactionKind, describe};
+
+/// Display type driven by the harness
+pub type HarnessDisplay = Display<Recorder<Emulator>>;
+
+type ConformanceResult = core::result::Result<(), ConformanceError>;
+type Expected = Vec<Expect>;
+
+/// Every refresh mode checked by [`Harness::run_all`]
+pub const REFRESH_MODES: [RefreshMode; 5] = [
+    RefreshMode::Full,
+    RefreshMode::Partial,
+    RefreshMode::Fast,
+    RefreshMode::Otp(OtpMode::Mode1),
+    RefreshMode::Otp(OtpMode::Mode2),
+];
+
+/// Every deep sleep mode checked by [`Harness::run_all`]
+pub const DEEP_SLEEP_MODES: [DeepSleepMode; 3] = [
+    DeepSleepMode::Normal,
+    DeepSleepMode::PreserveRam,
+    DeepSleepMode::PreserveRamAndAnalog,
+];
+
+/// One expected step of a command sequence
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expect {
+    /// Hardware reset
+    Reset,
+    /// Command with any (or no) data
+    Command(u8),
+    /// Command followed by exactly this data
+    CommandData(u8, Vec<u8>),
+    /// Busy wait that completed
+    BusyWait,
+}
+
+impl Expect {
+    /// Whether a recorded transaction satisfies this step
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        match (self, &transaction.kind) {
+            (Self::Reset, TransactionKind::Reset) => true,
+            (Self::BusyWait, TransactionKind::BusyWait { completed, .. }) => *completed,
+            (Self::Command(expected), TransactionKind::Command { command, .. }) => {
+                *command == Some(*expected)
+            }
+            (Self::CommandData(expected, bytes), TransactionKind::Command { command, data }) => {
+                *command == Some(*expected) && data == bytes
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Expect {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = |command: u8| {
+            command_name(command).map_or_else(|| format!("CMD 0x{:02X}", command), String::from)
+        };
+        match self {
+            Self::Reset => write!(f, "RESET"),
+            Self::BusyWait => write!(f, "BUSY"),
+            Self::Command(command) => write!(f, "{} <any data>", name(*command)),
+            Self::CommandData(command, data) => {
+                write!(f, "{} {}", name(*command), describe(*command, data))
+            }
+        }
+    }
+}
+
+/// A conformance check that did not pass
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConformanceError {
+    /// The driver returned an error
+    Driver {
+        /// Scenario being run
+        scenario: String,
+        /// Debug rendering of the driver error
+        error: String,
+    },
+    /// The recorded sequence differs from the expected one
+    Mismatch {
+        /// Scenario being run
+        scenario: String,
+        /// Index of the first differing step
+        index: usize,
+        /// Expected step, or `None` if the driver sent extra steps
+        expected: Option<Expect>,
+        /// Recorded step, or `None` if the driver stopped early
+        actual: Option<Transaction>,
+    },
+    /// The controller ended in the wrong state
+    State {
+        /// Scenario being run
+        scenario: String,
+        /// What was wrong
+        problem: &'static str,
+    },
+}
+
+impl fmt::Display for ConformanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Driver { scenario, error } => {
+                write!(f, "{}: driver error {}", scenario, error)
+            }
+            Self::Mismatch {
+                scenario,
+                index,
+                expected,
+                actual,
+            } => {
+                write!(f, "{}: step {}: expected ", scenario, index)?;
+                match expected {
+                    Some(expected) => write!(f, "{}", expected)?,
+                    None => write!(f, "end of sequence")?,
+                }
+                match actual {
+                    Some(actual) => write!(f, ", got {}", actual),
+                    None => write!(f, ", got end of sequence"),
+                }
+            }
+            Self::State { scenario, problem } => write!(f, "{}: {}", scenario, problem),
+        }
+    }
+}
+
+impl core::error::Error for ConformanceError {}
+
+/// Check a recorded trace against an expected sequence, step by step
+///
+/// Data chunking and timestamps are ignored (see [`Trace::transactions`]).
+pub fn assert_sequence(scenario: &str, trace: &Trace, expected: &[Expect]) -> ConformanceResult {
+    let actual = trace.transactions();
+    let steps = actual.len().max(expected.len());
+    for index in 0..steps {
+        let (want, got) = (expected.get(index), actual.get(index));
+        if let (Some(want), Some(got)) = (want, got) {
+            if want.matches(got) {
+                continue;
+            }
+        }
+        return Err(ConformanceError::Mismatch {
+            scenario: String::from(scenario),
+            index,
+            expected: want.cloned(),
+            actual: got.cloned(),
+        });
+    }
+    Ok(())
+}
+
+/// Every `data_entry_mode` (0x00..=0x07) and `ram_y_inverted` combination of a preset
+pub fn data_entry_matrix(base: &Config) -> impl Iterator<Item = Config> + '_ {
+    (0u8..8).flat_map(move |mode| {
+        [false, true].into_iter().map(move |inverted| {
+            let mut config = base.clone();
+            config.data_entry_mode = mode;
+            config.ram_y_inverted = inverted;
+            config
+        })
+    })
+}
+
+struct NoDelay;
+
+impl DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Conformance checks for one [`Config`]
+#[derive(Clone, Debug)]
+pub struct Harness {
+    config: Config,
+}
+
+impl Harness {
+    /// Create a harness for a configuration
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// The configuration under test
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Create a display driving an emulator through a recorder
+    pub fn display(&self) -> HarnessDisplay {
+        let emulator = Emulator::from_config(&self.config);
+        Display::new(Recorder::new(emulator), self.config.clone())
+    }
+
+    /// Run every scenario, stopping at the first failure
+    ///
+    /// Region updates are checked for a region away from the panel edges
+    /// (see [`Harness::default_region`]) in every refresh mode.
+    pub fn run_all(&self) -> ConformanceResult {
+        self.check_reset()?;
+        for mode in REFRESH_MODES {
+            self.check_refresh_mode(mode)?;
+            self.check_region_update(self.default_region(), mode)?;
+        }
+        self.check_tri_color_update()?;
+        for mode in DEEP_SLEEP_MODES {
+            self.check_deep_sleep(mode)?;
+        }
+        Ok(())
+    }
+
+    /// A byte-aligned region near the middle of the panel
+    pub fn default_region(&self) -> Region {
+        let dims = self.config.dimensions;
+        let x = (dims.cols / 2) / 8 * 8;
+        let x = x.min(dims.cols - 8);
+        let y = dims.rows / 2;
+        let h = (dims.rows - y).min(4);
+        Region::new(x, y, 8, h)
+    }
+
+    /// Reset and initialization
+    pub fn check_reset(&self) -> ConformanceResult {
+        const SCENARIO: &str = "reset";
+        let mut display = self.display();
+        run(SCENARIO, display.reset(&mut NoDelay))?;
+        assert_sequence(
+            SCENARIO,
+            display.interface().trace(),
+            &self.expected_reset(),
+        )
+    }
+
+    /// Full-screen BW update in `mode`, first from power-off, then with the display on
+    pub fn check_refresh_mode(&self, mode: RefreshMode) -> ConformanceResult {
+        let scenario = format!("update {:?}", mode);
+        let dims = self.config.dimensions;
+        let full = Region::new(0, 0, dims.cols, dims.rows);
+        let black = pattern(dims.buffer_size());
+
+        let mut display = self.reset_display(&scenario)?;
+        run(
+            &scenario,
+            display.update_with_mode(&black, &[], mode, &mut NoDelay),
+        )?;
+        run(
+            &scenario,
+            display.update_with_mode(&black, &[], mode, &mut NoDelay),
+        )?;
+
+        let mut expected = self.expected_update(mode, full, &black, &[], false);
+        expected.extend(self.expected_update(mode, full, &black, &[], true));
+        assert_sequence(&scenario, display.interface().trace(), &expected)
+    }
+
+    /// Windowed BW update of `region` in `mode`
+    pub fn check_region_update(&self, region: Region, mode: RefreshMode) -> ConformanceResult {
+        let scenario = format!(
+            "region {:?} {:?} (data entry 0x{:02X}, y inverted {})",
+            region, mode, self.config.data_entry_mode, self.config.ram_y_inverted
+        );
+        let black = pattern(region.buffer_size());
+
+        let mut display = self.reset_display(&scenario)?;
+        let update = UpdateRegion {
+            region,
+            black_buffer: &black,
+            red_buffer: &[],
+            mode,
+        };
+        run(&scenario, display.update_region(update, &mut NoDelay))?;
+
+        let expected = self.expected_update(mode, region, &black, &[], false);
+        assert_sequence(&scenario, display.interface().trace(), &expected)
+    }
+
+    /// Full refresh with an explicit red plane
+    pub fn check_tri_color_update(&self) -> ConformanceResult {
+        const SCENARIO: &str = "tri-color update";
+        let dims = self.config.dimensions;
+        let full = Region::new(0, 0, dims.cols, dims.rows);
+        let black = pattern(dims.buffer_size());
+        let red = vec![0x0F; dims.buffer_size()];
+
+        let mut display = self.reset_display(SCENARIO)?;
+        run(SCENARIO, display.update(&black, &red, &mut NoDelay))?;
+
+        let expected = self.expected_update(RefreshMode::Full, full, &black, &red, false);
+        assert_sequence(SCENARIO, display.interface().trace(), &expected)
+    }
+
+    /// Deep sleep from power-off, and after an update left the display on
+    pub fn check_deep_sleep(&self, mode: DeepSleepMode) -> ConformanceResult {
+        let scenario = format!("deep sleep {:?}", mode);
+        let sleep = Expect::CommandData(DEEP_SLEEP, vec![mode as u8]);
+
+        let mut display = self.reset_display(&scenario)?;
+        run(&scenario, display.deep_sleep(&mut NoDelay, mode))?;
+        assert_sequence(
+            &scenario,
+            display.interface().trace(),
+            core::slice::from_ref(&sleep),
+        )?;
+        Self::check_asleep(&scenario, &display)?;
+
+        let mut display = self.reset_display(&scenario)?;
+        let black = pattern(self.config.dimensions.buffer_size());
+        run(&scenario, display.update(&black, &[], &mut NoDelay))?;
+        display.interface_mut().take_trace();
+        run(&scenario, display.deep_sleep(&mut NoDelay, mode))?;
+        let expected = [
+            Expect::CommandData(DISPLAY_UPDATE_CTRL1, vec![CTRL1_BYPASS_RED]),
+            Expect::CommandData(
+                DISPLAY_UPDATE_CTRL2,
+                vec![CTRL2_DISABLE_ANALOG | CTRL2_DISABLE_CLOCK],
+            ),
+            Expect::Command(MASTER_ACTIVATION),
+            Expect::BusyWait,
+            sleep,
+        ];
+        assert_sequence(&scenario, display.interface().trace(), &expected)?;
+        Self::check_asleep(&scenario, &display)
+    }
+
+    fn check_asleep(scenario: &str, display: &HarnessDisplay) -> ConformanceResult {
+        if display.interface().inner().is_asleep() {
+            Ok(())
+        } else {
+            Err(ConformanceError::State {
+                scenario: String::from(scenario),
+                problem: "controller is not in deep sleep",
+            })
+        }
+    }
+
+    /// A display that has been reset, with the reset sequence cleared from its trace
+    fn reset_display(&self, scenario: &str) -> Result<HarnessDisplay, ConformanceError> {
+        let mut display = self.display();
+        run(scenario, display.reset(&mut NoDelay))?;
+        display.interface_mut().take_trace();
+        Ok(display)
+    }
+
+    /// Expected sequence for [`Display::reset`]
+    pub fn expected_reset(&self) -> Expected {
+        let c = &self.config;
+        let mux = (c.dimensions.rows - 1).to_le_bytes();
+        vec![
+            Expect::Reset,
+            Expect::Command(SOFT_RESET),
+            Expect::BusyWait,
+            Expect::CommandData(TEMP_SENSOR_CONTROL, vec![c.temp_sensor_control]),
+            Expect::CommandData(BOOSTER_SOFT_START, c.booster_soft_start.to_vec()),
+            Expect::CommandData(
+                DRIVER_OUTPUT_CONTROL,
+                vec![mux[0], mux[1], c.driver_gate_scanning()],
+            ),
+            Expect::CommandData(BORDER_WAVEFORM, vec![c.border_waveform]),
+            Expect::CommandData(WRITE_VCOM, vec![c.vcom]),
+            Expect::CommandData(AUTO_WRITE_BW_RAM, vec![c.clear_bw_value]),
+            Expect::BusyWait,
+            Expect::CommandData(AUTO_WRITE_RED_RAM, vec![c.clear_red_value]),
+            Expect::BusyWait,
+        ]
+    }
+
+    /// Expected RAM window and counter setup for a region
+    ///
+    /// X addresses run from the region's left edge when data entry increments
+    /// X (ID0 set) and from its right edge otherwise; likewise for Y with ID1.
+    /// With `ram_y_inverted`, the region is mirrored vertically in RAM first.
+    pub fn expected_ram_area(&self, region: Region) -> Expected {
+        let c = &self.config;
+        let x_increment = c.data_entry_mode & 0x01 != 0;
+        let y_increment = c.data_entry_mode & 0x02 != 0;
+
+        let (left, right) = match c.ram_x_addressing {
+            RamXAddressing::Pixels => (region.x, region.x + region.w - 1),
+            RamXAddressing::Bytes => (region.x / 8, (region.x + region.w - 1) / 8),
+        };
+        let top = if c.ram_y_inverted {
+            c.dimensions.rows - region.y - region.h
+        } else {
+            region.y
+        };
+        let bottom = top + region.h - 1;
+
+        let (x_start, x_end) = if x_increment {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        let (y_start, y_end) = if y_increment {
+            (top, bottom)
+        } else {
+            (bottom, top)
+        };
+        vec![
+            Expect::CommandData(DATA_ENTRY_MODE, vec![c.data_entry_mode]),
+            Expect::CommandData(SET_RAM_X_RANGE, pair(x_start, x_end)),
+            Expect::CommandData(SET_RAM_Y_RANGE, pair(y_start, y_end)),
+            Expect::CommandData(SET_RAM_X_COUNTER, x_start.to_le_bytes().to_vec()),
+            Expect::CommandData(SET_RAM_Y_COUNTER, y_start.to_le_bytes().to_vec()),
+        ]
+    }
+
+    /// Display Update Control 2 value for a refresh
+    pub fn expected_ctrl2(&self, mode: RefreshMode, display_on: bool) -> u8 {
+        let c = &self.config;
+        let sequence = match mode {
+            RefreshMode::Full => c.display_update_ctrl2_full,
+            RefreshMode::Partial => c.display_update_ctrl2_partial,
+            RefreshMode::Fast => c.display_update_ctrl2_fast,
+            RefreshMode::Otp(otp) => {
+                let mode_2 = if otp == OtpMode::Mode2 {
+                    CTRL2_DISPLAY_MODE_2
+                } else {
+                    0
+                };
+                (c.display_update_ctrl2_full & !CTRL2_DISPLAY_MODE_2)
+                    | CTRL2_LOAD_TEMPERATURE
+                    | CTRL2_LOAD_LUT
+                    | mode_2
+            }
+        };
+        if display_on {
+            sequence
+        } else {
+            sequence | c.display_update_power_on
+        }
+    }
+
+    /// Expected sequence for an update of `region` with the given buffers
+    ///
+    /// `red` is the caller's red plane; an empty or all-zero plane means a
+    /// BW-only update.
+    pub fn expected_update(
+        &self,
+        mode: RefreshMode,
+        region: Region,
+        black: &[u8],
+        red: &[u8],
+        display_on: bool,
+    ) -> Expected {
+        let explicit_red = red.iter().any(|byte| *byte != 0);
+        let fast = mode == RefreshMode::Fast;
+        let mut expected = match mode {
+            RefreshMode::Partial => vec![Expect::CommandData(WRITE_LUT, LUT_PARTIAL.to_vec())],
+            RefreshMode::Fast => vec![Expect::CommandData(WRITE_LUT, LUT_FAST.to_vec())],
+            RefreshMode::Full | RefreshMode::Otp(_) => Vec::new(),
+        };
+
+        expected.extend(self.expected_ram_area(region));
+        expected.push(Expect::CommandData(WRITE_RAM_BW, black.to_vec()));
+        if explicit_red {
+            expected.push(Expect::CommandData(WRITE_RAM_RED, red.to_vec()));
+        } else if !fast {
+            // RED RAM tracks the displayed image for the next differential refresh
+            expected.push(Expect::CommandData(WRITE_RAM_RED, black.to_vec()));
+        }
+
+        let ctrl1 = if explicit_red || fast {
+            CTRL1_NORMAL
+        } else {
+            CTRL1_BYPASS_RED
+        };
+        expected.extend([
+            Expect::CommandData(DISPLAY_UPDATE_CTRL1, vec![ctrl1]),
+            Expect::CommandData(
+                DISPLAY_UPDATE_CTRL2,
+                vec![self.expected_ctrl2(mode, display_on)],
+            ),
+            Expect::Command(MASTER_ACTIVATION),
+            Expect::BusyWait,
+        ]);
+
+        if fast && !explicit_red {
+            expected.extend(self.expected_ram_area(region));
+            expected.push(Expect::CommandData(WRITE_RAM_RED, black.to_vec()));
+        }
+        expected
+    }
+}
+
+/// Map a driver result into a conformance result
+fn run<E: fmt::Debug>(scenario: &str, result: Result<(), E>) -> ConformanceResult {
+    result.map_err(|error| ConformanceError::Driver {
+        scenario: String::from(scenario),
+        error: format!("{:?}", error),
+    })
+}
+
+/// Little-endian start/end address pair
+fn pair(start: u16, end: u16) -> Vec<u8> {
+    let (start, end) = (start.to_le_bytes(), end.to_le_bytes());
+    vec![start[0], start[1], end[0], end[1]]
+}
+
+/// Distinct, non-uniform buffer contents so misrouted data is detected
+fn pattern(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8 | 0x01).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::config::{Builder, Dimensions};
+    use crate::trace::Event;
+    use alloc::string::ToString;
+
+    fn config(rows: u16, cols: u16) -> TestResult<Config> {
+        Ok(Builder::new()
+            .dimensions(Dimensions::new(rows, cols)?)
+            .build()?)
+    }
+
+    #[test]
+    fn test_default_config_conforms() -> TestResult {
+        Harness::new(config(32, 48)?).run_all()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_panel_preset_conforms() -> TestResult {
+        let preset = Builder::new()
+            .dimensions(Dimensions::new(480, 800)?)
+            .data_entry_mode(0x01)
+            .ram_y_inverted(true)
+            .clear_bw_value(0xF7)
+            .clear_red_value(0xF7)
+            .display_update_ctrl2_full(0x34)
+            .display_update_ctrl2_partial(0xD4)
+            .display_update_ctrl2_fast(0x1C)
+            .build()?;
+        Harness::new(preset).run_all()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_windows_across_data_entry_matrix() -> TestResult {
+        let mut base = config(40, 64)?;
+        let regions = [Region::new(8, 3, 16, 5), Region::new(56, 39, 8, 1)];
+        let configs: Vec<Config> = [RamXAddressing::Pixels, RamXAddressing::Bytes]
+            .into_iter()
+            .flat_map(|addressing| {
+                base.ram_x_addressing = addressing;
+                data_entry_matrix(&base).collect::<Vec<_>>()
+            })
+            .collect();
+        for harness in configs.into_iter().map(Harness::new) {
+            for (region, mode) in regions
+                .iter()
+                .flat_map(|region| REFRESH_MODES.map(|mode| (*region, mode)))
+            {
+                harness.check_region_update(region, mode)?;
+            }
+        }
+        assert_eq!(data_entry_matrix(&base).count(), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn test_expected_ram_area_values() -> TestResult {
+        let mut c = config(480, 800)?;
+        c.data_entry_mode = 0x01;
+        c.ram_y_inverted = true;
+        let expected = Harness::new(c).expected_ram_area(Region::new(0, 0, 800, 480));
+        assert_eq!(
+            expected[1],
+            Expect::CommandData(SET_RAM_X_RANGE, vec![0, 0, 0x1F, 0x03])
+        );
+        assert_eq!(
+            expected[2],
+            Expect::CommandData(SET_RAM_Y_RANGE, vec![0xDF, 0x01, 0, 0])
+        );
+        assert_eq!(
+            expected[4],
+            Expect::CommandData(SET_RAM_Y_COUNTER, vec![0xDF, 0x01])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_assert_sequence_reports_first_mismatch() -> TestResult {
+        let mut trace = Trace::new();
+        trace.push(0, Event::Command(SET_RAM_Y_RANGE));
+        trace.push(0, Event::Data(vec![0, 0, 15, 0]));
+        trace.push(0, Event::Command(MASTER_ACTIVATION));
+
+        let expected = [Expect::CommandData(SET_RAM_Y_RANGE, vec![15, 0, 0, 0])];
+        let error = assert_sequence("demo", &trace, &expected)
+            .err()
+            .ok_or("expected an error")?;
+        assert_eq!(
+            error.to_string(),
+            "demo: step 0: expected SET_RAM_Y_RANGE start=15 end=0, \
+             got [       0 us] SET_RAM_Y_RANGE start=0 end=15"
+        );
+
+        let expected = [Expect::Command(SET_RAM_Y_RANGE)];
+        let error = assert_sequence("demo", &trace, &expected)
+            .err()
+            .ok_or("expected an error")?;
+        assert!(matches!(
+            error,
+            ConformanceError::Mismatch {
+                index: 1,
+                expected: None,
+                actual: Some(_),
+                ..
+            }
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deviating_config_is_detected() -> TestResult {
+        // Expectations for one preset do not hold for a display built from another
+        let harness = Harness::new(config(32, 48)?);
+        let mut display = Harness::new({
+            let mut c = config(32, 48)?;
+            c.vcom = 0x50;
+            c
+        })
+        .display();
+        display.reset(&mut NoDelay)?;
+        let error = assert_sequence(
+            "reset",
+            display.interface().trace(),
+            &harness.expected_reset(),
+        )
+        .err()
+        .ok_or("expected an error")?;
+        assert!(matches!(error, ConformanceError::Mismatch { index: 7, .. }));
+        Ok(())
+    }
+}
@@ -0,0 +1,1374 @@
+//! Image to framebuffer conversion (requires `std` feature)
+//!
+//! Turns artwork into the packed BW and RED planes expected by
+//! [`Display::update`](crate::display::Display::update). An
+//! [`Image`](crate::convert::Image) is decoded from PNG or binary PGM/PPM,
+//! reduced to black, white and (for tri-color panels) red by a
+//! [`Converter`](crate::convert::Converter), and packed with
+//! [`apply_orientation`](crate::rotation::apply_orientation), so image
+//! coordinates are the drawing coordinates of a
+//! [`GraphicDisplay`](crate::graphics::GraphicDisplay) with the same rotation
+//! and mirroring.
+//!
+//! The `ssd1677-convert` tool wraps this module for the command line.
+//!
+//! ## Color Reduction
+//!
+//! With a red threshold set, a pixel is red when its red channel exceeds both
+//! green and blue by at least the threshold. Every other pixel is reduced to
+//! black or white by its luminance, using the selected
+//! [`Dither`](crate::convert::Dither) method. Transparent pixels are
+//! composited over white first.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::convert::{Converter, Dither, Image};
+//! use ssd1677::Dimensions;
+//!
+//! // 16x8 image: left half black, right half red
+//! let pixels = (0..16 * 8)
+//!     .map(|i| if i % 16 < 8 { [0, 0, 0] } else { [255, 0, 0] })
+//!     .collect();
+//! let image = Image::new(16, 8, pixels).unwrap();
+//!
+//! let planes = Converter::new(Dimensions::new(8, 16).unwrap())
+//!     .dither(Dither::Threshold)
+//!     .red_threshold(Some(96))
+//!     .convert(&image)
+//!     .unwrap();
+//! assert_eq!(planes.black[..2], [0x00, 0xFF]);
+//! assert_eq!(planes.red[..2], [0x00, 0xFF]);
+//! ```
+
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::vec;
+use std::vec::Vec;
+
+use crate::color::{BLACK_LUMINANCE, Color, luminance, red_excess};
+use crate::config::{Dimensions, Mirror, Rotation};
+use crate::rotation::apply_orientation;
+use crate::snapshot::{Crc32, PNG_SIGNATURE, PnmReader, SnapshotError, adler32};
+
+type ConvertResult<T> = core::result::Result<T, ConvertError>;
+type Bytes = Vec<u8>;
+
+/// Reason reported when an image's size overflows `usize`
+const TOO_LARGE: &str = "image too large";
+
+/// 8-bit RGB pixel
+pub type Rgb = [u8; 3];
+
+/// Errors that can occur while decoding or converting an image
+#[derive(Debug)]
+pub enum ConvertError {
+    /// The image data is malformed
+    InvalidImage(&'static str),
+    /// The image uses a feature this decoder does not support
+    Unsupported(&'static str),
+    /// The image does not match the panel's drawing size
+    SizeMismatch {
+        /// Drawing size (width, height) of the panel
+        expected: (u32, u32),
+        /// Size (width, height) of the image
+        actual: (u32, u32),
+    },
+    /// Reading a file failed
+    Io(io::Error),
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidImage(reason) => write!(f, "Invalid image: {}", reason),
+            Self::Unsupported(feature) => write!(f, "Unsupported image: {}", feature),
+            Self::SizeMismatch { expected, actual } => write!(
+                f,
+                "Image is {}x{}, panel draws {}x{}",
+                actual.0, actual.1, expected.0, expected.1
+            ),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl core::error::Error for ConvertError {}
+
+impl From<io::Error> for ConvertError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<SnapshotError> for ConvertError {
+    fn from(error: SnapshotError) -> Self {
+        match error {
+            SnapshotError::InvalidImage(reason) => Self::InvalidImage(reason),
+            SnapshotError::Io(e) => Self::Io(e),
+            _ => Self::InvalidImage("invalid PNM file"),
+        }
+    }
+}
+
+/// Method used to reduce gray levels to black and white
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Dither {
+    /// Plain threshold, best for line art and text
+    Threshold,
+    /// Floyd-Steinberg error diffusion, best for photos
+    #[default]
+    FloydSteinberg,
+    /// Atkinson error diffusion (diffuses 3/4 of the error; higher contrast)
+    Atkinson,
+    /// 4x4 Bayer ordered dithering (regular pattern, stable under small edits)
+    Ordered,
+}
+
+impl Dither {
+    /// Parse a method name (`threshold`, `floyd-steinberg`, `atkinson`, `ordered`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "threshold" | "none" => Some(Self::Threshold),
+            "floyd-steinberg" | "fs" => Some(Self::FloydSteinberg),
+            "atkinson" => Some(Self::Atkinson),
+            "ordered" | "bayer" => Some(Self::Ordered),
+            _ => None,
+        }
+    }
+
+    /// Error diffusion kernel
+    fn kernel(self) -> &'static [Tap] {
+        match self {
+            Self::Threshold | Self::Ordered => &[],
+            Self::FloydSteinberg => &FLOYD_STEINBERG,
+            Self::Atkinson => &ATKINSON,
+        }
+    }
+
+    /// Divisor applied to the kernel weights
+    fn divisor(self) -> i16 {
+        match self {
+            Self::Threshold | Self::Ordered | Self::FloydSteinberg => 16,
+            Self::Atkinson => 8,
+        }
+    }
+}
+
+/// Error diffusion tap: `(dx, dy, weight)`
+type Tap = (isize, usize, i16);
+
+/// Floyd-Steinberg kernel in 16ths
+const FLOYD_STEINBERG: [Tap; 4] = [(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)];
+
+/// Atkinson kernel in 8ths
+const ATKINSON: [Tap; 6] = [
+    (1, 0, 1),
+    (2, 0, 1),
+    (-1, 1, 1),
+    (0, 1, 1),
+    (1, 1, 1),
+    (0, 2, 1),
+];
+
+/// 4x4 Bayer threshold matrix, row-major
+const BAYER_4X4: [i16; 16] = [0, 8, 2, 10, 12, 4, 14, 6, 3, 11, 1, 9, 15, 7, 13, 5];
+
+/// Decoded RGB image
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Create an image from row-major pixels
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConvertError::InvalidImage`] if `pixels` does not hold
+    /// exactly `width * height` entries.
+    pub fn new(width: u32, height: u32, pixels: Vec<Rgb>) -> ConvertResult<Self> {
+        if (width as usize).checked_mul(height as usize) != Some(pixels.len()) {
+            return Err(ConvertError::InvalidImage(
+                "pixel count does not match size",
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Image width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Image height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pixel at `(x, y)`, or `None` outside the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.pixels
+            .get(y as usize * self.width as usize + x as usize)
+            .copied()
+    }
+
+    /// Read and decode an image file (see [`Image::decode`])
+    pub fn load<P: AsRef<Path>>(path: P) -> ConvertResult<Self> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    /// Decode a PNG or binary PGM/PPM image, detected from its signature
+    pub fn decode(data: &[u8]) -> ConvertResult<Self> {
+        if data.starts_with(&PNG_SIGNATURE) {
+            Self::decode_png(data)
+        } else if data.starts_with(b"P5") || data.starts_with(b"P6") {
+            Self::decode_pnm(data)
+        } else {
+            Err(ConvertError::Unsupported("not a PNG, PGM or PPM file"))
+        }
+    }
+
+    /// Decode a binary PGM (`P5`) or PPM (`P6`) image with up to 8 bits per sample
+    pub fn decode_pnm(data: &[u8]) -> ConvertResult<Self> {
+        let mut reader = PnmReader { data, pos: 0 };
+        let magic = reader.token()?;
+        let width = reader.number()?;
+        let height = reader.number()?;
+        let max_value = reader.number()?;
+        if max_value == 0 || max_value > 255 {
+            return Err(ConvertError::Unsupported("PNM samples wider than 8 bits"));
+        }
+        // Exactly one whitespace byte separates the header from the raster
+        let raster = data.get(reader.pos + 1..).unwrap_or_default();
+        let channels = match magic {
+            b"P5" => 1,
+            b"P6" => 3,
+            _ => return Err(ConvertError::Unsupported("not a binary PGM/PPM file")),
+        };
+
+        let size = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|count| count.checked_mul(channels))
+            .ok_or(ConvertError::InvalidImage(TOO_LARGE))?;
+        let raster = raster
+            .get(..size)
+            .ok_or(ConvertError::InvalidImage("truncated raster"))?;
+        let scale = |value: u8| ((value as u32 * 255) / max_value).min(255) as u8;
+        let pixels = raster
+            .chunks(channels)
+            .map(|px| match px {
+                [gray] => [scale(*gray); 3],
+                _ => [scale(px[0]), scale(px[1]), scale(px[2])],
+            })
+            .collect();
+        Self::new(width, height, pixels)
+    }
+
+    /// Decode a PNG image
+    ///
+    /// All color types and bit depths are supported; 16-bit samples are
+    /// reduced to 8 bits and transparency (alpha channels or a palette
+    /// `tRNS` chunk) is composited over white. Interlaced images and
+    /// color-key transparency for gray/RGB images are not supported.
+    pub fn decode_png(data: &[u8]) -> ConvertResult<Self> {
+        let mut rest = data
+            .strip_prefix(&PNG_SIGNATURE)
+            .ok_or(ConvertError::InvalidImage("missing PNG signature"))?;
+        let mut header = None;
+        let mut palette = Bytes::new();
+        let mut alpha = Bytes::new();
+        let mut compressed = Bytes::new();
+
+        while let Some((kind, payload, next)) = png_chunk(rest)? {
+            match &kind {
+                b"IHDR" => header = Some(PngHeader::parse(payload)?),
+                b"PLTE" => palette = payload.to_vec(),
+                b"tRNS" => alpha = payload.to_vec(),
+                b"IDAT" => compressed.extend_from_slice(payload),
+                b"IEND" => break,
+                _ => {}
+            }
+            rest = next;
+        }
+
+        let header = header.ok_or(ConvertError::InvalidImage("missing IHDR chunk"))?;
+        // Filtered rows are never larger than this, so stop inflating there
+        let raw = zlib_decompress(&compressed, header.filtered_size()?)?;
+        let rows = header.unfilter(&raw)?;
+        let pixels = rows
+            .chunks(header.stride()?.max(1))
+            .flat_map(|row| (0..header.width as usize).map(move |x| (row, x)))
+            .map(|(row, x)| header.pixel(row, x, &palette, &alpha))
+            .collect();
+        Self::new(header.width, header.height, pixels)
+    }
+
+    /// Scale to a new size with nearest-neighbour sampling
+    pub fn resize(&self, width: u32, height: u32) -> Self {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            let source_y = (y as u64 * self.height as u64 / height.max(1) as u64) as u32;
+            pixels.extend((0..width).map(|x| {
+                let source_x = (x as u64 * self.width as u64 / width.max(1) as u64) as u32;
+                self.pixel(source_x, source_y).unwrap_or([255; 3])
+            }));
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Packed driver buffers produced by [`Converter::convert`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Planes {
+    /// BW plane (bit 0 = black)
+    pub black: Vec<u8>,
+    /// RED plane (bit 1 = red)
+    pub red: Vec<u8>,
+}
+
+impl Planes {
+    /// Whether any pixel is red
+    pub fn has_red(&self) -> bool {
+        self.red.iter().any(|byte| *byte != 0)
+    }
+}
+
+/// Converts images into driver buffers for one panel configuration
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Converter {
+    dimensions: Dimensions,
+    rotation: Rotation,
+    mirror: Mirror,
+    dither: Dither,
+    threshold: u8,
+    red_threshold: Option<u8>,
+}
+
+impl Converter {
+    /// Create a converter for a panel
+    ///
+    /// Defaults: no rotation or mirroring, Floyd-Steinberg dithering, luminance
+    /// threshold 128 and no red.
+    pub fn new(dimensions: Dimensions) -> Self {
+        Self {
+            dimensions,
+            rotation: Rotation::Rotate0,
+            mirror: Mirror::None,
+            dither: Dither::default(),
+            threshold: BLACK_LUMINANCE,
+            red_threshold: None,
+        }
+    }
+
+    /// Rotation the buffers will be displayed with
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Mirroring the buffers will be drawn with
+    ///
+    /// For a display using [`Builder::hardware_mirror`](crate::config::Builder::hardware_mirror),
+    /// pass [`Config::software_mirror`](crate::config::Config::software_mirror).
+    pub fn mirror(mut self, mirror: Mirror) -> Self {
+        self.mirror = mirror;
+        self
+    }
+
+    /// Method used to reduce gray levels
+    pub fn dither(mut self, dither: Dither) -> Self {
+        self.dither = dither;
+        self
+    }
+
+    /// Luminance below which a pixel is black (0-255)
+    pub fn threshold(mut self, threshold: u8) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Minimum amount by which red must exceed green and blue for a red pixel
+    ///
+    /// `None` (the default) produces a BW-only image. Values below 1 are
+    /// treated as 1.
+    pub fn red_threshold(mut self, red_threshold: Option<u8>) -> Self {
+        self.red_threshold = red_threshold;
+        self
+    }
+
+    /// Drawing size (width, height) images must have
+    pub fn size(&self) -> (u32, u32) {
+        let (cols, rows) = (self.dimensions.cols as u32, self.dimensions.rows as u32);
+        match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => (cols, rows),
+            Rotation::Rotate90 | Rotation::Rotate270 => (rows, cols),
+        }
+    }
+
+    /// Reduce an image to panel colors, in image order
+    pub fn quantize(&self, image: &Image) -> Vec<Color> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let (kernel, divisor) = (self.dither.kernel(), self.dither.divisor());
+        let mut levels: Vec<i16> = image.pixels.iter().map(|&px| luma(px)).collect();
+        let mut colors = vec![Color::White; levels.len()];
+
+        for (index, color) in colors.iter_mut().enumerate() {
+            if self.is_red(image.pixels[index]) {
+                *color = Color::Red;
+                continue;
+            }
+            let (x, y) = (index % width, index / width);
+            let level = levels[index];
+            let biased = match self.dither {
+                Dither::Ordered => level + 120 - BAYER_4X4[(y % 4) * 4 + x % 4] * 16,
+                _ => level,
+            };
+            let black = biased < self.threshold as i16;
+            *color = if black { Color::Black } else { Color::White };
+
+            let error = level - if black { 0 } else { 255 };
+            let targets = kernel.iter().filter_map(|&(dx, dy, weight)| {
+                x.checked_add_signed(dx)
+                    .filter(|tx| *tx < width && y + dy < height)
+                    .map(|tx| ((y + dy) * width + tx, weight))
+            });
+            for (target, weight) in targets {
+                levels[target] += error * weight / divisor;
+            }
+        }
+        colors
+    }
+
+    /// Convert an image of exactly [`Converter::size`] into driver buffers
+    pub fn convert(&self, image: &Image) -> ConvertResult<Planes> {
+        let expected = self.size();
+        let actual = (image.width, image.height);
+        if actual != expected {
+            return Err(ConvertError::SizeMismatch { expected, actual });
+        }
+
+        let (cols, rows) = (self.dimensions.cols as u32, self.dimensions.rows as u32);
+        let size = self.dimensions.buffer_size();
+        let mut planes = Planes {
+            black: vec![0xFF; size],
+            red: vec![0x00; size],
+        };
+        for (index, color) in self.quantize(image).into_iter().enumerate() {
+            let (x, y) = (index as u32 % image.width, index as u32 / image.width);
+            let (byte, bit) = apply_orientation(x, y, cols, rows, self.rotation, self.mirror);
+            match color {
+                Color::Black => planes.black[byte] &= !bit,
+                Color::White => {}
+                Color::Red => planes.red[byte] |= bit,
+            }
+        }
+        Ok(planes)
+    }
+
+    fn is_red(&self, [r, g, b]: Rgb) -> bool {
+        self.red_threshold
+            .is_some_and(|threshold| red_excess(r, g, b) >= threshold.max(1))
+    }
+}
+
+/// Rec. 601 luminance, widened for error diffusion
+fn luma([r, g, b]: Rgb) -> i16 {
+    luminance(r, g, b) as i16
+}
+
+/// Composite a color with alpha over white
+fn over_white(color: Rgb, alpha: u8) -> Rgb {
+    let blend = |c: u8| ((c as u32 * alpha as u32 + 255 * (255 - alpha as u32)) / 255) as u8;
+    color.map(blend)
+}
+
+/// Split the next chunk off a PNG stream, checking its CRC
+///
+/// Returns `None` at the end of the data.
+#[allow(clippy::type_complexity)]
+fn png_chunk(data: &[u8]) -> ConvertResult<Option<([u8; 4], &[u8], &[u8])>> {
+    if data.is_empty() {
+        return Ok(None);
+    }
+    let truncated = ConvertError::InvalidImage("truncated chunk");
+    let (length, rest) = data.split_first_chunk::<4>().ok_or(truncated)?;
+    let length = u32::from_be_bytes(*length) as usize;
+    let (kind, rest) = rest
+        .split_first_chunk::<4>()
+        .ok_or(ConvertError::InvalidImage("truncated chunk"))?;
+    let payload = rest
+        .get(..length)
+        .ok_or(ConvertError::InvalidImage("truncated chunk"))?;
+    let end = length
+        .checked_add(4)
+        .ok_or(ConvertError::InvalidImage("truncated chunk"))?;
+    let crc = rest
+        .get(length..end)
+        .ok_or(ConvertError::InvalidImage("truncated chunk"))?;
+
+    let mut check = Crc32::new();
+    check.update(kind);
+    check.update(payload);
+    if check.finish().to_be_bytes() != crc {
+        return Err(ConvertError::InvalidImage("chunk CRC mismatch"));
+    }
+    Ok(Some((*kind, payload, &rest[end..])))
+}
+
+/// Fields of a PNG `IHDR` chunk
+struct PngHeader {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+impl PngHeader {
+    fn parse(payload: &[u8]) -> ConvertResult<Self> {
+        let [header @ .., _, _, interlace] = payload else {
+            return Err(ConvertError::InvalidImage("invalid IHDR chunk"));
+        };
+        let &[w0, w1, w2, w3, h0, h1, h2, h3, bit_depth, color_type] = header else {
+            return Err(ConvertError::InvalidImage("invalid IHDR chunk"));
+        };
+        let header = Self {
+            width: u32::from_be_bytes([w0, w1, w2, w3]),
+            height: u32::from_be_bytes([h0, h1, h2, h3]),
+            bit_depth,
+            color_type,
+        };
+        let valid_depth = match header.color_type {
+            0 => matches!(header.bit_depth, 1 | 2 | 4 | 8 | 16),
+            3 => matches!(header.bit_depth, 1 | 2 | 4 | 8),
+            2 | 4 | 6 => matches!(header.bit_depth, 8 | 16),
+            _ => false,
+        };
+        if !valid_depth {
+            return Err(ConvertError::InvalidImage(
+                "invalid color type or bit depth",
+            ));
+        }
+        if *interlace != 0 {
+            return Err(ConvertError::Unsupported("interlaced PNG"));
+        }
+        Ok(header)
+    }
+
+    fn channels(&self) -> usize {
+        match self.color_type {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    /// Bytes per row, excluding the filter byte
+    fn stride(&self) -> ConvertResult<usize> {
+        (self.width as usize)
+            .checked_mul(self.channels() * self.bit_depth as usize)
+            .map(|bits| bits.div_ceil(8))
+            .ok_or(ConvertError::InvalidImage(TOO_LARGE))
+    }
+
+    /// Size of the decompressed image data, including the filter bytes
+    fn filtered_size(&self) -> ConvertResult<usize> {
+        self.stride()?
+            .checked_add(1)
+            .and_then(|row| row.checked_mul(self.height as usize))
+            .ok_or(ConvertError::InvalidImage(TOO_LARGE))
+    }
+
+    /// Undo the per-row filters of decompressed image data
+    fn unfilter(&self, raw: &[u8]) -> ConvertResult<Bytes> {
+        let stride = self.stride()?;
+        let pixel_bytes = (self.channels() * self.bit_depth as usize).div_ceil(8);
+        let height = self.height as usize;
+        if raw.len() < self.filtered_size()? {
+            return Err(ConvertError::InvalidImage("truncated image data"));
+        }
+
+        // The row above the first row is all zeros
+        let size = height
+            .checked_add(1)
+            .and_then(|rows| rows.checked_mul(stride))
+            .ok_or(ConvertError::InvalidImage(TOO_LARGE))?;
+        let mut out = vec![0u8; size];
+        for (y, line) in raw.chunks(stride + 1).take(height).enumerate() {
+            let (filter, line) = (line[0], &line[1..]);
+            let (done, current) = out.split_at_mut((y + 1) * stride);
+            let previous = &done[y * stride..];
+            let current = &mut current[..stride];
+            for i in 0..stride {
+                let left = i.checked_sub(pixel_bytes).map_or(0, |j| current[j]);
+                let up_left = i.checked_sub(pixel_bytes).map_or(0, |j| previous[j]);
+                let up = previous[i];
+                let predictor = match filter {
+                    0 => 0,
+                    1 => left,
+                    2 => up,
+                    3 => u8::midpoint(left, up),
+                    4 => paeth(left, up, up_left),
+                    _ => return Err(ConvertError::InvalidImage("invalid filter type")),
+                };
+                current[i] = line[i].wrapping_add(predictor);
+            }
+        }
+        out.drain(..stride);
+        Ok(out)
+    }
+
+    /// Sample `index` of a row, scaled to 8 bits (palette indices are not scaled)
+    fn sample(&self, row: &[u8], index: usize) -> u8 {
+        let depth = self.bit_depth as usize;
+        match depth {
+            8 => row[index],
+            16 => row[index * 2],
+            _ => {
+                let offset = index * depth;
+                let max = (1u16 << depth) - 1;
+                let value = (row[offset / 8] >> (8 - depth - offset % 8)) as u16 & max;
+                if self.color_type == 3 {
+                    value as u8
+                } else {
+                    (value * 255 / max) as u8
+                }
+            }
+        }
+    }
+
+    fn pixel(&self, row: &[u8], x: usize, palette: &[u8], alpha: &[u8]) -> Rgb {
+        let base = x * self.channels();
+        let sample = |channel: usize| self.sample(row, base + channel);
+        match self.color_type {
+            0 => [sample(0); 3],
+            2 => [sample(0), sample(1), sample(2)],
+            3 => {
+                let index = sample(0) as usize;
+                let color = palette
+                    .get(index * 3..index * 3 + 3)
+                    .map_or([0; 3], |rgb| [rgb[0], rgb[1], rgb[2]]);
+                over_white(color, alpha.get(index).copied().unwrap_or(255))
+            }
+            4 => over_white([sample(0); 3], sample(1)),
+            _ => over_white([sample(0), sample(1), sample(2)], sample(3)),
+        }
+    }
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let distance = |value: u8| (estimate - value as i16).abs();
+    if distance(left) <= distance(up) && distance(left) <= distance(up_left) {
+        left
+    } else if distance(up) <= distance(up_left) {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Decompress a zlib stream, failing if it inflates to more than `limit` bytes
+fn zlib_decompress(data: &[u8], limit: usize) -> ConvertResult<Bytes> {
+    let [cmf, flg, ..] = data else {
+        return Err(ConvertError::InvalidImage("truncated zlib stream"));
+    };
+    if cmf & 0x0F != 8 || (*cmf as u16 * 256 + *flg as u16) % 31 != 0 || flg & 0x20 != 0 {
+        return Err(ConvertError::InvalidImage("invalid zlib header"));
+    }
+    let mut inflater = Inflater {
+        data,
+        pos: 2,
+        bit_buffer: 0,
+        bit_count: 0,
+        out: Vec::new(),
+        limit,
+    };
+    inflater.inflate()?;
+
+    let checksum = data
+        .get(inflater.pos..inflater.pos + 4)
+        .ok_or(ConvertError::InvalidImage("missing zlib checksum"))?;
+    if adler32(&inflater.out).to_be_bytes() != checksum {
+        return Err(ConvertError::InvalidImage("zlib checksum mismatch"));
+    }
+    Ok(inflater.out)
+}
+
+/// Base lengths for length codes 257..285
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+/// Extra bits for length codes 257..285
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances for distance codes 0..29
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+/// Extra bits for distance codes 0..29
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order in which code length code lengths are stored
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Canonical Huffman decoding table
+struct Huffman {
+    /// Number of codes of each length
+    counts: [u16; 16],
+    /// Symbols ordered by code
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> ConvertResult<Self> {
+        let mut counts = [0u16; 16];
+        for &length in lengths {
+            counts[length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut left: i32 = 1;
+        for &count in &counts[1..] {
+            left = left * 2 - count as i32;
+            if left < 0 {
+                return Err(ConvertError::InvalidImage("over-subscribed Huffman code"));
+            }
+        }
+
+        let mut offsets = [0u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                symbols[offsets[length as usize] as usize] = symbol as u16;
+                offsets[length as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+}
+
+/// Deflate decoder (RFC 1951)
+struct Inflater<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit_buffer: u32,
+    bit_count: u32,
+    out: Bytes,
+    /// Maximum output size
+    limit: usize,
+}
+
+impl Inflater<'_> {
+    /// Check that `count` more output bytes stay within the limit
+    fn reserve(&self, count: usize) -> ConvertResult<()> {
+        if count > self.limit - self.out.len() {
+            return Err(ConvertError::InvalidImage("decompressed data too large"));
+        }
+        Ok(())
+    }
+
+    fn bits(&mut self, count: u32) -> ConvertResult<u32> {
+        while self.bit_count < count {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or(ConvertError::InvalidImage("truncated deflate stream"))?;
+            self.pos += 1;
+            self.bit_buffer |= (byte as u32) << self.bit_count;
+            self.bit_count += 8;
+        }
+        let value = self.bit_buffer & ((1u32 << count) - 1);
+        self.bit_buffer >>= count;
+        self.bit_count -= count;
+        Ok(value)
+    }
+
+    fn inflate(&mut self) -> ConvertResult<()> {
+        loop {
+            let last = self.bits(1)? == 1;
+            match self.bits(2)? {
+                0 => self.stored()?,
+                1 => self.fixed()?,
+                2 => self.dynamic()?,
+                _ => return Err(ConvertError::InvalidImage("invalid deflate block type")),
+            }
+            if last {
+                return Ok(());
+            }
+        }
+    }
+
+    fn stored(&mut self) -> ConvertResult<()> {
+        // Discard the rest of the current byte
+        self.bit_buffer = 0;
+        self.bit_count = 0;
+        let header = self
+            .data
+            .get(self.pos..self.pos + 4)
+            .ok_or(ConvertError::InvalidImage("truncated stored block"))?;
+        let length = u16::from_le_bytes([header[0], header[1]]);
+        if length != !u16::from_le_bytes([header[2], header[3]]) {
+            return Err(ConvertError::InvalidImage("invalid stored block length"));
+        }
+        self.pos += 4;
+        let block = self
+            .data
+            .get(self.pos..self.pos + length as usize)
+            .ok_or(ConvertError::InvalidImage("truncated stored block"))?;
+        self.reserve(block.len())?;
+        self.out.extend_from_slice(block);
+        self.pos += length as usize;
+        Ok(())
+    }
+
+    fn fixed(&mut self) -> ConvertResult<()> {
+        let mut lengths = [0u8; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        let literals = Huffman::new(&lengths)?;
+        let distances = Huffman::new(&[5; 30])?;
+        self.codes(&literals, &distances)
+    }
+
+    fn dynamic(&mut self) -> ConvertResult<()> {
+        let literal_count = self.bits(5)? as usize + 257;
+        let distance_count = self.bits(5)? as usize + 1;
+        let code_count = self.bits(4)? as usize + 4;
+        if literal_count > 286 || distance_count > 30 {
+            return Err(ConvertError::InvalidImage("too many Huffman codes"));
+        }
+
+        let mut code_lengths = [0u8; 19];
+        for &index in &CODE_LENGTH_ORDER[..code_count] {
+            code_lengths[index] = self.bits(3)? as u8;
+        }
+        let code_length_codes = Huffman::new(&code_lengths)?;
+
+        let mut lengths = Vec::with_capacity(literal_count + distance_count);
+        while lengths.len() < literal_count + distance_count {
+            let symbol = self.decode(&code_length_codes)?;
+            let (value, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => {
+                    let previous = *lengths
+                        .last()
+                        .ok_or(ConvertError::InvalidImage("repeat without a length"))?;
+                    (previous, 3 + self.bits(2)?)
+                }
+                17 => (0, 3 + self.bits(3)?),
+                _ => (0, 11 + self.bits(7)?),
+            };
+            lengths.extend(core::iter::repeat_n(value, repeat as usize));
+        }
+        if lengths.len() > literal_count + distance_count {
+            return Err(ConvertError::InvalidImage("too many code lengths"));
+        }
+        if lengths[256] == 0 {
+            return Err(ConvertError::InvalidImage("missing end-of-block code"));
+        }
+
+        let literals = Huffman::new(&lengths[..literal_count])?;
+        let distances = Huffman::new(&lengths[literal_count..])?;
+        self.codes(&literals, &distances)
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> ConvertResult<u16> {
+        let (mut code, mut first, mut index) = (0u32, 0u32, 0u32);
+        for &count in &huffman.counts[1..] {
+            code |= self.bits(1)?;
+            let count = count as u32;
+            if code - first < count {
+                return Ok(huffman.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(ConvertError::InvalidImage("invalid Huffman code"))
+    }
+
+    fn codes(&mut self, literals: &Huffman, distances: &Huffman) -> ConvertResult<()> {
+        loop {
+            let symbol = self.decode(literals)? as usize;
+            if symbol < 256 {
+                self.reserve(1)?;
+                self.out.push(symbol as u8);
+                continue;
+            }
+            if symbol == 256 {
+                return Ok(());
+            }
+
+            let code = symbol - 257;
+            let (Some(&base), Some(&extra)) = (LENGTH_BASE.get(code), LENGTH_EXTRA.get(code))
+            else {
+                return Err(ConvertError::InvalidImage("invalid length code"));
+            };
+            let length = base as usize + self.bits(extra as u32)? as usize;
+
+            let code = self.decode(distances)? as usize;
+            let (Some(&base), Some(&extra)) = (DISTANCE_BASE.get(code), DISTANCE_EXTRA.get(code))
+            else {
+                return Err(ConvertError::InvalidImage("invalid distance code"));
+            };
+            let distance = base as usize + self.bits(extra as u32)? as usize;
+            if distance > self.out.len() {
+                return Err(ConvertError::InvalidImage("distance too far back"));
+            }
+
+            self.reserve(length)?;
+            let start = self.out.len() - distance;
+            for offset in 0..length {
+                let byte = self.out[start + offset];
+                self.out.push(byte);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::snapshot::{ImageFormat, Snapshot};
+
+    fn dims(rows: u16, cols: u16) -> TestResult<Dimensions> {
+        Ok(Dimensions::new(rows, cols)?)
+    }
+
+    fn gray_ramp(width: u32, height: u32) -> TestResult<Image> {
+        let pixels = (0..height)
+            .flat_map(|_| (0..width).map(move |x| [(x * 255 / (width - 1)) as u8; 3]))
+            .collect();
+        Ok(Image::new(width, height, pixels)?)
+    }
+
+    /// zlib.compress(b"abcabcabcabcabcabc hello hello", 9): fixed Huffman
+    const FIXED_STREAM: [u8; 21] = [
+        0x78, 0xDA, 0x4B, 0x4C, 0x4A, 0x4E, 0x44, 0x45, 0x0A, 0x19, 0xA9, 0x39, 0x39, 0xF9, 0x10,
+        0x12, 0x00, 0xAF, 0x3E, 0x0B, 0x4D,
+    ];
+
+    /// zlib.compress(bytes((i * i + i // 3) % 23 for i in range(50)), 9): dynamic Huffman
+    const DYNAMIC_STREAM: [u8; 52] = [
+        0x78, 0xDA, 0x05, 0xC1, 0x89, 0x0D, 0x00, 0x20, 0x08, 0x04, 0x30, 0xFC, 0x02, 0x8A, 0xE6,
+        0x40, 0x09, 0xFB, 0x8F, 0x6A, 0x4B, 0xA5, 0x2F, 0x6F, 0x18, 0x01, 0x9D, 0x6B, 0x1B, 0xC9,
+        0x95, 0x84, 0x0A, 0xCF, 0xED, 0x55, 0xF3, 0x8C, 0x7C, 0xEE, 0x51, 0xD8, 0x1A, 0x98, 0xAE,
+        0xC1, 0xA2, 0x7E, 0x35, 0x16, 0x02, 0x38,
+    ];
+
+    /// Deflate bits packed LSB first behind a zlib header
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        bit_count: usize,
+    }
+
+    impl BitWriter {
+        /// Append `count` bits of `value`, least significant first
+        fn bits(mut self, value: u32, count: usize) -> Self {
+            for bit in 0..count {
+                let index = self.bit_count / 8;
+                self.bytes.resize(index + 1, 0);
+                self.bytes[index] |= ((value >> bit & 1) as u8) << (self.bit_count % 8);
+                self.bit_count += 1;
+            }
+            self
+        }
+
+        /// Append a Huffman code, most significant bit first
+        fn code(self, code: u32, length: usize) -> Self {
+            let reversed = (0..length).fold(0, |acc, bit| acc << 1 | (code >> bit & 1));
+            self.bits(reversed, length)
+        }
+
+        /// Append a dynamic block header whose code length code is
+        /// `0` -> symbol 0 and `1` -> symbol 16
+        fn dynamic_header(self) -> Self {
+            // BFINAL, BTYPE=2, HLIT=0, HDIST=0, HCLEN=0 (lengths for 16, 17, 18, 0)
+            self.bits(1, 1)
+                .bits(2, 2)
+                .bits(0, 14)
+                .bits(1, 3)
+                .bits(0, 3)
+                .bits(0, 3)
+                .bits(1, 3)
+        }
+
+        fn zlib(self) -> Vec<u8> {
+            let mut stream = vec![0x78, 0x01];
+            stream.extend(self.bytes);
+            stream
+        }
+    }
+
+    fn inflate_error(stream: &[u8]) -> &'static str {
+        match zlib_decompress(stream, usize::MAX) {
+            Err(ConvertError::InvalidImage(reason)) => reason,
+            _ => "(no InvalidImage error)",
+        }
+    }
+
+    #[test]
+    fn test_inflate_fixed_and_dynamic_blocks() -> TestResult {
+        assert_eq!(
+            zlib_decompress(&FIXED_STREAM, usize::MAX)?,
+            b"abcabcabcabcabcabc hello hello"
+        );
+
+        let expected: Vec<u8> = (0..50u32).map(|i| ((i * i + i / 3) % 23) as u8).collect();
+        assert_eq!(zlib_decompress(&DYNAMIC_STREAM, usize::MAX)?, expected);
+
+        let mut corrupt = FIXED_STREAM;
+        corrupt[20] ^= 1;
+        assert_eq!(inflate_error(&corrupt), "zlib checksum mismatch");
+        Ok(())
+    }
+
+    #[test]
+    fn test_inflate_rejects_malformed_streams() {
+        assert_eq!(inflate_error(&[0x78]), "truncated zlib stream");
+        assert_eq!(inflate_error(&[0x78, 0x02]), "invalid zlib header");
+        assert_eq!(inflate_error(&[0x78, 0x01]), "truncated deflate stream");
+        assert_eq!(
+            inflate_error(&BitWriter::default().bits(0b111, 3).zlib()),
+            "invalid deflate block type"
+        );
+
+        // Stored blocks: LEN/NLEN mismatch, and fewer bytes than LEN
+        let stored = |tail: &[u8]| [&[0x78, 0x01, 0x01][..], tail].concat();
+        assert_eq!(
+            inflate_error(&stored(&[0x05, 0x00, 0x00, 0x00])),
+            "invalid stored block length"
+        );
+        assert_eq!(
+            inflate_error(&stored(&[0x05, 0x00, 0xFA, 0xFF, b'a', b'b'])),
+            "truncated stored block"
+        );
+        assert_eq!(inflate_error(&stored(&[0x05])), "truncated stored block");
+
+        // Fixed block starting with a length/distance pair: nothing to copy from
+        let far_back = BitWriter::default()
+            .bits(1, 1)
+            .bits(1, 2)
+            .code(0b000_0001, 7)
+            .code(0, 5);
+        assert_eq!(inflate_error(&far_back.zlib()), "distance too far back");
+    }
+
+    #[test]
+    fn test_inflate_rejects_bad_huffman_tables() {
+        // Four code length codes of length 1
+        let over_subscribed = BitWriter::default()
+            .bits(1, 1)
+            .bits(2, 2)
+            .bits(0, 14)
+            .bits(1, 3)
+            .bits(1, 3)
+            .bits(1, 3)
+            .bits(1, 3);
+        assert_eq!(
+            inflate_error(&over_subscribed.zlib()),
+            "over-subscribed Huffman code"
+        );
+
+        // Code 16 (repeat previous) before any length
+        let repeat = BitWriter::default().dynamic_header().code(1, 1);
+        assert_eq!(inflate_error(&repeat.zlib()), "repeat without a length");
+
+        // 258 zero lengths leave the end-of-block symbol without a code
+        let no_end = (0..258).fold(BitWriter::default().dynamic_header(), |writer, _| {
+            writer.code(0, 1)
+        });
+        assert_eq!(inflate_error(&no_end.zlib()), "missing end-of-block code");
+
+        // Too many literal/length codes (HLIT = 30 -> 287)
+        let too_many = BitWriter::default()
+            .bits(1, 1)
+            .bits(2, 2)
+            .bits(30, 5)
+            .bits(0, 9);
+        assert_eq!(inflate_error(&too_many.zlib()), "too many Huffman codes");
+    }
+
+    #[test]
+    fn test_inflate_survives_truncation_and_bit_flips() {
+        for stream in [&FIXED_STREAM[..], &DYNAMIC_STREAM[..]] {
+            for len in 0..stream.len() {
+                assert!(zlib_decompress(&stream[..len], usize::MAX).is_err());
+            }
+            for bit in 0..stream.len() * 8 {
+                let mut corrupt = stream.to_vec();
+                corrupt[bit / 8] ^= 1 << (bit % 8);
+                // Any outcome but a panic is fine; most flips are caught by the checksum
+                let _ = zlib_decompress(&corrupt, usize::MAX);
+            }
+        }
+    }
+
+    /// Fixed Huffman stream of one zero byte followed by `copies` 258-byte
+    /// copies of it (no checksum)
+    fn zeros_stream(copies: usize) -> Vec<u8> {
+        let header = BitWriter::default().bits(1, 1).bits(1, 2).code(0x30, 8);
+        (0..copies)
+            .fold(header, |bits, _| bits.code(0xC5, 8).code(0, 5))
+            .code(0, 7)
+            .zlib()
+    }
+
+    #[test]
+    fn test_inflate_stops_at_limit() {
+        let stream = zeros_stream(1000);
+        let limited = match zlib_decompress(&stream, 1024) {
+            Err(ConvertError::InvalidImage(reason)) => reason,
+            _ => "(no InvalidImage error)",
+        };
+        assert_eq!(limited, "decompressed data too large");
+        assert!(zlib_decompress(&FIXED_STREAM, 29).is_err());
+    }
+
+    /// PNG file holding an `IHDR` chunk and a single `IDAT` chunk
+    fn png(width: u32, height: u32, bit_depth: u8, color_type: u8, idat: &[u8]) -> Vec<u8> {
+        let mut ihdr = Vec::new();
+        ihdr.extend(width.to_be_bytes());
+        ihdr.extend(height.to_be_bytes());
+        ihdr.extend([bit_depth, color_type, 0, 0, 0]);
+        let mut file = PNG_SIGNATURE.to_vec();
+        for (kind, payload) in [(b"IHDR", &ihdr[..]), (b"IDAT", idat), (b"IEND", &[])] {
+            let mut check = Crc32::new();
+            check.update(kind);
+            check.update(payload);
+            file.extend((payload.len() as u32).to_be_bytes());
+            file.extend(kind);
+            file.extend(payload);
+            file.extend(check.finish().to_be_bytes());
+        }
+        file
+    }
+
+    #[test]
+    fn test_decode_rejects_huge_sizes() {
+        for (bit_depth, color_type) in [(1, 0), (8, 0), (16, 0), (8, 2), (16, 6), (8, 3)] {
+            for (width, height) in [(u32::MAX, u32::MAX), (u32::MAX, 1), (1, u32::MAX)] {
+                let file = png(width, height, bit_depth, color_type, &FIXED_STREAM);
+                assert!(Image::decode(&file).is_err());
+            }
+        }
+
+        // A tiny image whose data inflates far past its size
+        let file = png(1, 1, 8, 0, &zeros_stream(100));
+        assert!(matches!(
+            Image::decode(&file),
+            Err(ConvertError::InvalidImage("decompressed data too large"))
+        ));
+
+        for header in ["P5 4294967295 4294967295 255\n", "P6 4294967295 2 255\n"] {
+            assert!(Image::decode(header.as_bytes()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_png_round_trips_snapshot() -> TestResult {
+        let mut snapshot = Snapshot::new(10, 3, Color::White);
+        snapshot.set_pixel(0, 0, Color::Black);
+        snapshot.set_pixel(9, 2, Color::Red);
+        let image = Image::decode(&snapshot.encode(ImageFormat::Png))?;
+
+        assert_eq!((image.width(), image.height()), (10, 3));
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 0]));
+        assert_eq!(image.pixel(1, 0), Some([255, 255, 255]));
+        assert_eq!(image.pixel(9, 2), Some([255, 0, 0]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_png_filters_and_alpha() -> TestResult {
+        // 3x2 RGBA, compressed by zlib: row 0 Sub-filtered, row 1 Paeth-filtered
+        let png = [
+            0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48,
+            0x44, 0x52, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02, 0x08, 0x06, 0x00, 0x00,
+            0x00, 0x9D, 0x74, 0x66, 0x1A, 0x00, 0x00, 0x00, 0x20, 0x49, 0x44, 0x41, 0x54, 0x78,
+            0xDA, 0x63, 0x64, 0x60, 0x60, 0xF8, 0xFF, 0x1F, 0x48, 0x70, 0x8B, 0xC8, 0x31, 0xB2,
+            0x00, 0xE9, 0x86, 0xD4, 0x94, 0x94, 0x86, 0xAF, 0xB3, 0x67, 0xFF, 0x07, 0x00, 0x46,
+            0x1E, 0x07, 0x99, 0xD8, 0xEE, 0x6E, 0x18, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E,
+            0x44, 0xAE, 0x42, 0x60, 0x82,
+        ];
+        let image = Image::decode(&png)?;
+        assert_eq!((image.width(), image.height()), (3, 2));
+        // Opaque black, opaque red, fully transparent (-> white)
+        assert_eq!(image.pixel(0, 0), Some([0, 0, 0]));
+        assert_eq!(image.pixel(1, 0), Some([255, 0, 0]));
+        assert_eq!(image.pixel(2, 0), Some([255, 255, 255]));
+        // Half-transparent black over white, opaque gray, opaque white
+        assert_eq!(image.pixel(0, 1), Some([128, 128, 128]));
+        assert_eq!(image.pixel(1, 1), Some([100, 100, 100]));
+        assert_eq!(image.pixel(2, 1), Some([255, 255, 255]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_pnm() -> TestResult {
+        let mut data = b"P6\n# art\n2 1\n15\n".to_vec();
+        data.extend([15, 0, 0, 0, 15, 15]);
+        let image = Image::decode(&data)?;
+        assert_eq!(image.pixel(0, 0), Some([255, 0, 0]));
+        assert_eq!(image.pixel(1, 0), Some([0, 255, 255]));
+
+        assert!(matches!(
+            Image::decode(b"GIF89a"),
+            Err(ConvertError::Unsupported(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_threshold_and_red_classification() -> TestResult {
+        let pixels = vec![[0, 0, 0], [200, 200, 200], [220, 40, 40], [140, 100, 100]];
+        let image = Image::new(4, 1, pixels)?;
+        let converter = Converter::new(dims(1, 8)?).dither(Dither::Threshold);
+        let expected_bw = [Color::Black, Color::White, Color::Black, Color::Black];
+        assert_eq!(converter.quantize(&image), expected_bw);
+
+        let tri = converter.red_threshold(Some(96));
+        let expected_tri = [Color::Black, Color::White, Color::Red, Color::Black];
+        assert_eq!(tri.quantize(&image), expected_tri);
+
+        // Thresholding at the defaults agrees with `Color::from_rgb`
+        let pixels: Vec<Rgb> = (0..=255u8)
+            .step_by(15)
+            .flat_map(|r| [[r, 0, 0], [r, 120, 30], [r, r, r], [r, 255, 90]])
+            .collect();
+        let image = Image::new(pixels.len() as u32, 1, pixels.clone())?;
+        let expected: Vec<Color> = pixels
+            .iter()
+            .map(|&[r, g, b]| Color::from_rgb(r, g, b))
+            .collect();
+        assert_eq!(tri.quantize(&image), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dithering_preserves_average_level() -> TestResult {
+        let image = gray_ramp(64, 16)?;
+        for dither in [Dither::FloydSteinberg, Dither::Atkinson, Dither::Ordered] {
+            let colors = Converter::new(dims(16, 64)?)
+                .dither(dither)
+                .quantize(&image);
+            // Left and right quarters are mostly black and mostly white
+            let whites = |range: core::ops::Range<usize>| {
+                colors
+                    .chunks(64)
+                    .flat_map(|row| row[range.clone()].iter())
+                    .filter(|color| **color == Color::White)
+                    .count()
+            };
+            assert!(whites(0..16) < 16 * 16 / 4, "{dither:?}");
+            assert!(whites(48..64) > 16 * 16 * 3 / 4, "{dither:?}");
+            // The middle is a mix
+            let middle = whites(24..40);
+            assert!(
+                middle > 16 * 16 / 4 && middle < 16 * 16 * 3 / 4,
+                "{dither:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_matches_rotated_layout() -> TestResult {
+        let panel = dims(8, 16)?;
+        let rotations = [
+            Rotation::Rotate0,
+            Rotation::Rotate90,
+            Rotation::Rotate180,
+            Rotation::Rotate270,
+        ];
+        let mirrors = [
+            Mirror::None,
+            Mirror::Horizontal,
+            Mirror::Vertical,
+            Mirror::Both,
+        ];
+        let orientations = rotations
+            .into_iter()
+            .flat_map(|rotation| mirrors.map(|mirror| (rotation, mirror)));
+        for (rotation, mirror) in orientations {
+            let converter = Converter::new(panel)
+                .rotation(rotation)
+                .mirror(mirror)
+                .dither(Dither::Threshold)
+                .red_threshold(Some(96));
+            let (width, height) = converter.size();
+            let mut pixels = vec![[255; 3]; (width * height) as usize];
+            pixels[0] = [0, 0, 0];
+            pixels[(width * height - 1) as usize] = [255, 0, 0];
+            let image = Image::new(width, height, pixels)?;
+
+            let planes = converter.convert(&image)?;
+            let snapshot = Snapshot::from_buffers_mirrored(
+                &planes.black,
+                &planes.red,
+                panel,
+                rotation,
+                mirror,
+            )?;
+            let case = std::format!("{rotation:?} {mirror:?}");
+            assert_eq!(snapshot.pixel(0, 0), Some(Color::Black), "{case}");
+            assert_eq!(
+                snapshot.pixel(width - 1, height - 1),
+                Some(Color::Red),
+                "{case}"
+            );
+            assert_eq!(snapshot.pixel(1, 0), Some(Color::White), "{case}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_requires_drawing_size() -> TestResult {
+        let converter = Converter::new(dims(8, 16)?).rotation(Rotation::Rotate90);
+        let error = converter
+            .convert(&gray_ramp(16, 8)?)
+            .err()
+            .ok_or("expected an error")?;
+        assert!(matches!(
+            error,
+            ConvertError::SizeMismatch {
+                expected: (8, 16),
+                actual: (16, 8)
+            }
+        ));
+        let resized = gray_ramp(16, 8)?.resize(8, 16);
+        assert!(converter.convert(&resized).is_ok());
+        Ok(())
+    }
+}
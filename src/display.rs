@@ -4,17 +4,27 @@ use embedded_hal::delay::DelayNs;
 
 use crate::command::{
     AUTO_WRITE_BW_RAM, AUTO_WRITE_RED_RAM, BOOSTER_SOFT_START, BORDER_WAVEFORM, CTRL1_BYPASS_RED,
-    CTRL1_NORMAL, DATA_ENTRY_MODE, DEEP_SLEEP, DISPLAY_UPDATE_CTRL1, DISPLAY_UPDATE_CTRL2,
-    DRIVER_OUTPUT_CONTROL, GATE_VOLTAGE, MASTER_ACTIVATION, SET_RAM_X_COUNTER, SET_RAM_X_RANGE,
-    SET_RAM_Y_COUNTER, SET_RAM_Y_RANGE, SOFT_RESET, SOURCE_VOLTAGE, TEMP_SENSOR_CONTROL, WRITE_LUT,
-    WRITE_RAM_BW, WRITE_RAM_RED, WRITE_VCOM,
+    CTRL1_NORMAL, CTRL2_DISABLE_ANALOG, CTRL2_DISABLE_CLOCK, CTRL2_DISPLAY_MODE_2,
+    CTRL2_ENABLE_CLOCK, CTRL2_LOAD_LUT, CTRL2_LOAD_TEMPERATURE, DATA_ENTRY_MODE, DEEP_SLEEP,
+    DISPLAY_UPDATE_CTRL1, DISPLAY_UPDATE_CTRL2, DRIVER_OUTPUT_CONTROL, GATE_SCAN_START,
+    GATE_VOLTAGE, GATE_VOLTAGE_DEFAULT, MASTER_ACTIVATION, SET_RAM_X_COUNTER, SET_RAM_X_RANGE,
+    SET_RAM_Y_COUNTER, SET_RAM_Y_RANGE, SOFT_RESET, SOURCE_VOLTAGE, SOURCE_VOLTAGE_DEFAULT,
+    TEMP_SENSOR_CONTROL, WRITE_LUT, WRITE_RAM_BW, WRITE_RAM_RED, WRITE_VCOM,
 };
-use crate::config::{Config, RamXAddressing};
+use crate::config::{Config, Dimensions, LutCheck, RamXAddressing};
 use crate::error::Error;
 use crate::interface::DisplayInterface;
-use crate::lut::{LUT_FAST, LUT_PARTIAL};
+use crate::lut::{LUT_FAST, LUT_PARTIAL, LutEntry};
+use crate::rle::{self, CompressedImage, Decoder};
+use crate::waveform::Waveform;
 
-type DisplayResult<I> = core::result::Result<(), Error<I>>;
+type DisplayResult<I, T = ()> = core::result::Result<T, Error<I>>;
+
+/// Size of the stack buffer used to stream compressed images into RAM
+///
+/// [`Display::update_compressed`] decodes and sends at most this many bytes
+/// per `send_data` call.
+pub const STREAM_CHUNK_SIZE: usize = 64;
 
 /// Region specification for partial updates
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -55,6 +65,18 @@ pub struct UpdateRegion<'a> {
     pub mode: RefreshMode,
 }
 
+/// Planes of an image taller than the panel, for hardware scrolling
+///
+/// Both planes hold whole panel rows laid out like a full buffer, as many as
+/// the canvas is tall. See [`Display::update_canvas`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScrollCanvas<'a> {
+    /// Black/white plane
+    pub black: &'a [u8],
+    /// Red plane (empty slice for BW-only canvases)
+    pub red: &'a [u8],
+}
+
 /// Refresh mode for display updates
 ///
 /// Different refresh modes trade off speed vs quality.
@@ -77,6 +99,35 @@ pub enum RefreshMode {
     /// Best for: UI updates, scrolling, cursor movement.
     /// May have slight ghosting on high-contrast transitions.
     Fast,
+    /// Full refresh that always reloads the OTP LUT
+    ///
+    /// Like [`RefreshMode::Full`], but forces the load-temperature and load-LUT
+    /// steps into the update sequence, so the OTP waveform is used even after
+    /// [`Display::load_lut`] has overwritten the LUT register.
+    Otp(OtpMode),
+}
+
+/// OTP waveform bank selected during a display update
+///
+/// The SSD1677 OTP holds waveforms for two display modes; which one a panel
+/// uses for what (e.g. full vs. fast) is panel-specific.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OtpMode {
+    /// Display Mode 1
+    #[default]
+    Mode1,
+    /// Display Mode 2
+    Mode2,
+}
+
+impl OtpMode {
+    /// Display Update Control 2 bits selecting this mode
+    fn ctrl2_bits(self) -> u8 {
+        match self {
+            Self::Mode1 => 0,
+            Self::Mode2 => CTRL2_DISPLAY_MODE_2,
+        }
+    }
 }
 
 /// Deep sleep mode configuration
@@ -94,6 +145,56 @@ pub enum DeepSleepMode {
     PreserveRamAndAnalog = 0x03,
 }
 
+/// Analog power state as last commanded by the driver
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PowerState {
+    /// Analog supplies are off
+    Off,
+    /// Analog supplies are on
+    On,
+    /// An update or power-down sequence failed part-way
+    Unknown,
+}
+
+/// RAM planes written by one pass of [`Display::update_banded`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BandPass {
+    /// BW plane to BW RAM, red plane to RED RAM
+    TriColor,
+    /// BW plane to both RAMs (the refresh bypasses red)
+    BlackToBoth,
+    /// BW plane to BW RAM only (a fast refresh against the previous image)
+    BlackOnly,
+    /// BW plane to RED RAM only (after a fast refresh)
+    BlackToRed,
+}
+
+/// Refresh started by [`Display::begin_update_with_mode`]
+///
+/// The panel is busy until the refresh ends; pass this to
+/// [`Display::finish_update`] before sending it anything else.
+#[must_use = "the panel stays busy until `finish_update` is called"]
+#[derive(Debug, PartialEq)]
+pub struct PendingUpdate {
+    /// Copy the BW image to RED RAM once the refresh is done
+    sync_red: bool,
+}
+
+/// Source of ambient temperature readings for LUT selection
+///
+/// Implemented for closures returning `Option<i16>`, so a sensor read can be
+/// passed directly to [`Display::update_temperature`].
+pub trait TemperatureSource {
+    /// Read the current temperature in °C, or `None` if unavailable
+    fn temperature_celsius(&mut self) -> Option<i16>;
+}
+
+impl<F: FnMut() -> Option<i16>> TemperatureSource for F {
+    fn temperature_celsius(&mut self) -> Option<i16> {
+        self()
+    }
+}
+
 /// Core display driver for SSD1677
 ///
 /// This struct provides low-level operations for the SSD1677 controller.
@@ -107,7 +208,13 @@ where
     /// Display configuration
     config: Config,
     /// Whether the display power is on
-    is_display_on: bool,
+    power: PowerState,
+    /// Last reported ambient temperature in °C
+    temperature: Option<i16>,
+    /// Buffer row shown at the top of the panel
+    scroll_offset: u16,
+    /// Whether a [`LutEntry`] replaced the default driving voltages
+    lut_voltages: bool,
 }
 
 impl<I> Display<I>
@@ -119,13 +226,19 @@ where
         Self {
             interface,
             config,
-            is_display_on: false,
+            power: PowerState::Off,
+            temperature: None,
+            scroll_offset: 0,
+            lut_voltages: false,
         }
     }
 
     /// Perform hardware reset, software reset, and initialization
     pub fn reset<D: DelayNs>(&mut self, delay: &mut D) -> DisplayResult<I> {
         self.interface.reset(delay);
+        self.power = PowerState::Off;
+        self.scroll_offset = 0;
+        self.lut_voltages = false;
         self.send_command(SOFT_RESET)?;
         self.interface.busy_wait(delay).map_err(Error::Interface)?;
         self.init(delay)
@@ -148,7 +261,7 @@ where
         self.send_data(&[
             ((rows - 1) % 256) as u8,
             ((rows - 1) / 256) as u8,
-            self.config.gate_scanning,
+            self.config.driver_gate_scanning(),
         ])?;
 
         // Border waveform
@@ -216,6 +329,61 @@ where
         self.update_with_mode_internal(black_buffer, red_buffer, mode, delay, true)
     }
 
+    /// Start an update without waiting for the refresh to finish
+    ///
+    /// Writes the frame and starts the refresh like
+    /// [`update_with_mode`](Display::update_with_mode), then returns while
+    /// the panel is still busy, so refreshes on several panels can run at the
+    /// same time. Complete it with [`finish_update`](Display::finish_update),
+    /// passing the same `black_buffer`; until then the panel must not be sent
+    /// anything else.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BufferTooSmall` if a buffer is too small, or
+    /// [`Error::Interface`] if there's a communication error.
+    pub fn begin_update_with_mode(
+        &mut self,
+        black_buffer: &[u8],
+        red_buffer: &[u8],
+        mode: RefreshMode,
+    ) -> DisplayResult<I, PendingUpdate> {
+        let explicit_red = self.write_frame(black_buffer, red_buffer, mode, true)?;
+        let sync_red = mode == RefreshMode::Fast && !explicit_red;
+        let display_mode = self.refresh_sequence(mode, false, explicit_red || sync_red)?;
+        // Powered on only once the refresh has been seen to finish
+        self.power = PowerState::Unknown;
+        self.trigger(display_mode)?;
+        Ok(PendingUpdate { sync_red })
+    }
+
+    /// Wait for an update started by
+    /// [`begin_update_with_mode`](Display::begin_update_with_mode) to finish
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Interface`] if there's a communication error or the
+    /// busy wait times out.
+    #[allow(clippy::needless_pass_by_value)] // consumed so each refresh is finished once
+    pub fn finish_update<D: DelayNs>(
+        &mut self,
+        pending: PendingUpdate,
+        black_buffer: &[u8],
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        let result = self.interface.busy_wait(delay).map_err(Error::Interface);
+        self.power = if result.is_ok() {
+            PowerState::On
+        } else {
+            PowerState::Unknown
+        };
+        result?;
+        if pending.sync_red {
+            self.sync_red_ram(black_buffer)?;
+        }
+        Ok(())
+    }
+
     /// Update display with specified refresh mode without loading built-in LUTs
     ///
     /// Useful for panels that rely on OTP LUTs with specific update control values.
@@ -232,7 +400,8 @@ where
     /// Update display with specified refresh mode and custom LUT
     ///
     /// Loads the provided LUT before refreshing, and does not overwrite it
-    /// with built-in LUTs.
+    /// with built-in LUTs. The LUT is validated first according to
+    /// [`Config::lut_check`].
     pub fn update_with_custom_lut<D: DelayNs>(
         &mut self,
         black_buffer: &[u8],
@@ -241,6 +410,7 @@ where
         lut: &[u8],
         delay: &mut D,
     ) -> DisplayResult<I> {
+        self.check_lut(lut)?;
         self.load_lut(lut)?;
         self.update_with_mode_internal(black_buffer, red_buffer, mode, delay, false)
     }
@@ -268,17 +438,436 @@ where
     /// Update a specific region of the display using a custom LUT
     ///
     /// Loads the provided LUT before refreshing, and does not overwrite it
-    /// with built-in LUTs.
+    /// with built-in LUTs. The LUT is validated first according to
+    /// [`Config::lut_check`].
     pub fn update_region_with_custom_lut<D: DelayNs>(
         &mut self,
         update: UpdateRegion<'_>,
         lut: &[u8],
         delay: &mut D,
     ) -> DisplayResult<I> {
+        self.check_lut(lut)?;
         self.load_lut(lut)?;
         self.update_region_internal(update, delay, false)
     }
 
+    /// Update the display from run-length encoded planes
+    ///
+    /// The planes (see [`crate::rle`]) are decoded straight into
+    /// WRITE_RAM_BW/WRITE_RAM_RED in chunks of [`STREAM_CHUNK_SIZE`] bytes, so
+    /// stored images can be shown without a framebuffer in RAM. The red plane
+    /// follows the same rules as in [`Display::update_with_mode`]: an empty or
+    /// all-zero plane bypasses red.
+    ///
+    /// Both planes are validated before anything is sent. Returns
+    /// [`Error::InvalidRle`] for malformed data and [`Error::BufferTooSmall`]
+    /// if a plane decodes to fewer bytes than the panel needs.
+    pub fn update_compressed<D: DelayNs>(
+        &mut self,
+        image: CompressedImage<'_>,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        let region = Region::new(
+            0,
+            0,
+            self.config.dimensions.cols,
+            self.config.dimensions.rows,
+        );
+        self.update_compressed_internal(region, image, mode, delay)
+    }
+
+    /// Update a region of the display from run-length encoded planes
+    ///
+    /// Like [`Display::update_compressed`], with planes covering only `region`.
+    pub fn update_region_compressed<D: DelayNs>(
+        &mut self,
+        region: Region,
+        image: CompressedImage<'_>,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        self.update_compressed_internal(region, image, mode, delay)
+    }
+
+    /// Update the display one horizontal band at a time
+    ///
+    /// For MCUs without room for full-size buffers. `black_band` (and
+    /// `red_band` for tri-color images) hold as many whole panel rows as fit;
+    /// the panel is split into bands of that many rows. For each band, both
+    /// buffers are reset to white, `render` is called with the band's region
+    /// and buffers (laid out like the matching rows of a full buffer), and the
+    /// result is written into the band's RAM window. One refresh follows the
+    /// last band.
+    ///
+    /// Pass an empty `red_band` for BW-only images. The red plane then follows
+    /// the same rules as an empty red buffer in [`Display::update_with_mode`];
+    /// in [`RefreshMode::Fast`] the bands are rendered a second time after the
+    /// refresh to bring the RED RAM up to date.
+    ///
+    /// Bands are written row by row, so Y-major data entry modes (bit 2 of
+    /// [`Config::data_entry_mode`]) are not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedDataEntryMode`] for Y-major data entry
+    /// modes, and [`Error::BufferTooSmall`] if `black_band` cannot hold one
+    /// row, or a non-empty `red_band` is shorter than `black_band`'s whole
+    /// rows.
+    pub fn update_banded<D, F>(
+        &mut self,
+        black_band: &mut [u8],
+        red_band: &mut [u8],
+        mode: RefreshMode,
+        delay: &mut D,
+        mut render: F,
+    ) -> DisplayResult<I>
+    where
+        D: DelayNs,
+        F: FnMut(Region, &mut [u8], &mut [u8]),
+    {
+        self.check_row_major()?;
+        let row_size = self.config.dimensions.cols as usize / 8;
+        let band_rows = (black_band.len() / row_size).min(self.config.dimensions.rows as usize);
+        if band_rows == 0 {
+            return Err(Error::BufferTooSmall {
+                required: row_size,
+                provided: black_band.len(),
+            });
+        }
+        let tri_color = !red_band.is_empty();
+        if tri_color && red_band.len() < band_rows * row_size {
+            return Err(Error::BufferTooSmall {
+                required: band_rows * row_size,
+                provided: red_band.len(),
+            });
+        }
+        let red_band = if tri_color {
+            &mut red_band[..band_rows * row_size]
+        } else {
+            red_band
+        };
+        let black_band = &mut black_band[..band_rows * row_size];
+
+        let pass = if tri_color {
+            BandPass::TriColor
+        } else if mode == RefreshMode::Fast {
+            BandPass::BlackOnly
+        } else {
+            BandPass::BlackToBoth
+        };
+        self.unscroll()?;
+        self.load_mode_lut(mode)?;
+        self.write_bands(black_band, red_band, pass, &mut render)?;
+        self.refresh_with_mode(mode, delay, false, pass != BandPass::BlackToBoth)?;
+        if pass == BandPass::BlackOnly {
+            self.write_bands(black_band, red_band, BandPass::BlackToRed, &mut render)?;
+        }
+        Ok(())
+    }
+
+    /// Reject Y-major data entry modes for writers that send whole rows
+    fn check_row_major(&self) -> DisplayResult<I> {
+        let mode = self.config.data_entry_mode;
+        if mode & 0x04 != 0 {
+            return Err(Error::UnsupportedDataEntryMode(mode));
+        }
+        Ok(())
+    }
+
+    /// Render every band into the buffers and write it to RAM
+    fn write_bands<F>(
+        &mut self,
+        black_band: &mut [u8],
+        red_band: &mut [u8],
+        pass: BandPass,
+        render: &mut F,
+    ) -> DisplayResult<I>
+    where
+        F: FnMut(Region, &mut [u8], &mut [u8]),
+    {
+        let Dimensions { rows, cols } = self.config.dimensions;
+        let band_rows = (black_band.len() / (cols as usize / 8)) as u16;
+        let y_increments = (self.config.data_entry_mode & 0x02) != 0;
+        for y in (0..rows).step_by(band_rows as usize) {
+            let band = Region::new(0, y, cols, band_rows.min(rows - y));
+            let size = band.buffer_size();
+            let black = &mut black_band[..size];
+            let red = red_band.get_mut(..size).unwrap_or_default();
+            black.fill(0xFF);
+            red.fill(0x00);
+            render(band, black, red);
+
+            let (black_ram, red_ram) = match pass {
+                BandPass::TriColor => (Some(&*black), Some(&*red)),
+                BandPass::BlackToBoth => (Some(&*black), Some(&*black)),
+                BandPass::BlackOnly => (Some(&*black), None),
+                BandPass::BlackToRed => (None, Some(&*black)),
+            };
+            // Place the band where its rows land in a full-window write
+            let ram_y = if y_increments != self.config.ram_y_inverted {
+                band.y
+            } else {
+                rows - band.y - band.h
+            };
+            self.set_ram_area(band.x, ram_y, band.w, band.h)?;
+            if let Some(data) = black_ram {
+                self.send_command(WRITE_RAM_BW)?;
+                self.send_data(data)?;
+            }
+            if let Some(data) = red_ram {
+                self.send_command(WRITE_RAM_RED)?;
+                self.send_data(data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Buffer row currently shown at the top of the panel
+    ///
+    /// See [`Display::set_scroll_offset`].
+    pub fn scroll_offset(&self) -> u16 {
+        self.scroll_offset
+    }
+
+    /// Rotate the image vertically so that buffer row `row` is shown at the top
+    ///
+    /// Sets the gate scan start position; rows past the last one wrap around
+    /// to row 0. Nothing is refreshed, so the shift shows up with the next
+    /// update. Rows are panel (gate) rows, which run along the drawing X axis
+    /// in [`crate::Rotation::Rotate90`] and `Rotate270`.
+    ///
+    /// Full-frame, region, compressed and banded updates write buffer row 0
+    /// to the top of RAM, so they set the offset back to 0 first. Outside the
+    /// updated region, the RAM left by a scroll then shows unshifted.
+    /// [`Display::reset`] also clears the offset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidRamArea`] if `row` is past the last panel row.
+    pub fn set_scroll_offset(&mut self, row: u16) -> DisplayResult<I> {
+        let Dimensions { rows, cols } = self.config.dimensions;
+        if row >= rows {
+            return Err(Error::InvalidRamArea {
+                x: 0,
+                y: row,
+                w: cols,
+                h: 1,
+            });
+        }
+        // RAM row 0 goes to gate `start`; with Y incrementing, buffer row 0
+        // sits in RAM row 0 and the rows have to move the other way
+        let y_increments = (self.config.data_entry_mode & 0x02) != 0;
+        let start = if y_increments {
+            (rows - row) % rows
+        } else {
+            row
+        };
+        self.send_command(GATE_SCAN_START)?;
+        self.send_data(&start.to_le_bytes())?;
+        self.scroll_offset = row;
+        Ok(())
+    }
+
+    /// Bring buffer row 0 back to the top before writing a frame from it
+    fn unscroll(&mut self) -> DisplayResult<I> {
+        if self.scroll_offset == 0 {
+            return Ok(());
+        }
+        self.set_scroll_offset(0)
+    }
+
+    /// Write whole buffer rows to RAM starting at buffer row `row`
+    ///
+    /// `black` (and `red`, when not empty) hold whole panel rows laid out like
+    /// the matching rows of a full buffer. Rows past the last panel row wrap
+    /// around to row 0, which together with [`Display::set_scroll_offset`]
+    /// lets the RAM act as a ring buffer for a taller image. With an empty
+    /// `red`, the black rows are written to the RED RAM as well.
+    ///
+    /// Nothing is refreshed. As with [`Display::update_banded`], Y-major data
+    /// entry modes are not supported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedDataEntryMode`] for Y-major data entry
+    /// modes, [`Error::InvalidRamArea`] if `row` is past the last panel row,
+    /// or `black` does not hold whole rows or holds more rows than the panel,
+    /// and [`Error::BufferTooSmall`] if a non-empty `red` is shorter than
+    /// `black`.
+    pub fn write_rows(&mut self, row: u16, black: &[u8], red: &[u8]) -> DisplayResult<I> {
+        self.check_row_major()?;
+        let Dimensions { rows, cols } = self.config.dimensions;
+        let row_size = cols as usize / 8;
+        let count = black.len() / row_size;
+        if row >= rows || black.len() % row_size != 0 || count > rows as usize {
+            return Err(Error::InvalidRamArea {
+                x: 0,
+                y: row,
+                w: cols,
+                h: u16::try_from(count).unwrap_or(u16::MAX),
+            });
+        }
+        if !red.is_empty() && red.len() < black.len() {
+            return Err(Error::BufferTooSmall {
+                required: black.len(),
+                provided: red.len(),
+            });
+        }
+        let red = if red.is_empty() {
+            black
+        } else {
+            &red[..black.len()]
+        };
+
+        // At most two runs: up to the last row, then from row 0
+        let first = count.min((rows - row) as usize) * row_size;
+        self.write_row_run(row, &black[..first], &red[..first])?;
+        self.write_row_run(0, &black[first..], &red[first..])
+    }
+
+    /// Write a run of rows that does not wrap
+    fn write_row_run(&mut self, row: u16, black: &[u8], red: &[u8]) -> DisplayResult<I> {
+        if black.is_empty() {
+            return Ok(());
+        }
+        let Dimensions { rows, cols } = self.config.dimensions;
+        let count = (black.len() / (cols as usize / 8)) as u16;
+        // Place the run where its rows land in a full-window write
+        let y_increments = (self.config.data_entry_mode & 0x02) != 0;
+        let ram_y = if y_increments != self.config.ram_y_inverted {
+            row
+        } else {
+            rows - row - count
+        };
+        self.set_ram_area(0, ram_y, cols, count)?;
+        self.send_command(WRITE_RAM_BW)?;
+        self.send_data(black)?;
+        self.send_command(WRITE_RAM_RED)?;
+        self.send_data(red)
+    }
+
+    /// Show the panel-sized view of a taller canvas starting at canvas row `top`
+    ///
+    /// Canvas row `c` is kept in buffer row `c % rows`, and the view is
+    /// brought to the top of the panel with [`Display::set_scroll_offset`],
+    /// so later [`Display::pan_canvas`] calls only have to write the rows
+    /// that scroll into view.
+    ///
+    /// Give the canvas a red plane on every call or on none. Without it the
+    /// refresh bypasses red, also in [`RefreshMode::Fast`]: scrolling moves
+    /// the previous image, so it cannot serve as the base of a differential
+    /// refresh.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedDataEntryMode`] for Y-major data entry
+    /// modes, and [`Error::BufferTooSmall`] if a plane of `canvas` ends before
+    /// the view does.
+    pub fn update_canvas<D: DelayNs>(
+        &mut self,
+        canvas: ScrollCanvas<'_>,
+        top: u16,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        let ScrollCanvas { black, red } = canvas;
+        self.check_row_major()?;
+        self.check_canvas(black, red, top)?;
+        self.write_canvas_rows(black, red, top.into(), self.config.dimensions.rows)?;
+        self.refresh_canvas(!red.is_empty(), top, mode, delay)
+    }
+
+    /// Pan the view of a canvas from canvas row `from` to canvas row `to`
+    ///
+    /// The canvas must be showing at `from`, as left by
+    /// [`Display::update_canvas`] or a previous pan. Only the rows that
+    /// scroll into view are written; a jump of a full panel height or more
+    /// rewrites the whole view. Panning to the current row does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedDataEntryMode`] for Y-major data entry
+    /// modes, and [`Error::BufferTooSmall`] if a plane ends before the view
+    /// at `to` does.
+    pub fn pan_canvas<D: DelayNs>(
+        &mut self,
+        canvas: ScrollCanvas<'_>,
+        from: u16,
+        to: u16,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        let ScrollCanvas { black, red } = canvas;
+        self.check_row_major()?;
+        let rows = self.config.dimensions.rows;
+        let distance = from.abs_diff(to);
+        if distance == 0 {
+            return Ok(());
+        }
+        if distance >= rows {
+            return self.update_canvas(canvas, to, mode, delay);
+        }
+        self.check_canvas(black, red, to)?;
+        if to > from {
+            self.write_canvas_rows(black, red, usize::from(from) + usize::from(rows), distance)?;
+        } else {
+            self.write_canvas_rows(black, red, to.into(), distance)?;
+        }
+        self.refresh_canvas(!red.is_empty(), to, mode, delay)
+    }
+
+    /// Check that both canvas planes cover the view starting at `top`
+    fn check_canvas(&self, black: &[u8], red: &[u8], top: u16) -> DisplayResult<I> {
+        let Dimensions { rows, cols } = self.config.dimensions;
+        let required = (top as usize + rows as usize) * (cols as usize / 8);
+        if black.len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                provided: black.len(),
+            });
+        }
+        if !red.is_empty() && red.len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                provided: red.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Write `count` checked canvas rows starting at canvas row `first`
+    fn write_canvas_rows(
+        &mut self,
+        black: &[u8],
+        red: &[u8],
+        first: usize,
+        count: u16,
+    ) -> DisplayResult<I> {
+        let Dimensions { rows, cols } = self.config.dimensions;
+        let row_size = cols as usize / 8;
+        let range = first * row_size..(first + count as usize) * row_size;
+        let red = if red.is_empty() {
+            red
+        } else {
+            &red[range.clone()]
+        };
+        self.write_rows((first % rows as usize) as u16, &black[range], red)
+    }
+
+    /// Scroll the canvas view starting at `top` into place and refresh
+    fn refresh_canvas<D: DelayNs>(
+        &mut self,
+        tri_color: bool,
+        top: u16,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        self.set_scroll_offset(top % self.config.dimensions.rows)?;
+        self.load_mode_lut(mode)?;
+        self.refresh_with_mode(mode, delay, false, tri_color)
+    }
+
     /// Full refresh with all pixels
     pub fn full_refresh<D: DelayNs>(&mut self, delay: &mut D) -> DisplayResult<I> {
         self.refresh_with_mode(RefreshMode::Full, delay, false, false)
@@ -297,6 +886,24 @@ where
         turn_off: bool,
         use_red: bool,
     ) -> DisplayResult<I> {
+        let display_mode = self.refresh_sequence(mode, turn_off, use_red)?;
+        let result = self.activate(display_mode, delay);
+        // A failed sequence may have stopped before or after the power steps
+        self.power = match result {
+            Ok(()) if turn_off => PowerState::Off,
+            Ok(()) => PowerState::On,
+            Err(_) => PowerState::Unknown,
+        };
+        result
+    }
+
+    /// Select the RAM planes to refresh from and build the update sequence
+    fn refresh_sequence(
+        &mut self,
+        mode: RefreshMode,
+        turn_off: bool,
+        use_red: bool,
+    ) -> DisplayResult<I, u8> {
         self.send_command(DISPLAY_UPDATE_CTRL1)?;
         let ctrl1 = if use_red {
             CTRL1_NORMAL
@@ -309,27 +916,34 @@ where
             RefreshMode::Full => self.config.display_update_ctrl2_full,
             RefreshMode::Partial => self.config.display_update_ctrl2_partial,
             RefreshMode::Fast => self.config.display_update_ctrl2_fast,
+            RefreshMode::Otp(otp) => {
+                (self.config.display_update_ctrl2_full & !CTRL2_DISPLAY_MODE_2)
+                    | CTRL2_LOAD_TEMPERATURE
+                    | CTRL2_LOAD_LUT
+                    | otp.ctrl2_bits()
+            }
         };
 
-        if !self.is_display_on {
+        if self.power != PowerState::On {
             display_mode |= self.config.display_update_power_on;
         }
-
         if turn_off {
             display_mode |= self.config.display_update_power_off;
-            self.is_display_on = false;
-        } else {
-            self.is_display_on = true;
         }
+        Ok(display_mode)
+    }
 
-        self.send_command(DISPLAY_UPDATE_CTRL2)?;
-        self.send_data(&[display_mode])?;
-
-        self.send_command(MASTER_ACTIVATION)?;
-
-        self.interface.busy_wait(delay).map_err(Error::Interface)?;
+    /// Send a Display Update Control 2 sequence and wait for it to finish
+    fn activate<D: DelayNs>(&mut self, sequence: u8, delay: &mut D) -> DisplayResult<I> {
+        self.trigger(sequence)?;
+        self.interface.busy_wait(delay).map_err(Error::Interface)
+    }
 
-        Ok(())
+    /// Send a Display Update Control 2 sequence and start it
+    fn trigger(&mut self, sequence: u8) -> DisplayResult<I> {
+        self.send_command(DISPLAY_UPDATE_CTRL2)?;
+        self.send_data(&[sequence])?;
+        self.send_command(MASTER_ACTIVATION)
     }
 
     /// Enter deep sleep mode
@@ -346,18 +960,15 @@ where
         delay: &mut D,
         mode: DeepSleepMode,
     ) -> DisplayResult<I> {
-        if self.is_display_on {
+        if self.power != PowerState::Off {
             // Power down first
-            self.send_command(DISPLAY_UPDATE_CTRL1)?;
-            self.send_data(&[CTRL1_BYPASS_RED])?;
-
-            self.send_command(DISPLAY_UPDATE_CTRL2)?;
-            self.send_data(&[0x03])?; // Power down
-
-            self.send_command(MASTER_ACTIVATION)?;
-            self.interface.busy_wait(delay).map_err(Error::Interface)?;
-
-            self.is_display_on = false;
+            let result = self.power_down(delay);
+            self.power = if result.is_ok() {
+                PowerState::Off
+            } else {
+                PowerState::Unknown
+            };
+            result?;
         }
 
         // Enter deep sleep
@@ -367,10 +978,17 @@ where
         Ok(())
     }
 
+    /// Switch off the analog supplies and the clock
+    fn power_down<D: DelayNs>(&mut self, delay: &mut D) -> DisplayResult<I> {
+        self.send_command(DISPLAY_UPDATE_CTRL1)?;
+        self.send_data(&[CTRL1_BYPASS_RED])?;
+        self.activate(CTRL2_DISABLE_ANALOG | CTRL2_DISABLE_CLOCK, delay)
+    }
+
     /// LUT size required by SSD1677 controller
-    pub const LUT_SIZE: usize = 112;
+    pub const LUT_SIZE: usize = crate::lut::LUT_SIZE;
     /// Short LUT size used by some panels (requires separate voltage settings)
-    pub const LUT_SHORT_SIZE: usize = 105;
+    pub const LUT_SHORT_SIZE: usize = crate::lut::LUT_SHORT_SIZE;
 
     /// Load custom LUT (112 bytes for SSD1677)
     ///
@@ -390,6 +1008,21 @@ where
         Ok(())
     }
 
+    /// Reload the LUT register from OTP
+    ///
+    /// Restores the controller's built-in waveform for the given display mode
+    /// after [`load_lut`](Self::load_lut) has replaced it. The temperature is
+    /// loaded first so the OTP selects the matching temperature range.
+    pub fn load_otp_lut<D: DelayNs>(&mut self, mode: OtpMode, delay: &mut D) -> DisplayResult<I> {
+        let mut sequence =
+            CTRL2_ENABLE_CLOCK | CTRL2_LOAD_TEMPERATURE | CTRL2_LOAD_LUT | mode.ctrl2_bits();
+        if self.power != PowerState::On {
+            sequence |= CTRL2_DISABLE_CLOCK;
+        }
+
+        self.activate(sequence, delay)
+    }
+
     /// Load a shortened LUT and set voltage registers separately
     ///
     /// This supports panels that use a 105-byte LUT plus separate gate/source/VCOM settings.
@@ -414,6 +1047,107 @@ where
         Ok(())
     }
 
+    /// Validate a custom LUT according to [`Config::lut_check`]
+    ///
+    /// LUTs of the wrong length are left for [`load_lut`](Self::load_lut) to reject.
+    fn check_lut(&self, lut: &[u8]) -> DisplayResult<I> {
+        if self.config.lut_check == LutCheck::Off {
+            return Ok(());
+        }
+        let Ok(bytes) = <&[u8; crate::lut::LUT_SIZE]>::try_from(lut) else {
+            return Ok(());
+        };
+
+        let waveform = Waveform::from_bytes(bytes);
+        waveform.for_each_issue(|issue| log::warn!("custom LUT: {issue}"));
+
+        let analysis = waveform.analyze();
+        log::debug!(
+            "custom LUT: {} frames, ~{} ms",
+            analysis.total_frames,
+            analysis.duration_ms
+        );
+        match analysis.first_error {
+            Some(issue) if self.config.lut_check == LutCheck::Reject => {
+                Err(Error::InvalidWaveform(issue))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Report the ambient temperature used to pick partial/fast LUTs
+    ///
+    /// Pass `None` to fall back to the built-in tables.
+    pub fn set_temperature(&mut self, celsius: Option<i16>) {
+        self.temperature = celsius;
+    }
+
+    /// Refresh the ambient temperature from a [`TemperatureSource`]
+    pub fn update_temperature<T: TemperatureSource>(&mut self, source: &mut T) {
+        self.temperature = source.temperature_celsius();
+    }
+
+    /// Get the last reported ambient temperature in °C
+    pub fn temperature(&self) -> Option<i16> {
+        self.temperature
+    }
+
+    /// Find the configured LUT for a refresh mode at the current temperature
+    fn lut_entry_for(&self, mode: RefreshMode) -> Option<&'static LutEntry> {
+        let set = match mode {
+            RefreshMode::Full | RefreshMode::Otp(_) => None,
+            RefreshMode::Partial => self.config.partial_lut_set,
+            RefreshMode::Fast => self.config.fast_lut_set,
+        }?;
+        set.select(self.temperature?)
+    }
+
+    /// Load the built-in or temperature-selected LUT for a refresh mode
+    ///
+    /// Voltages programmed for one [`LutEntry`] are reset to the power-on gate and
+    /// source voltages and the configured VCOM once a LUT without voltages is used.
+    fn load_mode_lut(&mut self, mode: RefreshMode) -> DisplayResult<I> {
+        let entry = self.lut_entry_for(mode);
+        match (entry, mode) {
+            (Some(entry), _) => self.load_lut(entry.lut)?,
+            (None, RefreshMode::Full | RefreshMode::Otp(_)) => {}
+            (None, RefreshMode::Partial) => self.load_lut(&LUT_PARTIAL)?,
+            (None, RefreshMode::Fast) => self.load_lut(&LUT_FAST)?,
+        }
+
+        if let Some(voltages) = entry.and_then(|entry| entry.voltages) {
+            self.set_gate_voltage(voltages.vgh)?;
+            self.set_source_voltage(voltages.source())?;
+            self.set_vcom(voltages.vcom)?;
+            self.lut_voltages = true;
+        } else if self.lut_voltages {
+            self.set_gate_voltage(GATE_VOLTAGE_DEFAULT)?;
+            self.set_source_voltage(SOURCE_VOLTAGE_DEFAULT)?;
+            self.set_vcom(self.config.vcom)?;
+            self.lut_voltages = false;
+        }
+        Ok(())
+    }
+
+    /// Load a typed waveform as a full 112-byte LUT
+    pub fn load_waveform(&mut self, waveform: &Waveform) -> DisplayResult<I> {
+        self.load_lut(&waveform.to_bytes())
+    }
+
+    /// Load a typed waveform as a short LUT and program its voltages separately
+    ///
+    /// Equivalent to [`load_lut_with_voltages`](Self::load_lut_with_voltages) with the
+    /// voltages taken from [`Waveform::voltages`].
+    pub fn load_waveform_with_voltages(&mut self, waveform: &Waveform) -> DisplayResult<I> {
+        let voltages = waveform.voltages;
+        self.load_lut_with_voltages(
+            &waveform.to_short_bytes(),
+            voltages.vgh,
+            voltages.source(),
+            voltages.vcom,
+        )
+    }
+
     /// Set gate driving voltage (VGH)
     pub fn set_gate_voltage(&mut self, voltage: u8) -> DisplayResult<I> {
         self.send_command(GATE_VOLTAGE)?;
@@ -539,7 +1273,29 @@ where
         &self.config
     }
 
-    fn update_with_mode_internal<D: DelayNs>(
+    /// Whether the driver knows the display's analog power to be on
+    ///
+    /// After an update or deep sleep fails part-way the power state is
+    /// unknown and this returns `false`: the next refresh then includes the
+    /// power-on steps and the next deep sleep powers down first.
+    pub fn is_display_on(&self) -> bool {
+        self.power == PowerState::On
+    }
+
+    /// Access the underlying interface
+    pub fn interface(&self) -> &I {
+        &self.interface
+    }
+
+    /// Mutably access the underlying interface
+    ///
+    /// Commands sent directly through the interface bypass the driver's
+    /// state tracking (e.g. whether the display is powered on).
+    pub fn interface_mut(&mut self) -> &mut I {
+        &mut self.interface
+    }
+
+    fn update_with_mode_internal<D: DelayNs>(
         &mut self,
         black_buffer: &[u8],
         red_buffer: &[u8],
@@ -547,12 +1303,30 @@ where
         delay: &mut D,
         use_builtin_lut: bool,
     ) -> DisplayResult<I> {
-        let explicit_red = !red_buffer.is_empty() && red_buffer.iter().any(|byte| *byte != 0);
+        let explicit_red = self.write_frame(black_buffer, red_buffer, mode, use_builtin_lut)?;
+        // A BW-only fast refresh is differential against the previous image in RED RAM
         let single_buffer_fast = mode == RefreshMode::Fast && !explicit_red;
+        self.refresh_with_mode(mode, delay, false, explicit_red || single_buffer_fast)?;
+        if single_buffer_fast {
+            self.sync_red_ram(black_buffer)?;
+        }
+        Ok(())
+    }
+
+    /// Write a full frame to RAM ahead of a refresh
+    ///
+    /// Returns whether the red plane is used.
+    fn write_frame(
+        &mut self,
+        black_buffer: &[u8],
+        red_buffer: &[u8],
+        mode: RefreshMode,
+        use_builtin_lut: bool,
+    ) -> DisplayResult<I, bool> {
+        let explicit_red = !red_buffer.is_empty() && red_buffer.iter().any(|byte| *byte != 0);
         // Keep RED RAM in sync for the next differential fast refresh even when the caller
         // only provides a BW buffer.
         let sync_red_before_refresh = mode != RefreshMode::Fast && !explicit_red;
-        let use_red_for_refresh = explicit_red || single_buffer_fast;
         let expected_size = self.config.dimensions.buffer_size();
 
         if black_buffer.len() < expected_size {
@@ -568,12 +1342,9 @@ where
             });
         }
 
+        self.unscroll()?;
         if use_builtin_lut {
-            match mode {
-                RefreshMode::Full => {}
-                RefreshMode::Partial => self.load_lut(&LUT_PARTIAL)?,
-                RefreshMode::Fast => self.load_lut(&LUT_FAST)?,
-            }
+            self.load_mode_lut(mode)?;
         }
 
         self.set_ram_area(
@@ -593,21 +1364,26 @@ where
             self.send_command(WRITE_RAM_RED)?;
             self.send_data(&black_buffer[..expected_size])?;
         }
+        Ok(explicit_red)
+    }
 
-        self.refresh_with_mode(mode, delay, false, use_red_for_refresh)?;
-
-        if single_buffer_fast {
-            self.set_ram_area(
-                0,
-                0,
-                self.config.dimensions.cols,
-                self.config.dimensions.rows,
-            )?;
-            self.send_command(WRITE_RAM_RED)?;
-            self.send_data(&black_buffer[..expected_size])?;
+    /// Copy the BW image to RED RAM after a differential fast refresh
+    fn sync_red_ram(&mut self, black_buffer: &[u8]) -> DisplayResult<I> {
+        let expected_size = self.config.dimensions.buffer_size();
+        if black_buffer.len() < expected_size {
+            return Err(Error::BufferTooSmall {
+                required: expected_size,
+                provided: black_buffer.len(),
+            });
         }
-
-        Ok(())
+        self.set_ram_area(
+            0,
+            0,
+            self.config.dimensions.cols,
+            self.config.dimensions.rows,
+        )?;
+        self.send_command(WRITE_RAM_RED)?;
+        self.send_data(&black_buffer[..expected_size])
     }
 
     fn update_region_internal<D: DelayNs>(
@@ -636,12 +1412,9 @@ where
             });
         }
 
+        self.unscroll()?;
         if use_builtin_lut {
-            match update.mode {
-                RefreshMode::Full => {}
-                RefreshMode::Partial => self.load_lut(&LUT_PARTIAL)?,
-                RefreshMode::Fast => self.load_lut(&LUT_FAST)?,
-            }
+            self.load_mode_lut(update.mode)?;
         }
 
         self.set_ram_area(
@@ -677,14 +1450,84 @@ where
 
         Ok(())
     }
+
+    fn update_compressed_internal<D: DelayNs>(
+        &mut self,
+        region: Region,
+        image: CompressedImage<'_>,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> DisplayResult<I> {
+        let (black_size, _) = rle::scan(image.black).map_err(Error::InvalidRle)?;
+        let (red_size, explicit_red) = rle::scan(image.red).map_err(Error::InvalidRle)?;
+        let single_buffer_fast = mode == RefreshMode::Fast && !explicit_red;
+        let sync_red_before_refresh = mode != RefreshMode::Fast && !explicit_red;
+        let use_red_for_refresh = explicit_red || single_buffer_fast;
+        let expected_size = region.buffer_size();
+
+        if black_size < expected_size {
+            return Err(Error::BufferTooSmall {
+                required: expected_size,
+                provided: black_size,
+            });
+        }
+        if explicit_red && red_size < expected_size {
+            return Err(Error::BufferTooSmall {
+                required: expected_size,
+                provided: red_size,
+            });
+        }
+
+        self.unscroll()?;
+        self.load_mode_lut(mode)?;
+        self.set_ram_area(region.x, region.y, region.w, region.h)?;
+        self.stream_ram(WRITE_RAM_BW, image.black, expected_size)?;
+
+        if explicit_red {
+            self.stream_ram(WRITE_RAM_RED, image.red, expected_size)?;
+        } else if sync_red_before_refresh {
+            self.stream_ram(WRITE_RAM_RED, image.black, expected_size)?;
+        }
+
+        self.refresh_with_mode(mode, delay, false, use_red_for_refresh)?;
+
+        if single_buffer_fast {
+            self.set_ram_area(region.x, region.y, region.w, region.h)?;
+            self.stream_ram(WRITE_RAM_RED, image.black, expected_size)?;
+        }
+
+        Ok(())
+    }
+
+    /// Decode the first `size` bytes of an encoded plane into a RAM plane
+    fn stream_ram(&mut self, command: u8, encoded: &[u8], size: usize) -> DisplayResult<I> {
+        self.send_command(command)?;
+        let mut decoder = Decoder::new(encoded);
+        let mut chunk = [0u8; STREAM_CHUNK_SIZE];
+        let mut remaining = size;
+        while remaining > 0 {
+            let len = remaining.min(STREAM_CHUNK_SIZE);
+            let count = decoder.read(&mut chunk[..len]).map_err(Error::InvalidRle)?;
+            if count == 0 {
+                break;
+            }
+            self.send_data(&chunk[..count])?;
+            remaining -= count;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::TestResult;
     use crate::config::{Builder, Dimensions};
+    use crate::emulator::Emulator;
+    use alloc::vec::Vec;
 
     #[derive(Debug)]
+    #[allow(clippy::type_complexity)]
     struct MockInterface {
         commands: alloc::vec::Vec<u8>,
         data: alloc::vec::Vec<alloc::vec::Vec<u8>>,
@@ -732,53 +1575,59 @@ mod tests {
         fn delay_ns(&mut self, _ns: u32) {}
     }
 
-    fn test_display() -> Display<MockInterface> {
+    type MockDisplay = Display<MockInterface>;
+
+    fn test_display() -> TestResult<MockDisplay> {
         let interface = MockInterface::new();
         let config = Builder::new()
-            .dimensions(Dimensions::new(480, 480).unwrap())
-            .build()
-            .unwrap();
-        Display::new(interface, config)
+            .dimensions(Dimensions::new(480, 480)?)
+            .build()?;
+        Ok(Display::new(interface, config))
     }
 
     #[test]
-    fn test_set_ram_area_zero_width_returns_error() {
-        let mut display = test_display();
+    fn test_set_ram_area_zero_width_returns_error() -> TestResult {
+        let mut display = test_display()?;
         let result = display.set_ram_area(0, 0, 0, 100);
         assert!(matches!(result, Err(Error::InvalidRamArea { w: 0, .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_set_ram_area_zero_height_returns_error() {
-        let mut display = test_display();
+    fn test_set_ram_area_zero_height_returns_error() -> TestResult {
+        let mut display = test_display()?;
         let result = display.set_ram_area(0, 0, 100, 0);
         assert!(matches!(result, Err(Error::InvalidRamArea { h: 0, .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_set_ram_area_out_of_bounds_x_returns_error() {
-        let mut display = test_display();
+    fn test_set_ram_area_out_of_bounds_x_returns_error() -> TestResult {
+        let mut display = test_display()?;
         let result = display.set_ram_area(400, 0, 100, 100);
         assert!(matches!(result, Err(Error::InvalidRamArea { .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_set_ram_area_out_of_bounds_y_returns_error() {
-        let mut display = test_display();
+    fn test_set_ram_area_out_of_bounds_y_returns_error() -> TestResult {
+        let mut display = test_display()?;
         let result = display.set_ram_area(0, 400, 100, 100);
         assert!(matches!(result, Err(Error::InvalidRamArea { .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_set_ram_area_valid_succeeds() {
-        let mut display = test_display();
+    fn test_set_ram_area_valid_succeeds() -> TestResult {
+        let mut display = test_display()?;
         let result = display.set_ram_area(0, 0, 480, 480);
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
-    fn test_load_lut_wrong_length_returns_error() {
-        let mut display = test_display();
+    fn test_load_lut_wrong_length_returns_error() -> TestResult {
+        let mut display = test_display()?;
         let short_lut = [0u8; 50];
         let result = display.load_lut(&short_lut);
         assert!(matches!(
@@ -788,11 +1637,12 @@ mod tests {
                 provided: 50
             })
         ));
+        Ok(())
     }
 
     #[test]
-    fn test_load_lut_too_long_returns_error() {
-        let mut display = test_display();
+    fn test_load_lut_too_long_returns_error() -> TestResult {
+        let mut display = test_display()?;
         let long_lut = [0u8; 200];
         let result = display.load_lut(&long_lut);
         assert!(matches!(
@@ -802,71 +1652,402 @@ mod tests {
                 provided: 200
             })
         ));
+        Ok(())
     }
 
     #[test]
-    fn test_load_lut_correct_length_succeeds() {
-        let mut display = test_display();
+    fn test_load_lut_correct_length_succeeds() -> TestResult {
+        let mut display = test_display()?;
         let lut = [0u8; 112];
         let result = display.load_lut(&lut);
         assert!(result.is_ok());
+        Ok(())
+    }
+
+    fn display_with_lut_check(check: LutCheck) -> TestResult<MockDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(480, 480)?)
+            .lut_check(check)
+            .build()?;
+        Ok(Display::new(MockInterface::new(), config))
+    }
+
+    #[test]
+    fn test_custom_lut_rejected_before_sending() -> TestResult {
+        let mut display = display_with_lut_check(LutCheck::Reject)?;
+        let mut delay = MockDelay;
+        let buffer_size = display.dimensions().buffer_size();
+        let black_buf = alloc::vec![0xFFu8; buffer_size];
+        let empty_lut = [0u8; 112];
+
+        let result = display.update_with_custom_lut(
+            &black_buf,
+            &[],
+            RefreshMode::Fast,
+            &empty_lut,
+            &mut delay,
+        );
+        assert!(matches!(
+            result,
+            Err(Error::InvalidWaveform(crate::waveform::LutIssue::NoFrames))
+        ));
+        assert!(display.interface.commands.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_lut_warn_still_sends() -> TestResult {
+        let mut display = display_with_lut_check(LutCheck::Warn)?;
+        let mut delay = MockDelay;
+        let buffer_size = display.dimensions().buffer_size();
+        let black_buf = alloc::vec![0xFFu8; buffer_size];
+        let empty_lut = [0u8; 112];
+
+        let result = display.update_with_custom_lut(
+            &black_buf,
+            &[],
+            RefreshMode::Fast,
+            &empty_lut,
+            &mut delay,
+        );
+        assert!(result.is_ok());
+        assert_eq!(display.interface.commands.first(), Some(&WRITE_LUT));
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_lut_valid_waveform_passes_reject() -> TestResult {
+        let mut display = display_with_lut_check(LutCheck::Reject)?;
+        let mut delay = MockDelay;
+        let mut waveform = Waveform::default();
+        waveform.transitions[1][0][0] = crate::waveform::VoltageSelect::Vsh1;
+        waveform.timing[0].phases[0] = 4;
+        let region_size = (80 / 8) * 80;
+        let black_buf = alloc::vec![0xFFu8; region_size];
+
+        let result = display.update_region_with_custom_lut(
+            UpdateRegion {
+                region: Region::new(0, 0, 80, 80),
+                black_buffer: &black_buf,
+                red_buffer: &[],
+                mode: RefreshMode::Partial,
+            },
+            &waveform.to_bytes(),
+            &mut delay,
+        );
+        assert!(result.is_ok());
+        Ok(())
+    }
+
+    static COLD_VOLTAGES: crate::waveform::LutVoltages = crate::waveform::LutVoltages {
+        vgh: 0x17,
+        vsh1: 0x41,
+        vsh2: 0xA8,
+        vsl: 0x32,
+        vcom: 0x30,
+    };
+    static FAST_SET: [LutEntry; 2] = [
+        LutEntry::new(-20, 10, &crate::lut::LUT_CLEANUP).with_voltages(COLD_VOLTAGES),
+        LutEntry::new(10, 40, &crate::lut::LUT_GRAYSCALE),
+    ];
+
+    fn display_with_fast_set() -> TestResult<MockDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(480, 480)?)
+            .fast_lut_set(crate::lut::LutSet::new(&FAST_SET))
+            .build()?;
+        Ok(Display::new(MockInterface::new(), config))
+    }
+
+    /// Payload of the first WRITE_LUT command, or empty if none was sent
+    fn loaded_lut(display: &MockDisplay) -> &[u8] {
+        display
+            .interface
+            .command_data
+            .iter()
+            .find(|(cmd, _)| *cmd == WRITE_LUT)
+            .map_or(&[], |(_, data)| data.as_slice())
+    }
+
+    #[test]
+    fn test_fast_lut_selected_by_temperature() -> TestResult {
+        let mut display = display_with_fast_set()?;
+        let mut delay = MockDelay;
+        let black_buf = alloc::vec![0xFFu8; display.dimensions().buffer_size()];
+
+        display.update_temperature(&mut || Some(25));
+        assert_eq!(display.temperature(), Some(25));
+        let result = display.update_with_mode(&black_buf, &[], RefreshMode::Fast, &mut delay);
+        assert!(result.is_ok());
+        assert_eq!(loaded_lut(&display), crate::lut::LUT_GRAYSCALE);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cold_lut_programs_voltages() -> TestResult {
+        let mut display = display_with_fast_set()?;
+        let mut delay = MockDelay;
+        let black_buf = alloc::vec![0xFFu8; display.dimensions().buffer_size()];
+
+        display.set_temperature(Some(-5));
+        let result = display.update_with_mode(&black_buf, &[], RefreshMode::Fast, &mut delay);
+        assert!(result.is_ok());
+
+        let sent = &display.interface.command_data;
+        assert_eq!(sent[0], (WRITE_LUT, crate::lut::LUT_CLEANUP.to_vec()));
+        assert_eq!(sent[1], (GATE_VOLTAGE, alloc::vec![0x17]));
+        assert_eq!(sent[2], (SOURCE_VOLTAGE, alloc::vec![0x41, 0xA8, 0x32]));
+        assert_eq!(sent[3], (WRITE_VCOM, alloc::vec![0x30]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_voltages_restored_after_cold_lut() -> TestResult {
+        let mut display = display_with_fast_set()?;
+        let mut delay = MockDelay;
+        let black_buf = alloc::vec![0xFFu8; display.dimensions().buffer_size()];
+        let cold = [
+            (GATE_VOLTAGE, alloc::vec![0x17]),
+            (SOURCE_VOLTAGE, alloc::vec![0x41, 0xA8, 0x32]),
+            (WRITE_VCOM, alloc::vec![0x30]),
+        ];
+        let defaults = [
+            (GATE_VOLTAGE, alloc::vec![GATE_VOLTAGE_DEFAULT]),
+            (SOURCE_VOLTAGE, SOURCE_VOLTAGE_DEFAULT.to_vec()),
+            (WRITE_VCOM, alloc::vec![display.config.vcom]),
+        ];
+        let steps = [
+            (Some(-5), RefreshMode::Fast, &cold[..]),
+            // Warm entry without voltages, restored only once
+            (Some(25), RefreshMode::Fast, &defaults[..]),
+            (Some(25), RefreshMode::Fast, &[][..]),
+            // Built-in fallback outside every range
+            (Some(-5), RefreshMode::Fast, &cold[..]),
+            (Some(45), RefreshMode::Fast, &defaults[..]),
+            // Full refresh with the OTP waveform
+            (Some(-5), RefreshMode::Fast, &cold[..]),
+            (Some(-5), RefreshMode::Full, &defaults[..]),
+        ];
+
+        for (step, (celsius, mode, expected)) in steps.into_iter().enumerate() {
+            display.interface.command_data.clear();
+            display.set_temperature(celsius);
+            display.update_with_mode(&black_buf, &[], mode, &mut delay)?;
+            let written: Vec<_> = display
+                .interface
+                .command_data
+                .iter()
+                .filter(|(cmd, _)| matches!(*cmd, GATE_VOLTAGE | SOURCE_VOLTAGE | WRITE_VCOM))
+                .cloned()
+                .collect();
+            assert_eq!(written, expected, "step {step}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_lut_set_falls_back_to_builtin() -> TestResult {
+        let mut delay = MockDelay;
+
+        // No temperature reported
+        let mut display = display_with_fast_set()?;
+        let black_buf = alloc::vec![0xFFu8; display.dimensions().buffer_size()];
+        let result = display.update_with_mode(&black_buf, &[], RefreshMode::Fast, &mut delay);
+        assert!(result.is_ok());
+        assert_eq!(loaded_lut(&display), LUT_FAST);
+
+        // Temperature outside every range
+        let mut display = display_with_fast_set()?;
+        display.set_temperature(Some(45));
+        let result = display.update_with_mode(&black_buf, &[], RefreshMode::Fast, &mut delay);
+        assert!(result.is_ok());
+        assert_eq!(loaded_lut(&display), LUT_FAST);
+
+        // No set configured for partial refresh
+        let mut display = display_with_fast_set()?;
+        display.set_temperature(Some(25));
+        let result = display.update_with_mode(&black_buf, &[], RefreshMode::Partial, &mut delay);
+        assert!(result.is_ok());
+        assert_eq!(loaded_lut(&display), LUT_PARTIAL);
+        Ok(())
+    }
+
+    fn ctrl2_values(display: &Display<MockInterface>) -> Vec<u8> {
+        display
+            .interface
+            .command_data
+            .iter()
+            .filter(|(cmd, _)| *cmd == DISPLAY_UPDATE_CTRL2)
+            .flat_map(|(_, data)| data.iter().copied())
+            .collect()
     }
 
     #[test]
-    fn test_deep_sleep_mode_normal() {
-        let mut display = test_display();
+    fn test_load_otp_lut_selects_bank() -> TestResult {
+        let mut display = test_display()?;
+        let mut delay = MockDelay;
+
+        assert!(display.load_otp_lut(OtpMode::Mode1, &mut delay).is_ok());
+        assert!(display.load_otp_lut(OtpMode::Mode2, &mut delay).is_ok());
+        assert_eq!(ctrl2_values(&display), [0xB1, 0xB9]);
+        assert_eq!(
+            display.interface.commands,
+            [
+                DISPLAY_UPDATE_CTRL2,
+                MASTER_ACTIVATION,
+                DISPLAY_UPDATE_CTRL2,
+                MASTER_ACTIVATION
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_otp_lut_keeps_clock_when_display_on() -> TestResult {
+        let mut display = test_display()?;
+        let mut delay = MockDelay;
+        assert!(display.fast_refresh(&mut delay).is_ok());
+        display.interface.command_data.clear();
+
+        assert!(display.load_otp_lut(OtpMode::Mode1, &mut delay).is_ok());
+        assert_eq!(ctrl2_values(&display), [0xB0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_otp_refresh_mode_forces_lut_load_after_custom_lut() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(480, 480)?)
+            .display_update_ctrl2_full(0xC7)
+            .build()?;
+        let mut display = Display::new(MockInterface::new(), config);
+        let mut delay = MockDelay;
+        let black_buf = alloc::vec![0xFFu8; display.dimensions().buffer_size()];
+
+        let result = display.update_with_custom_lut(
+            &black_buf,
+            &[],
+            RefreshMode::Fast,
+            &LUT_FAST,
+            &mut delay,
+        );
+        assert!(result.is_ok());
+        let result = display.update_with_mode(
+            &black_buf,
+            &[],
+            RefreshMode::Otp(OtpMode::Mode1),
+            &mut delay,
+        );
+        assert!(result.is_ok());
+        let result = display.update_with_mode(
+            &black_buf,
+            &[],
+            RefreshMode::Otp(OtpMode::Mode2),
+            &mut delay,
+        );
+        assert!(result.is_ok());
+
+        // Fast (powers on), then OTP Mode 1 and Mode 2 with load-temperature/LUT bits
+        assert_eq!(ctrl2_values(&display), [0xC7 | 0xC0, 0xF7, 0xFF]);
+        let lut_writes: Vec<u8> = display
+            .interface
+            .commands
+            .iter()
+            .copied()
+            .filter(|cmd| *cmd == WRITE_LUT)
+            .collect();
+        assert_eq!(lut_writes, [WRITE_LUT]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_waveform_sends_encoded_lut() -> TestResult {
+        let mut display = test_display()?;
+        let waveform = Waveform::from_bytes(&LUT_PARTIAL);
+        assert!(display.load_waveform(&waveform).is_ok());
+        assert_eq!(
+            display.interface.command_data.last(),
+            Some(&(WRITE_LUT, LUT_PARTIAL.to_vec()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_waveform_with_voltages_splits_registers() -> TestResult {
+        let mut display = test_display()?;
+        let waveform = Waveform::from_bytes(&crate::lut::LUT_GRAYSCALE);
+        assert!(display.load_waveform_with_voltages(&waveform).is_ok());
+
+        let sent = &display.interface.command_data;
+        assert_eq!(sent.len(), 4);
+        assert_eq!(sent[0].0, WRITE_LUT);
+        assert_eq!(sent[0].1.len(), Display::<MockInterface>::LUT_SHORT_SIZE);
+        assert_eq!(sent[1], (GATE_VOLTAGE, alloc::vec![0x17]));
+        assert_eq!(sent[2], (SOURCE_VOLTAGE, alloc::vec![0x41, 0xA8, 0x32]));
+        assert_eq!(sent[3], (WRITE_VCOM, alloc::vec![0x30]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_deep_sleep_mode_normal() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let result = display.deep_sleep(&mut delay, DeepSleepMode::Normal);
         assert!(result.is_ok());
-        let last_data = display.interface.data.last().unwrap();
+        let last_data = display.interface.data.last().ok_or("no data sent")?;
         assert_eq!(last_data, &[0x00]);
+        Ok(())
     }
 
     #[test]
-    fn test_deep_sleep_mode_preserve_ram() {
-        let mut display = test_display();
+    fn test_deep_sleep_mode_preserve_ram() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let result = display.deep_sleep(&mut delay, DeepSleepMode::PreserveRam);
         assert!(result.is_ok());
-        let last_data = display.interface.data.last().unwrap();
+        let last_data = display.interface.data.last().ok_or("no data sent")?;
         assert_eq!(last_data, &[0x01]);
+        Ok(())
     }
 
     #[test]
-    fn test_deep_sleep_mode_preserve_ram_and_analog() {
-        let mut display = test_display();
+    fn test_deep_sleep_mode_preserve_ram_and_analog() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let result = display.deep_sleep(&mut delay, DeepSleepMode::PreserveRamAndAnalog);
         assert!(result.is_ok());
-        let last_data = display.interface.data.last().unwrap();
+        let last_data = display.interface.data.last().ok_or("no data sent")?;
         assert_eq!(last_data, &[0x03]);
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_full() {
-        let mut display = test_display();
+    fn test_update_with_mode_full() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xFFu8; buffer_size];
         let red_buf = alloc::vec![0x00u8; buffer_size];
         let result = display.update_with_mode(&black_buf, &red_buf, RefreshMode::Full, &mut delay);
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_fast() {
-        let mut display = test_display();
+    fn test_update_with_mode_fast() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xFFu8; buffer_size];
         let red_buf = alloc::vec![0x00u8; buffer_size];
         let result = display.update_with_mode(&black_buf, &red_buf, RefreshMode::Fast, &mut delay);
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_fast_empty_red_uses_differential_compare() {
-        let mut display = test_display();
+    fn test_update_with_mode_fast_empty_red_uses_differential_compare() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xAAu8; buffer_size];
@@ -883,11 +2064,12 @@ mod tests {
             .find(|(cmd, _)| *cmd == DISPLAY_UPDATE_CTRL1)
             .map(|(_, data)| data.clone());
         assert_eq!(ctrl1, Some(alloc::vec![CTRL1_NORMAL]));
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_all_zero_red_bypasses_red_plane() {
-        let mut display = test_display();
+    fn test_update_with_mode_all_zero_red_bypasses_red_plane() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xFFu8; buffer_size];
@@ -904,11 +2086,12 @@ mod tests {
             .map(|(_, data)| data.clone());
 
         assert_eq!(ctrl1, Some(alloc::vec![CTRL1_BYPASS_RED]));
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_full_syncs_red_ram_when_red_is_empty() {
-        let mut display = test_display();
+    fn test_update_with_mode_full_syncs_red_ram_when_red_is_empty() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xA5u8; buffer_size];
@@ -925,11 +2108,12 @@ mod tests {
         });
 
         assert!(wrote_synced_red);
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_nonzero_red_uses_red_plane() {
-        let mut display = test_display();
+    fn test_update_with_mode_nonzero_red_uses_red_plane() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xFFu8; buffer_size];
@@ -948,11 +2132,12 @@ mod tests {
             .map(|(_, data)| data.clone());
 
         assert_eq!(ctrl1, Some(alloc::vec![CTRL1_NORMAL]));
+        Ok(())
     }
 
     #[test]
-    fn test_update_with_mode_partial() {
-        let mut display = test_display();
+    fn test_update_with_mode_partial() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let buffer_size = display.dimensions().buffer_size();
         let black_buf = alloc::vec![0xFFu8; buffer_size];
@@ -960,11 +2145,12 @@ mod tests {
         let result =
             display.update_with_mode(&black_buf, &red_buf, RefreshMode::Partial, &mut delay);
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
-    fn test_update_region_valid() {
-        let mut display = test_display();
+    fn test_update_region_valid() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let region_size = (80 / 8) * 80; // 80x80 region
         let black_buf = alloc::vec![0xFFu8; region_size];
@@ -979,11 +2165,12 @@ mod tests {
             &mut delay,
         );
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
-    fn test_update_region_out_of_bounds() {
-        let mut display = test_display();
+    fn test_update_region_out_of_bounds() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let region_size = (80 / 8) * 80;
         let black_buf = alloc::vec![0xFFu8; region_size];
@@ -998,11 +2185,12 @@ mod tests {
             &mut delay,
         );
         assert!(matches!(result, Err(Error::InvalidRamArea { .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_update_region_buffer_too_small() {
-        let mut display = test_display();
+    fn test_update_region_buffer_too_small() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let black_buf = alloc::vec![0xFFu8; 10]; // Too small for 80x80
         let red_buf = alloc::vec![0x00u8; 10];
@@ -1016,18 +2204,293 @@ mod tests {
             &mut delay,
         );
         assert!(matches!(result, Err(Error::BufferTooSmall { .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_fast_refresh() {
-        let mut display = test_display();
+    fn test_fast_refresh() -> TestResult {
+        let mut display = test_display()?;
         let mut delay = MockDelay;
         let result = display.fast_refresh(&mut delay);
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
     fn test_refresh_mode_default_is_full() {
         assert_eq!(RefreshMode::default(), RefreshMode::Full);
     }
+
+    #[test]
+    fn test_update_banded_matches_full_update_for_every_data_entry_mode() -> TestResult {
+        use crate::conformance::data_entry_matrix;
+
+        let base = Builder::new()
+            .dimensions(Dimensions::new(20, 16)?)
+            .build()?;
+        let black: Vec<u8> = (0..base.dimensions.buffer_size())
+            .map(|i| (i as u8).wrapping_mul(37))
+            .collect();
+        // Y-major addressing (AM) is rejected, see the test below
+        let configs = data_entry_matrix(&base).filter(|config| config.data_entry_mode & 0x04 == 0);
+        for config in configs {
+            let mut full = Display::new(Emulator::from_config(&config), config.clone());
+            full.reset(&mut MockDelay)?;
+            full.update(&black, &[], &mut MockDelay)?;
+
+            // 3 rows per band, the last band holds the remaining 2
+            let mut banded = Display::new(Emulator::from_config(&config), config.clone());
+            banded.reset(&mut MockDelay)?;
+            banded.update_banded(
+                &mut [0u8; 6],
+                &mut [],
+                RefreshMode::Full,
+                &mut MockDelay,
+                |band, buffer, _| {
+                    let start = band.y as usize * 2;
+                    buffer.copy_from_slice(&black[start..start + buffer.len()]);
+                },
+            )?;
+
+            assert_eq!(
+                banded.interface().visible_bw(),
+                full.interface().visible_bw(),
+                "data entry 0x{:02X}, y inverted {}",
+                config.data_entry_mode,
+                config.ram_y_inverted
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_banded_rejects_y_major_data_entry() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .data_entry_mode(0x07)
+            .build()?;
+        let mut display = Display::new(MockInterface::new(), config);
+        let result = display.update_banded(
+            &mut [0u8; 8],
+            &mut [],
+            RefreshMode::Full,
+            &mut MockDelay,
+            |_, _, _| {},
+        );
+        assert!(matches!(result, Err(Error::UnsupportedDataEntryMode(0x07))));
+        assert!(display.interface.commands.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_row_writes_reject_y_major_data_entry() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .data_entry_mode(0x07)
+            .build()?;
+        let mut display = Display::new(MockInterface::new(), config);
+        let black = [0xFFu8; 64];
+        let canvas = ScrollCanvas {
+            black: &black,
+            red: &[],
+        };
+        assert!(matches!(
+            display.write_rows(0, &black[..8], &[]),
+            Err(Error::UnsupportedDataEntryMode(0x07))
+        ));
+        assert!(matches!(
+            display.update_canvas(canvas, 0, RefreshMode::Full, &mut MockDelay),
+            Err(Error::UnsupportedDataEntryMode(0x07))
+        ));
+        assert!(matches!(
+            display.pan_canvas(canvas, 0, 4, RefreshMode::Full, &mut MockDelay),
+            Err(Error::UnsupportedDataEntryMode(0x07))
+        ));
+        assert!(display.interface.commands.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_updates_clear_scroll_offset_first() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .build()?;
+        let mut display = Display::new(Emulator::from_config(&config), config);
+        display.reset(&mut MockDelay)?;
+        let black: Vec<u8> = (0..32u8).collect();
+        display.set_scroll_offset(5)?;
+        display.update(&black, &[], &mut MockDelay)?;
+        assert_eq!(display.scroll_offset(), 0);
+        assert_eq!(display.interface().visible_bw(), black);
+
+        display.set_scroll_offset(3)?;
+        let region = UpdateRegion {
+            region: Region::new(0, 0, 16, 16),
+            black_buffer: &[0xFF; 32],
+            red_buffer: &[],
+            mode: RefreshMode::Full,
+        };
+        display.update_region(region, &mut MockDelay)?;
+        assert_eq!(display.scroll_offset(), 0);
+        assert_eq!(display.interface().visible_bw(), [0xFF; 32]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_updates_leave_gate_start_alone_when_not_scrolled() -> TestResult {
+        let mut display = test_display()?;
+        let black = alloc::vec![0xFFu8; 480 * 480 / 8];
+        display.update(&black, &[], &mut MockDelay)?;
+        assert!(!display.interface.commands.contains(&GATE_SCAN_START));
+
+        display.set_scroll_offset(1)?;
+        display.interface.commands.clear();
+        display.update(&black, &[], &mut MockDelay)?;
+        let commands = &display.interface.commands;
+        assert_eq!(commands[0], GATE_SCAN_START);
+        assert!(!commands[1..].contains(&GATE_SCAN_START));
+        Ok(())
+    }
+
+    type EmulatedDisplay = Display<Emulator>;
+
+    /// 32x32 display driving an emulator that shows full frames upright
+    fn scrolling_display(data_entry_mode: u8, ram_y_inverted: bool) -> TestResult<EmulatedDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(32, 32)?)
+            .data_entry_mode(data_entry_mode)
+            .ram_y_inverted(ram_y_inverted)
+            .build()?;
+        let mut display = Display::new(Emulator::from_config(&config), config);
+        display.reset(&mut MockDelay)?;
+        Ok(display)
+    }
+
+    /// 100 rows of 4 bytes, each row different from every other
+    fn tall_canvas() -> Vec<u8> {
+        (0..100u8)
+            .flat_map(|c| [c, !c, c.wrapping_mul(37), 0xA5])
+            .collect()
+    }
+
+    fn view(canvas: &[u8], top: usize) -> &[u8] {
+        &canvas[top * 4..(top + 32) * 4]
+    }
+
+    #[test]
+    fn test_pan_canvas_shows_view_with_wraparound() -> TestResult {
+        let black = tall_canvas();
+        let canvas = ScrollCanvas {
+            black: &black,
+            red: &[],
+        };
+        for (mode, inverted) in [(0x03, false), (0x01, true), (0x01, false)] {
+            let mut display = scrolling_display(mode, inverted)?;
+            display.update_canvas(canvas, 5, RefreshMode::Full, &mut MockDelay)?;
+            assert_eq!(display.interface().visible_bw(), view(&black, 5));
+            assert_eq!(display.scroll_offset(), 5);
+
+            let mut top = 5;
+            for to in [12, 3, 40, 68, 20, 21] {
+                display.pan_canvas(canvas, top, to, RefreshMode::Fast, &mut MockDelay)?;
+                top = to;
+                let emulator = display.interface();
+                assert_eq!(emulator.visible_bw(), view(&black, top.into()));
+                assert_eq!(emulator.red_ram(), emulator.bw_ram());
+                assert_eq!(display.scroll_offset(), top % 32);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pan_canvas_writes_only_rows_entering_view() -> TestResult {
+        let mut display = scrolling_display(0x01, false)?;
+        let first = tall_canvas();
+        let second: Vec<u8> = first.iter().map(|byte| !byte).collect();
+        let canvas = |black| ScrollCanvas { black, red: &[] };
+        display.update_canvas(canvas(&first), 0, RefreshMode::Full, &mut MockDelay)?;
+        display.pan_canvas(canvas(&second), 0, 4, RefreshMode::Full, &mut MockDelay)?;
+
+        // Rows that stayed in view still show the first canvas
+        let visible = display.interface().visible_bw();
+        assert_eq!(visible[..28 * 4], first[4 * 4..32 * 4]);
+        assert_eq!(visible[28 * 4..], second[32 * 4..36 * 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pan_canvas_with_red_plane() -> TestResult {
+        let mut display = scrolling_display(0x03, false)?;
+        let black = tall_canvas();
+        let red: Vec<u8> = (0..100u8).flat_map(|c| [0, 0, 0, c & 0x0F]).collect();
+        let canvas = ScrollCanvas {
+            black: &black,
+            red: &red,
+        };
+        display.update_canvas(canvas, 50, RefreshMode::Full, &mut MockDelay)?;
+        display.pan_canvas(canvas, 50, 61, RefreshMode::Full, &mut MockDelay)?;
+
+        let emulator = display.interface();
+        assert_eq!(emulator.visible_red(), view(&red, 61));
+        let shown: Vec<u8> = view(&black, 61)
+            .iter()
+            .zip(view(&red, 61))
+            .map(|(bw, red)| bw | red)
+            .collect();
+        assert_eq!(emulator.visible_bw(), shown);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scroll_rejects_out_of_range_rows() -> TestResult {
+        let mut display = scrolling_display(0x01, false)?;
+        let black = tall_canvas();
+        assert!(matches!(
+            display.set_scroll_offset(32),
+            Err(Error::InvalidRamArea { y: 32, .. })
+        ));
+        assert!(matches!(
+            display.write_rows(0, &black[..6], &[]),
+            Err(Error::InvalidRamArea { .. })
+        ));
+        assert!(matches!(
+            display.write_rows(0, &black[..33 * 4], &[]),
+            Err(Error::InvalidRamArea { h: 33, .. })
+        ));
+
+        let canvas = ScrollCanvas {
+            black: &black,
+            red: &black[..40 * 4],
+        };
+        assert!(matches!(
+            display.update_canvas(canvas, 69, RefreshMode::Full, &mut MockDelay),
+            Err(Error::BufferTooSmall {
+                required: 404,
+                provided: 400
+            })
+        ));
+        assert!(matches!(
+            display.pan_canvas(canvas, 0, 10, RefreshMode::Full, &mut MockDelay),
+            Err(Error::BufferTooSmall {
+                required: 168,
+                provided: 160
+            })
+        ));
+        assert_eq!(display.interface().refresh_count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_clears_scroll_offset() -> TestResult {
+        let mut display = scrolling_display(0x01, true)?;
+        display.set_scroll_offset(9)?;
+        display.reset(&mut MockDelay)?;
+        assert_eq!(display.scroll_offset(), 0);
+
+        let black = tall_canvas();
+        display.update(&black[..128], &[], &mut MockDelay)?;
+        assert_eq!(display.interface().visible_bw(), &black[..128]);
+        Ok(())
+    }
 }
@@ -0,0 +1,855 @@
+//! Software SSD1677 emulator
+//!
+//! [`Emulator`](crate::emulator::Emulator) implements [`DisplayInterface`] by
+//! interpreting the command stream the way the controller would, instead of
+//! driving real hardware.
+//! It keeps the BW and RED RAM planes, the RAM window and address counters,
+//! and a visible panel image that is only updated when a display update is
+//! activated. Tests can drive a [`Display`](crate::display::Display) through
+//! it and assert on what would actually appear on screen.
+//!
+//! ## Modelled Behaviour
+//!
+//! - `DATA_ENTRY_MODE`: X/Y increment or decrement and X-first or Y-first
+//!   counter advance
+//! - `SET_RAM_X_RANGE` / `SET_RAM_Y_RANGE` / `SET_RAM_X_COUNTER` /
+//!   `SET_RAM_Y_COUNTER`, with X addresses in pixels or bytes
+//!   (see [`RamXAddressing`](crate::config::RamXAddressing))
+//! - `WRITE_RAM_BW` / `WRITE_RAM_RED`, wrapping inside the RAM window
+//! - `AUTO_WRITE_BW_RAM` / `AUTO_WRITE_RED_RAM` regular patterns
+//! - `DISPLAY_UPDATE_CTRL1` RAM options (normal, bypass as 0, inverse)
+//! - `DISPLAY_UPDATE_CTRL2` + `MASTER_ACTIVATION`, including the analog
+//!   power steps
+//! - `DRIVER_OUTPUT_CONTROL` gate scanning direction (`GATE_SCAN_REVERSE`)
+//! - `GATE_SCAN_START`, rotating the rows of the image with wraparound
+//! - `WRITE_LUT`, `SOFT_RESET` and `DEEP_SLEEP`
+//!
+//! Every other command is accepted and its parameters are kept for
+//! inspection via [`Emulator::register`](crate::emulator::Emulator::register).
+//!
+//! ## Refresh Model
+//!
+//! Waveforms are not simulated. When the OTP LUT is in use the panel is
+//! treated as tri-color: a set RED bit shows red, otherwise the BW bit
+//! selects white or black. Once a LUT has been written with `WRITE_LUT`
+//! (and until the OTP LUT is reloaded via `CTRL2_LOAD_LUT`), the refresh is
+//! treated as a differential B/W update: RED RAM holds the previous frame and
+//! the BW bit alone decides the final pixel.
+//!
+//! A display update only changes the visible image while the analog supplies
+//! are on, i.e. after a sequence with `CTRL2_ENABLE_ANALOG` and before one
+//! with `CTRL2_DISABLE_ANALOG` (either may be part of the same sequence).
+//!
+//! ## Panel Orientation
+//!
+//! Visible pixel `(x, y)` shows RAM pixel `(x, y)` unless the emulator is
+//! mirrored with [`Emulator::mirror`](crate::emulator::Emulator::mirror).
+//! Reversing the gate scanning order flips the Y axis on top of that.
+//! [`Emulator::from_config`](crate::emulator::Emulator::from_config) mirrors the
+//! Y axis when the configured data entry mode decrements Y (or, with the
+//! configured gate scanning, when it doesn't), which matches panels wired so
+//! that a full-screen write places buffer row 0 at the top.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::emulator::Emulator;
+//! use ssd1677::{Builder, Color, Dimensions, Display};
+//! # use embedded_hal::delay::DelayNs;
+//! # struct MockDelay;
+//! # impl DelayNs for MockDelay { fn delay_ns(&mut self, _ns: u32) {} }
+//! # let mut delay = MockDelay;
+//!
+//! let config = Builder::new()
+//!     .dimensions(Dimensions::new(16, 16).unwrap())
+//!     .build()
+//!     .unwrap();
+//! let mut display = Display::new(Emulator::from_config(&config), config);
+//! display.reset(&mut delay).unwrap();
+//!
+//! let black = [0x00; 32];
+//! display.update(&black, &[], &mut delay).unwrap();
+//!
+//! let emulator = display.interface();
+//! assert_eq!(emulator.refresh_count(), 1);
+//! assert_eq!(emulator.visible_pixel(3, 7), Some(Color::Black));
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::Infallible;
+use embedded_hal::delay::DelayNs;
+
+use crate::color::Color;
+use crate::command::*;
+use crate::config::{Config, Dimensions, RamXAddressing};
+use crate::interface::DisplayInterface;
+
+type Plane = Vec<u8>;
+type Params = Vec<u8>;
+type Registers = BTreeMap<u8, Params>;
+
+/// Power-on value of `DATA_ENTRY_MODE` (X increment, Y increment, X first)
+const POR_DATA_ENTRY_MODE: u8 = 0x03;
+/// Power-on value of `DISPLAY_UPDATE_CTRL2`
+const POR_CTRL2: u8 = 0xFF;
+/// RAM option bits in each nibble of `DISPLAY_UPDATE_CTRL1`
+const RAM_OPTION_BYPASS: u8 = 0x04;
+const RAM_OPTION_INVERSE: u8 = 0x08;
+/// Deep sleep mode byte that does not retain RAM
+const DEEP_SLEEP_NORMAL: u8 = 0x00;
+
+/// Which RAM plane a write targets
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RamPlane {
+    Bw,
+    Red,
+}
+
+/// Inclusive RAM window along one axis, in RAM columns (bytes) or rows
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Window {
+    start: u16,
+    end: u16,
+}
+
+impl Window {
+    fn low(self) -> u16 {
+        self.start.min(self.end)
+    }
+
+    fn high(self) -> u16 {
+        self.start.max(self.end)
+    }
+
+    /// Advance `counter` one step, wrapping inside the window
+    ///
+    /// Returns `true` when the counter wrapped.
+    fn step(self, counter: &mut u16, increment: bool) -> bool {
+        if increment {
+            if *counter >= self.high() {
+                *counter = self.low();
+                return true;
+            }
+            *counter += 1;
+        } else {
+            if *counter <= self.low() {
+                *counter = self.high();
+                return true;
+            }
+            *counter -= 1;
+        }
+        false
+    }
+}
+
+/// Software model of an SSD1677 controller and panel
+///
+/// See the [module documentation](self) for what is modelled.
+#[derive(Clone, Debug)]
+pub struct Emulator {
+    dimensions: Dimensions,
+    ram_x_addressing: RamXAddressing,
+    /// Visible image mirrored along (X, Y)
+    mirror: (bool, bool),
+    bw_ram: Plane,
+    red_ram: Plane,
+    visible_bw: Plane,
+    visible_red: Plane,
+    command: Option<u8>,
+    registers: Registers,
+    data_entry_mode: u8,
+    x_window: Window,
+    y_window: Window,
+    x_counter: u16,
+    y_counter: u16,
+    ctrl1: u8,
+    ctrl2: u8,
+    custom_lut: Option<Params>,
+    analog_on: bool,
+    sleep_mode: Option<u8>,
+    refresh_count: usize,
+    ignored_count: usize,
+}
+
+impl Emulator {
+    /// Create an emulator for a panel of the given dimensions
+    ///
+    /// RAM X addresses are interpreted in pixels and the panel is not
+    /// mirrored. RAM starts out all zero (black, no red), as does the
+    /// visible image.
+    pub fn new(dimensions: Dimensions) -> Self {
+        let size = dimensions.buffer_size();
+        let mut emulator = Self {
+            dimensions,
+            ram_x_addressing: RamXAddressing::Pixels,
+            mirror: (false, false),
+            bw_ram: vec![0; size],
+            red_ram: vec![0; size],
+            visible_bw: vec![0; size],
+            visible_red: vec![0; size],
+            command: None,
+            registers: BTreeMap::new(),
+            data_entry_mode: POR_DATA_ENTRY_MODE,
+            x_window: Window { start: 0, end: 0 },
+            y_window: Window { start: 0, end: 0 },
+            x_counter: 0,
+            y_counter: 0,
+            ctrl1: CTRL1_NORMAL,
+            ctrl2: POR_CTRL2,
+            custom_lut: None,
+            analog_on: false,
+            sleep_mode: None,
+            refresh_count: 0,
+            ignored_count: 0,
+        };
+        emulator.reset_registers();
+        emulator
+    }
+
+    /// Create an emulator matching a driver configuration
+    ///
+    /// Uses the configured dimensions and RAM X addressing, and mirrors the
+    /// visible Y axis when `config.data_entry_mode` decrements Y. A
+    /// `GATE_SCAN_REVERSE` bit in `config.gate_scanning` is taken as part of
+    /// the panel's wiring, so only toggling it (see
+    /// [`Config::driver_gate_scanning`]) flips the image.
+    pub fn from_config(config: &Config) -> Self {
+        let y_decrement = config.data_entry_mode & 0x02 == 0;
+        let mirror_y = y_decrement != (config.gate_scanning & GATE_SCAN_REVERSE != 0);
+        Self::new(config.dimensions)
+            .ram_x_addressing(config.ram_x_addressing)
+            .mirror(false, mirror_y)
+    }
+
+    /// Set the unit of RAM X addresses
+    pub fn ram_x_addressing(mut self, addressing: RamXAddressing) -> Self {
+        self.ram_x_addressing = addressing;
+        self
+    }
+
+    /// Mirror the visible image relative to RAM along either axis
+    pub fn mirror(mut self, mirror_x: bool, mirror_y: bool) -> Self {
+        self.mirror = (mirror_x, mirror_y);
+        self
+    }
+
+    /// Panel dimensions
+    pub fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    /// Raw BW RAM contents (row-major, MSB = leftmost pixel)
+    pub fn bw_ram(&self) -> &[u8] {
+        &self.bw_ram
+    }
+
+    /// Raw RED RAM contents (row-major, MSB = leftmost pixel)
+    pub fn red_ram(&self) -> &[u8] {
+        &self.red_ram
+    }
+
+    /// BW plane of the visible image, in the same layout as a driver buffer
+    pub fn visible_bw(&self) -> &[u8] {
+        &self.visible_bw
+    }
+
+    /// RED plane of the visible image, in the same layout as a driver buffer
+    pub fn visible_red(&self) -> &[u8] {
+        &self.visible_red
+    }
+
+    /// Color currently shown at visible pixel `(x, y)`
+    ///
+    /// Returns `None` if the coordinates are outside the panel.
+    pub fn visible_pixel(&self, x: u16, y: u16) -> Option<Color> {
+        let index = self.bit_index(x, y)?;
+        Some(Color::from_bits(
+            plane_bit(&self.visible_bw, index),
+            plane_bit(&self.visible_red, index),
+        ))
+    }
+
+    /// Color stored in RAM at pixel `(x, y)`, read as tri-color data
+    ///
+    /// Coordinates are RAM coordinates; mirroring is not applied.
+    pub fn ram_pixel(&self, x: u16, y: u16) -> Option<Color> {
+        let index = self.bit_index(x, y)?;
+        Some(Color::from_bits(
+            plane_bit(&self.bw_ram, index),
+            plane_bit(&self.red_ram, index),
+        ))
+    }
+
+    /// Number of activations that updated the visible image
+    pub fn refresh_count(&self) -> usize {
+        self.refresh_count
+    }
+
+    /// Whether the analog supplies (gate and source voltages) are on
+    pub fn is_analog_on(&self) -> bool {
+        self.analog_on
+    }
+
+    /// Whether the controller is in deep sleep
+    pub fn is_asleep(&self) -> bool {
+        self.sleep_mode.is_some()
+    }
+
+    /// Number of commands and data writes ignored while in deep sleep
+    pub fn ignored_count(&self) -> usize {
+        self.ignored_count
+    }
+
+    /// Last parameters written for a command
+    ///
+    /// RAM writes are not recorded here; use [`Emulator::bw_ram`] and
+    /// [`Emulator::red_ram`] instead.
+    #[allow(clippy::type_complexity)]
+    pub fn register(&self, command: u8) -> Option<&[u8]> {
+        self.registers.get(&command).map(Vec::as_slice)
+    }
+
+    /// LUT written with `WRITE_LUT`, if it is the one in use
+    ///
+    /// Returns `None` after reset or once the OTP LUT has been reloaded.
+    #[allow(clippy::type_complexity)]
+    pub fn custom_lut(&self) -> Option<&[u8]> {
+        self.custom_lut.as_deref()
+    }
+
+    /// Current data entry mode
+    pub fn data_entry_mode(&self) -> u8 {
+        self.data_entry_mode
+    }
+
+    /// Current RAM address counters as (column byte, row)
+    pub fn ram_counters(&self) -> (u16, u16) {
+        (self.x_counter, self.y_counter)
+    }
+
+    fn stride(&self) -> usize {
+        self.dimensions.cols as usize / 8
+    }
+
+    fn bit_index(&self, x: u16, y: u16) -> Option<usize> {
+        if x >= self.dimensions.cols || y >= self.dimensions.rows {
+            return None;
+        }
+        Some(y as usize * self.dimensions.cols as usize + x as usize)
+    }
+
+    /// Restore power-on register values (RAM is left untouched)
+    fn reset_registers(&mut self) {
+        let columns = (self.dimensions.cols / 8).saturating_sub(1);
+        let rows = self.dimensions.rows.saturating_sub(1);
+        self.command = None;
+        self.registers.clear();
+        self.data_entry_mode = POR_DATA_ENTRY_MODE;
+        self.x_window = Window {
+            start: 0,
+            end: columns,
+        };
+        self.y_window = Window {
+            start: 0,
+            end: rows,
+        };
+        self.x_counter = 0;
+        self.y_counter = 0;
+        self.ctrl1 = CTRL1_NORMAL;
+        self.ctrl2 = POR_CTRL2;
+        self.custom_lut = None;
+        self.analog_on = false;
+    }
+
+    /// Convert a RAM X address into a column byte
+    fn x_column(&self, low: u8, high: u8) -> u16 {
+        let address = u16::from_le_bytes([low, high]);
+        match self.ram_x_addressing {
+            RamXAddressing::Pixels => address / 8,
+            RamXAddressing::Bytes => address,
+        }
+    }
+
+    fn run_command(&mut self, command: u8) {
+        match command {
+            SOFT_RESET => self.reset_registers(),
+            MASTER_ACTIVATION => self.activate(),
+            _ => {}
+        }
+    }
+
+    fn write_parameter(&mut self, command: u8, byte: u8) {
+        let params = self.registers.entry(command).or_default();
+        params.push(byte);
+        let params = params.clone();
+        match (command, params.as_slice()) {
+            (DATA_ENTRY_MODE, [mode]) => self.data_entry_mode = mode & 0x07,
+            (SET_RAM_X_RANGE, [s0, s1, e0, e1]) => {
+                self.x_window = Window {
+                    start: self.x_column(*s0, *s1),
+                    end: self.x_column(*e0, *e1),
+                };
+            }
+            (SET_RAM_Y_RANGE, [s0, s1, e0, e1]) => {
+                self.y_window = Window {
+                    start: u16::from_le_bytes([*s0, *s1]),
+                    end: u16::from_le_bytes([*e0, *e1]),
+                };
+            }
+            (SET_RAM_X_COUNTER, [low, high]) => self.x_counter = self.x_column(*low, *high),
+            (SET_RAM_Y_COUNTER, [low, high]) => {
+                self.y_counter = u16::from_le_bytes([*low, *high]);
+            }
+            (AUTO_WRITE_BW_RAM, [pattern]) => self.auto_write(RamPlane::Bw, *pattern),
+            (AUTO_WRITE_RED_RAM, [pattern]) => self.auto_write(RamPlane::Red, *pattern),
+            (DISPLAY_UPDATE_CTRL1, [options, ..]) => self.ctrl1 = *options,
+            (DISPLAY_UPDATE_CTRL2, [sequence]) => self.ctrl2 = *sequence,
+            (WRITE_LUT, lut) => self.custom_lut = Some(lut.to_vec()),
+            (DEEP_SLEEP, [mode]) => self.sleep_mode = Some(*mode),
+            _ => {}
+        }
+    }
+
+    /// Write one byte at the RAM counters and advance them
+    fn write_ram(&mut self, plane: RamPlane, byte: u8) {
+        let (column, row) = (self.x_counter as usize, self.y_counter as usize);
+        if column < self.stride() && row < self.dimensions.rows as usize {
+            let index = row * self.stride() + column;
+            match plane {
+                RamPlane::Bw => self.bw_ram[index] = byte,
+                RamPlane::Red => self.red_ram[index] = byte,
+            }
+        }
+
+        let x_increment = self.data_entry_mode & 0x01 != 0;
+        let y_increment = self.data_entry_mode & 0x02 != 0;
+        if self.data_entry_mode & 0x04 != 0 {
+            if self.y_window.step(&mut self.y_counter, y_increment) {
+                self.x_window.step(&mut self.x_counter, x_increment);
+            }
+        } else if self.x_window.step(&mut self.x_counter, x_increment) {
+            self.y_window.step(&mut self.y_counter, y_increment);
+        }
+    }
+
+    /// Fill a whole RAM plane with a regular pattern
+    ///
+    /// Bit 7 is the value of the first step, bits 6:4 select the step height
+    /// and bits 2:0 the step width (8 << n pixels). Steps larger than the
+    /// panel produce a solid fill.
+    fn auto_write(&mut self, plane: RamPlane, pattern: u8) {
+        let first = pattern & 0x80 != 0;
+        let step_height = 8usize << ((pattern >> 4) & 0x07);
+        let step_width = 8usize << (pattern & 0x07);
+        let cols = self.dimensions.cols as usize;
+        let target = match plane {
+            RamPlane::Bw => &mut self.bw_ram,
+            RamPlane::Red => &mut self.red_ram,
+        };
+        for (index, byte) in target.iter_mut().enumerate() {
+            let x = (index * 8) % cols;
+            let y = (index * 8) / cols;
+            let odd_step = (x / step_width + y / step_height) % 2 == 1;
+            *byte = if first != odd_step { 0xFF } else { 0x00 };
+        }
+    }
+
+    /// Run the sequence selected by `DISPLAY_UPDATE_CTRL2`
+    fn activate(&mut self) {
+        if self.ctrl2 & CTRL2_ENABLE_ANALOG != 0 {
+            self.analog_on = true;
+        }
+        if self.ctrl2 & CTRL2_LOAD_LUT != 0 {
+            self.custom_lut = None;
+        }
+        if self.ctrl2 & CTRL2_DISPLAY != 0 && self.analog_on {
+            self.render();
+            self.refresh_count += 1;
+        }
+        if self.ctrl2 & CTRL2_DISABLE_ANALOG != 0 {
+            self.analog_on = false;
+        }
+    }
+
+    /// Update the visible image from RAM
+    fn render(&mut self) {
+        let (cols, rows) = (self.dimensions.cols, self.dimensions.rows);
+        let differential = self.custom_lut.is_some();
+        let gate_reversed = self
+            .registers
+            .get(&DRIVER_OUTPUT_CONTROL)
+            .and_then(|params| params.get(2))
+            .is_some_and(|scan| scan & GATE_SCAN_REVERSE != 0);
+        let start = self
+            .registers
+            .get(&GATE_SCAN_START)
+            .and_then(|params| params.get(..2))
+            .map_or(0, |params| {
+                u16::from_le_bytes([params[0], params[1]]) % rows
+            });
+        let mut visible_bw = vec![0u8; self.visible_bw.len()];
+        let mut visible_red = vec![0u8; self.visible_red.len()];
+
+        for y in 0..rows {
+            // Gates scanned in reverse drive the rows bottom-up
+            let gate = if gate_reversed { rows - 1 - y } else { y };
+            for x in 0..cols {
+                let (show_bw, show_red) = self.render_pixel(x, gate, start, differential);
+                let target = y as usize * cols as usize + x as usize;
+                set_plane_bit(&mut visible_bw, target, show_bw);
+                set_plane_bit(&mut visible_red, target, show_red);
+            }
+        }
+
+        self.visible_bw = visible_bw;
+        self.visible_red = visible_red;
+    }
+
+    /// BW and RED bits shown at visible pixel `(x, y)` after a refresh
+    ///
+    /// RAM row 0 is driven on gate `start`, so every row shows the RAM row
+    /// `start` rows before the one it shows unshifted.
+    fn render_pixel(&self, x: u16, y: u16, start: u16, differential: bool) -> (bool, bool) {
+        let (cols, rows) = (self.dimensions.cols, self.dimensions.rows);
+        let (mirror_x, mirror_y) = self.mirror;
+        let ram_x = if mirror_x { cols - 1 - x } else { x };
+        let ram_y = if mirror_y { rows - 1 - y } else { y };
+        let ram_y = (ram_y + rows - start) % rows;
+        let source = ram_y as usize * cols as usize + ram_x as usize;
+        let bw = ram_option(plane_bit(&self.bw_ram, source), self.ctrl1 & 0x0F);
+        let red = ram_option(plane_bit(&self.red_ram, source), self.ctrl1 >> 4);
+        if red && !differential {
+            (true, true)
+        } else {
+            (bw, false)
+        }
+    }
+}
+
+fn plane_bit(plane: &[u8], index: usize) -> bool {
+    plane[index / 8] & (0x80 >> (index % 8)) != 0
+}
+
+fn set_plane_bit(plane: &mut [u8], index: usize, value: bool) {
+    let mask = 0x80 >> (index % 8);
+    if value {
+        plane[index / 8] |= mask;
+    } else {
+        plane[index / 8] &= !mask;
+    }
+}
+
+/// Apply a `DISPLAY_UPDATE_CTRL1` RAM option nibble to a RAM bit
+fn ram_option(bit: bool, option: u8) -> bool {
+    if option & RAM_OPTION_BYPASS != 0 {
+        false
+    } else if option & RAM_OPTION_INVERSE != 0 {
+        !bit
+    } else {
+        bit
+    }
+}
+
+impl DisplayInterface for Emulator {
+    type Error = Infallible;
+
+    fn send_command(&mut self, command: u8) -> Result<(), Self::Error> {
+        if self.is_asleep() {
+            self.ignored_count += 1;
+            return Ok(());
+        }
+        self.command = Some(command);
+        if command != WRITE_RAM_BW && command != WRITE_RAM_RED {
+            self.registers.insert(command, Vec::new());
+        }
+        self.run_command(command);
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        if self.is_asleep() {
+            self.ignored_count += 1;
+            return Ok(());
+        }
+        let Some(command) = self.command else {
+            return Ok(());
+        };
+        for &byte in data {
+            match command {
+                WRITE_RAM_BW => self.write_ram(RamPlane::Bw, byte),
+                WRITE_RAM_RED => self.write_ram(RamPlane::Red, byte),
+                _ => self.write_parameter(command, byte),
+            }
+        }
+        Ok(())
+    }
+
+    fn reset<D: DelayNs>(&mut self, _delay: &mut D) {
+        if self.sleep_mode == Some(DEEP_SLEEP_NORMAL) {
+            self.bw_ram.fill(0);
+            self.red_ram.fill(0);
+        }
+        self.sleep_mode = None;
+        self.reset_registers();
+    }
+
+    fn busy_wait<D: DelayNs>(&mut self, _delay: &mut D) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::config::Builder;
+    use crate::display::{DeepSleepMode, Display, RefreshMode, Region, UpdateRegion};
+
+    struct MockDelay;
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    type EmulatedDisplay = Display<Emulator>;
+
+    fn emulated_display(data_entry_mode: u8, ram_y_inverted: bool) -> TestResult<EmulatedDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(32, 32)?)
+            .data_entry_mode(data_entry_mode)
+            .ram_y_inverted(ram_y_inverted)
+            .build()?;
+        let mut display = Display::new(Emulator::from_config(&config), config);
+        display.reset(&mut MockDelay)?;
+        Ok(display)
+    }
+
+    /// Buffer with a single black pixel at (x, y) on a white 32x32 panel
+    fn single_pixel(x: usize, y: usize) -> [u8; 128] {
+        let mut buffer = [0xFF; 128];
+        buffer[y * 4 + x / 8] &= !(0x80 >> (x % 8));
+        buffer
+    }
+
+    type Pixels = Vec<(u16, u16)>;
+
+    fn black_pixels(emulator: &Emulator) -> Pixels {
+        (0..32)
+            .flat_map(|y| (0..32).map(move |x| (x, y)))
+            .filter(|&(x, y)| emulator.visible_pixel(x, y) == Some(Color::Black))
+            .collect()
+    }
+
+    #[test]
+    fn test_full_update_shows_buffer_for_consistent_configs() -> TestResult {
+        for (mode, inverted) in [(0x03, false), (0x01, true)] {
+            let mut display = emulated_display(mode, inverted)?;
+            display.update(&single_pixel(5, 2), &[], &mut MockDelay)?;
+            assert_eq!(black_pixels(display.interface()), [(5, 2)]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_visible_image_changes_only_on_activation() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        let emulator = display.interface_mut();
+        emulator.send_command(WRITE_RAM_BW)?;
+        emulator.send_data(&[0xFF; 128])?;
+        assert_eq!(emulator.refresh_count(), 0);
+        assert_eq!(emulator.ram_pixel(0, 0), Some(Color::White));
+        assert_eq!(emulator.visible_pixel(0, 0), Some(Color::Black));
+
+        display.update(&[0xFF; 128], &[], &mut MockDelay)?;
+        assert_eq!(display.interface().refresh_count(), 1);
+        assert_eq!(display.interface().visible_pixel(0, 0), Some(Color::White));
+        Ok(())
+    }
+
+    #[test]
+    fn test_region_update_lands_in_place() -> TestResult {
+        for (mode, inverted) in [(0x03, false), (0x01, true)] {
+            let mut display = emulated_display(mode, inverted)?;
+            display.update(&[0xFF; 128], &[], &mut MockDelay)?;
+
+            // 8x4 region at (16, 8) with a black pixel at its local (2, 3)
+            let mut region = [0xFF; 4];
+            region[3] = !0x20;
+            let update = UpdateRegion {
+                region: Region::new(16, 8, 8, 4),
+                black_buffer: &region,
+                red_buffer: &[],
+                mode: RefreshMode::Full,
+            };
+            display.update_region(update, &mut MockDelay)?;
+            assert_eq!(black_pixels(display.interface()), [(18, 11)]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_red_buffer_shows_red_with_otp_lut() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        let mut red = [0x00; 128];
+        red[0] = 0x80;
+        display.update(&[0xFF; 128], &red, &mut MockDelay)?;
+
+        let emulator = display.interface();
+        assert_eq!(emulator.visible_pixel(0, 0), Some(Color::Red));
+        assert_eq!(emulator.visible_pixel(1, 0), Some(Color::White));
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_refresh_is_differential() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        display.update(&[0xFF; 128], &[], &mut MockDelay)?;
+        display.update_with_mode(&single_pixel(7, 9), &[], RefreshMode::Fast, &mut MockDelay)?;
+
+        let emulator = display.interface();
+        assert!(emulator.custom_lut().is_some());
+        assert_eq!(black_pixels(emulator), [(7, 9)]);
+        // RED RAM now holds the new frame for the next differential refresh
+        assert_eq!(emulator.red_ram(), emulator.bw_ram());
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_write_patterns() -> TestResult {
+        let mut emulator = Emulator::new(Dimensions::new(32, 32)?);
+        emulator.send_command(AUTO_WRITE_BW_RAM)?;
+        emulator.send_data(&[0xF7])?;
+        assert!(emulator.bw_ram().iter().all(|byte| *byte == 0xFF));
+
+        // 8x8 checkerboard starting at 0
+        emulator.send_command(AUTO_WRITE_RED_RAM)?;
+        emulator.send_data(&[0x00])?;
+        assert_eq!(emulator.red_ram()[..4], [0x00, 0xFF, 0x00, 0xFF]);
+        assert_eq!(emulator.red_ram()[32..36], [0xFF, 0x00, 0xFF, 0x00]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ram_window_wraps_and_y_first_mode() -> TestResult {
+        let mut emulator = Emulator::new(Dimensions::new(16, 32)?);
+        emulator.send_command(DATA_ENTRY_MODE)?;
+        emulator.send_data(&[0x07])?;
+        emulator.send_command(SET_RAM_X_RANGE)?;
+        emulator.send_data(&[8, 0, 23, 0])?;
+        emulator.send_command(SET_RAM_Y_RANGE)?;
+        emulator.send_data(&[0, 0, 1, 0])?;
+        emulator.send_command(SET_RAM_X_COUNTER)?;
+        emulator.send_data(&[8, 0])?;
+        emulator.send_command(SET_RAM_Y_COUNTER)?;
+        emulator.send_data(&[0, 0])?;
+
+        emulator.send_command(WRITE_RAM_BW)?;
+        emulator.send_data(&[0x11, 0x22])?;
+        emulator.send_data(&[0x33, 0x44, 0x55])?;
+
+        // Y first: (col 1, row 0), (col 1, row 1), (col 2, row 0), (col 2, row 1),
+        // then wrap back to the window origin
+        assert_eq!(emulator.bw_ram()[1], 0x55);
+        assert_eq!(emulator.bw_ram()[5], 0x22);
+        assert_eq!(emulator.bw_ram()[2], 0x33);
+        assert_eq!(emulator.bw_ram()[6], 0x44);
+        assert_eq!(emulator.ram_counters(), (1, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_byte_x_addressing() -> TestResult {
+        let mut emulator =
+            Emulator::new(Dimensions::new(8, 32)?).ram_x_addressing(RamXAddressing::Bytes);
+        emulator.send_command(SET_RAM_X_COUNTER)?;
+        emulator.send_data(&[2, 0])?;
+        emulator.send_command(WRITE_RAM_RED)?;
+        emulator.send_data(&[0xAA])?;
+        assert_eq!(emulator.red_ram()[2], 0xAA);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deep_sleep_ignores_commands_until_reset() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        display.update(&[0x00; 128], &[], &mut MockDelay)?;
+        display.deep_sleep(&mut MockDelay, DeepSleepMode::PreserveRam)?;
+        assert!(display.interface().is_asleep());
+
+        display.update(&[0xFF; 128], &[], &mut MockDelay)?;
+        let emulator = display.interface();
+        assert!(emulator.ignored_count() > 0);
+        assert_eq!(emulator.refresh_count(), 1);
+        assert_eq!(emulator.visible_pixel(0, 0), Some(Color::Black));
+
+        display.reset(&mut MockDelay)?;
+        assert!(!display.interface().is_asleep());
+        assert_eq!(display.interface().register(DEEP_SLEEP), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_needs_analog_power() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        let emulator = display.interface_mut();
+        emulator.send_command(WRITE_RAM_BW)?;
+        emulator.send_data(&[0xFF; 128])?;
+        emulator.send_command(DISPLAY_UPDATE_CTRL2)?;
+        emulator.send_data(&[CTRL2_DISPLAY])?;
+        emulator.send_command(MASTER_ACTIVATION)?;
+        assert_eq!(emulator.refresh_count(), 0);
+        assert_eq!(emulator.visible_pixel(0, 0), Some(Color::Black));
+
+        // The driver adds the power-on steps while the display is off
+        display.update(&[0xFF; 128], &[], &mut MockDelay)?;
+        assert_eq!(display.interface().refresh_count(), 1);
+        assert_eq!(display.interface().visible_pixel(0, 0), Some(Color::White));
+        // The default full sequence (0xF7) powers down again
+        assert!(!display.interface().is_analog_on());
+        Ok(())
+    }
+
+    #[test]
+    fn test_otp_reload_drops_custom_lut() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        display.load_lut(&crate::lut::LUT_FAST)?;
+        assert!(display.interface().custom_lut().is_some());
+
+        display.update_with_mode(&[0xFF; 128], &[], RefreshMode::Full, &mut MockDelay)?;
+        assert_eq!(display.interface().custom_lut(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_gate_scan_start_rotates_rows() -> TestResult {
+        let mut display = emulated_display(0x03, false)?;
+        let ram: Vec<u8> = (0..128u8).collect();
+        let emulator = display.interface_mut();
+        emulator.send_command(WRITE_RAM_BW)?;
+        emulator.send_data(&ram)?;
+        // RAM row 0 is driven on gate 3
+        emulator.send_command(GATE_SCAN_START)?;
+        emulator.send_data(&[3, 0])?;
+        display.full_refresh(&mut MockDelay)?;
+
+        let emulator = display.interface();
+        assert_eq!(emulator.bw_ram(), ram);
+        for y in 0..32 {
+            let ram_y = (y + 29) % 32;
+            assert_eq!(
+                emulator.visible_bw()[y * 4..y * 4 + 4],
+                ram[ram_y * 4..ram_y * 4 + 4]
+            );
+        }
+
+        // Soft reset returns the start position to gate 0
+        display.reset(&mut MockDelay)?;
+        let emulator = display.interface_mut();
+        emulator.send_command(WRITE_RAM_BW)?;
+        emulator.send_data(&ram)?;
+        display.full_refresh(&mut MockDelay)?;
+        assert_eq!(display.interface().visible_bw(), ram);
+        Ok(())
+    }
+}
@@ -24,6 +24,8 @@
 //! ```
 
 use crate::interface::DisplayInterface;
+use crate::rle::RleError;
+use crate::waveform::LutIssue;
 
 /// Maximum gate outputs (rows) supported by SSD1677 controller
 ///
@@ -55,6 +57,9 @@ pub enum Error<I: DisplayInterface> {
     /// - 1 <= rows <= MAX_GATE_OUTPUTS (680)
     /// - 8 <= cols <= MAX_SOURCE_OUTPUTS (960)
     /// - cols must be a multiple of 8
+    ///
+    /// Also returned by `GraphicDisplay::with_framebuffer` when the config
+    /// doesn't match the framebuffer's size.
     InvalidDimensions {
         /// Number of rows (height) requested
         rows: u16,
@@ -105,6 +110,22 @@ pub enum Error<I: DisplayInterface> {
         /// Provided length
         provided: usize,
     },
+    /// Custom LUT failed validation
+    ///
+    /// Only returned when [`LutCheck::Reject`](crate::config::LutCheck::Reject) is configured.
+    InvalidWaveform(LutIssue),
+    /// Run-length encoded image data is malformed
+    ///
+    /// Returned by [`Display::update_compressed`](crate::display::Display::update_compressed)
+    /// before anything is sent to the controller.
+    InvalidRle(RleError),
+    /// The configured data entry mode can't be used for this operation
+    ///
+    /// Row-by-row writers such as
+    /// [`Display::update_banded`](crate::display::Display::update_banded)
+    /// need X-major addressing; Y-major modes (bit 2 set) fill RAM column by
+    /// column.
+    UnsupportedDataEntryMode(u8),
 }
 
 impl<I: DisplayInterface> core::fmt::Display for Error<I> {
@@ -136,6 +157,11 @@ impl<I: DisplayInterface> core::fmt::Display for Error<I> {
                     "Invalid short LUT length: expected {expected} bytes, provided {provided}"
                 )
             }
+            Self::InvalidWaveform(issue) => write!(f, "Invalid waveform: {issue}"),
+            Self::InvalidRle(error) => write!(f, "Invalid compressed image: {error}"),
+            Self::UnsupportedDataEntryMode(mode) => {
+                write!(f, "Unsupported data entry mode: 0x{mode:02X}")
+            }
         }
     }
 }
@@ -0,0 +1,470 @@
+//! Fault injection for error-path testing
+//!
+//! [`FaultInjector`](crate::fault::FaultInjector) wraps any
+//! [`DisplayInterface`] and fails one chosen operation the way real hardware
+//! can: an SPI transfer that errors, a BUSY pin that cannot be read, a BUSY
+//! line stuck high until the wait times out, or a data byte corrupted on the
+//! wire. Operations are counted from zero per kind (commands, data transfers,
+//! busy waits), so a test can run an operation once cleanly, read the counts,
+//! and then fail each step in turn.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::fault::{Fault, FaultError, FaultInjector};
+//! use ssd1677::interface::DisplayInterface;
+//! use ssd1677::{Builder, Dimensions, Display, Error};
+//! # use embedded_hal::delay::DelayNs;
+//! # struct MockDelay;
+//! # impl DelayNs for MockDelay { fn delay_ns(&mut self, _ns: u32) {} }
+//! # struct NullInterface;
+//! # impl DisplayInterface for NullInterface {
+//! #     type Error = core::convert::Infallible;
+//! #     fn send_command(&mut self, _: u8) -> Result<(), Self::Error> { Ok(()) }
+//! #     fn send_data(&mut self, _: &[u8]) -> Result<(), Self::Error> { Ok(()) }
+//! #     fn reset<D: DelayNs>(&mut self, _: &mut D) {}
+//! #     fn busy_wait<D: DelayNs>(&mut self, _: &mut D) -> Result<(), Self::Error> { Ok(()) }
+//! # }
+//! # let mut delay = MockDelay;
+//!
+//! let config = Builder::new()
+//!     .dimensions(Dimensions::new(16, 16).unwrap())
+//!     .build()
+//!     .unwrap();
+//! let interface = FaultInjector::new(NullInterface).fault(Fault::StuckBusy(0));
+//! let mut display = Display::new(interface, config);
+//!
+//! let result = display.reset(&mut delay);
+//! assert!(matches!(result, Err(Error::Interface(FaultError::Timeout))));
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use embedded_hal::delay::DelayNs;
+
+use crate::interface::DisplayInterface;
+
+type FaultResult<E> = core::result::Result<(), FaultError<E>>;
+
+/// Operation to fail, counted from zero per kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Fail the Nth command with [`FaultError::Spi`]; the command is not sent
+    Command(usize),
+    /// Fail the Nth data transfer with [`FaultError::Spi`]; no data is sent
+    Data(usize),
+    /// Fail the Nth busy wait with [`FaultError::Pin`]
+    BusyPin(usize),
+    /// Hold BUSY high from the Nth busy wait on, so every wait returns
+    /// [`FaultError::Timeout`] until the fault is cleared
+    StuckBusy(usize),
+    /// XOR the first byte of the Nth data transfer with `mask` and report success
+    CorruptData {
+        /// Index of the data transfer to corrupt
+        transfer: usize,
+        /// Bits to flip
+        mask: u8,
+    },
+}
+
+/// Errors returned by [`FaultInjector`]
+///
+/// The injected variants mirror those of
+/// [`InterfaceError`](crate::interface::InterfaceError).
+#[derive(Debug, PartialEq, Eq)]
+pub enum FaultError<E> {
+    /// Injected SPI failure
+    Spi,
+    /// Injected GPIO failure
+    Pin,
+    /// Injected busy-wait timeout
+    Timeout,
+    /// Error from the wrapped interface
+    Inner(E),
+}
+
+impl<E: Debug> fmt::Display for FaultError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spi => write!(f, "Injected SPI error"),
+            Self::Pin => write!(f, "Injected pin error"),
+            Self::Timeout => write!(f, "Injected timeout waiting for display"),
+            Self::Inner(e) => write!(f, "Interface error: {e:?}"),
+        }
+    }
+}
+
+impl<E: Debug> core::error::Error for FaultError<E> {}
+
+/// Interface wrapper that fails a chosen operation
+///
+/// Operations that are not faulted are forwarded unchanged.
+#[derive(Debug)]
+pub struct FaultInjector<I> {
+    inner: I,
+    fault: Option<Fault>,
+    commands: usize,
+    data_transfers: usize,
+    busy_waits: usize,
+    injected: usize,
+}
+
+impl<I: DisplayInterface> FaultInjector<I> {
+    /// Wrap an interface without any fault armed
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            fault: None,
+            commands: 0,
+            data_transfers: 0,
+            busy_waits: 0,
+            injected: 0,
+        }
+    }
+
+    /// Arm a fault
+    pub fn fault(mut self, fault: Fault) -> Self {
+        self.fault = Some(fault);
+        self
+    }
+
+    /// Arm, replace or (with `None`) clear the fault
+    pub fn set_fault(&mut self, fault: Option<Fault>) {
+        self.fault = fault;
+    }
+
+    /// Restart counting operations from zero
+    pub fn reset_counts(&mut self) {
+        self.commands = 0;
+        self.data_transfers = 0;
+        self.busy_waits = 0;
+        self.injected = 0;
+    }
+
+    /// Number of commands attempted, including failed ones
+    pub fn commands(&self) -> usize {
+        self.commands
+    }
+
+    /// Number of data transfers attempted, including failed ones
+    pub fn data_transfers(&self) -> usize {
+        self.data_transfers
+    }
+
+    /// Number of busy waits attempted, including failed ones
+    pub fn busy_waits(&self) -> usize {
+        self.busy_waits
+    }
+
+    /// Number of operations failed or corrupted so far
+    pub fn injected(&self) -> usize {
+        self.injected
+    }
+
+    /// Borrow the wrapped interface
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// Mutably borrow the wrapped interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Unwrap the interface
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Record an injected fault and return its error
+    #[allow(clippy::type_complexity)]
+    fn inject(&mut self, error: FaultError<I::Error>) -> FaultResult<I::Error> {
+        self.injected += 1;
+        Err(error)
+    }
+}
+
+impl<I: DisplayInterface> DisplayInterface for FaultInjector<I> {
+    type Error = FaultError<I::Error>;
+
+    fn send_command(&mut self, command: u8) -> FaultResult<I::Error> {
+        let index = self.commands;
+        self.commands += 1;
+        if self.fault == Some(Fault::Command(index)) {
+            return self.inject(FaultError::Spi);
+        }
+        self.inner.send_command(command).map_err(FaultError::Inner)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> FaultResult<I::Error> {
+        let index = self.data_transfers;
+        self.data_transfers += 1;
+        match self.fault {
+            Some(Fault::Data(n)) if n == index => self.inject(FaultError::Spi),
+            Some(Fault::CorruptData { transfer, mask }) if transfer == index => {
+                let mut corrupted = Vec::from(data);
+                if let Some(first) = corrupted.first_mut() {
+                    *first ^= mask;
+                    self.injected += 1;
+                }
+                self.inner.send_data(&corrupted).map_err(FaultError::Inner)
+            }
+            _ => self.inner.send_data(data).map_err(FaultError::Inner),
+        }
+    }
+
+    fn reset<D: DelayNs>(&mut self, delay: &mut D) {
+        self.inner.reset(delay);
+    }
+
+    fn busy_wait<D: DelayNs>(&mut self, delay: &mut D) -> FaultResult<I::Error> {
+        let index = self.busy_waits;
+        self.busy_waits += 1;
+        match self.fault {
+            Some(Fault::BusyPin(n)) if n == index => self.inject(FaultError::Pin),
+            Some(Fault::StuckBusy(from)) if index >= from => self.inject(FaultError::Timeout),
+            _ => self.inner.busy_wait(delay).map_err(FaultError::Inner),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::command::{DISPLAY_UPDATE_CTRL2, MASTER_ACTIVATION};
+    use crate::config::{Builder, Config, Dimensions};
+    use crate::display::{DeepSleepMode, Display, RefreshMode};
+    use crate::emulator::Emulator;
+    use crate::error::Error;
+    use crate::trace::{Event, Recorder};
+
+    struct MockDelay;
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    type FaultyDisplay = Display<FaultInjector<Emulator>>;
+    type Counts = (usize, usize, usize);
+
+    const BUFFER: usize = 16 * 16 / 8;
+
+    /// Panel sequences without built-in power steps, so the driver's power
+    /// tracking decides whether the analog supplies are switched on
+    fn config() -> TestResult<Config> {
+        Ok(Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .data_entry_mode(0x03)
+            .display_update_ctrl2_full(0x34)
+            .display_update_ctrl2_partial(0x04)
+            .display_update_ctrl2_fast(0x04)
+            .build()?)
+    }
+
+    fn faulty_display(powered: bool) -> TestResult<FaultyDisplay> {
+        let config = config()?;
+        let interface = FaultInjector::new(Emulator::from_config(&config));
+        let mut display = Display::new(interface, config);
+        display.reset(&mut MockDelay)?;
+        if powered {
+            display.update(&[0xFF; BUFFER], &[], &mut MockDelay)?;
+        }
+        display.interface_mut().reset_counts();
+        Ok(display)
+    }
+
+    /// A pattern with black and white pixels in every row
+    fn black() -> [u8; BUFFER] {
+        let mut buffer = [0xFF; BUFFER];
+        for (index, byte) in buffer.iter_mut().enumerate() {
+            *byte = if index % 3 == 0 { 0x0F } else { 0xA5 };
+        }
+        buffer
+    }
+
+    fn red() -> [u8; BUFFER] {
+        let mut buffer = [0x00; BUFFER];
+        buffer[5] = 0x80;
+        buffer
+    }
+
+    /// Whether the panel shows the BW buffer
+    fn shows(display: &FaultyDisplay, black: &[u8]) -> bool {
+        let emulator = display.interface().inner();
+        emulator.visible_bw() == black && emulator.visible_red().iter().all(|byte| *byte == 0)
+    }
+
+    /// The driver may only believe the display is on if it really is
+    fn assert_power_consistent(display: &FaultyDisplay, step: &str) {
+        if display.is_display_on() {
+            assert!(
+                display.interface().inner().is_analog_on(),
+                "{step}: driver reports power on, controller is off"
+            );
+        }
+    }
+
+    /// Operation counts of a clean run of an update
+    fn counts(display: &FaultyDisplay) -> Counts {
+        let interface = display.interface();
+        (
+            interface.commands(),
+            interface.data_transfers(),
+            interface.busy_waits(),
+        )
+    }
+
+    fn faults_for(counts: Counts) -> impl Iterator<Item = Fault> {
+        let (commands, data, busy) = counts;
+        (0..commands)
+            .map(Fault::Command)
+            .chain((0..data).map(Fault::Data))
+            .chain((0..busy).map(Fault::BusyPin))
+            .chain((0..busy).map(Fault::StuckBusy))
+    }
+
+    #[test]
+    fn test_every_update_failure_point_recovers() -> TestResult {
+        let modes = [RefreshMode::Full, RefreshMode::Partial, RefreshMode::Fast];
+        let red = red();
+        let cases = modes.iter().flat_map(|mode| {
+            [&[][..], &red[..]]
+                .into_iter()
+                .flat_map(move |red| [false, true].map(|powered| (*mode, red, powered)))
+        });
+
+        for (mode, red, powered) in cases {
+            let mut clean = faulty_display(powered)?;
+            clean.update_with_mode(&black(), red, mode, &mut MockDelay)?;
+            let expected = clean.interface().inner();
+            for fault in faults_for(counts(&clean)) {
+                let step = alloc::format!(
+                    "{mode:?} red={} powered={powered} {fault:?}",
+                    !red.is_empty()
+                );
+                let mut display = faulty_display(powered)?;
+                display.interface_mut().set_fault(Some(fault));
+
+                let result = display.update_with_mode(&black(), red, mode, &mut MockDelay);
+                assert!(matches!(result, Err(Error::Interface(_))), "{step}");
+                assert_eq!(display.interface().injected(), 1, "{step}");
+                assert_power_consistent(&display, &step);
+
+                display.interface_mut().set_fault(None);
+                display.update_with_mode(&black(), red, mode, &mut MockDelay)?;
+                let emulator = display.interface().inner();
+                assert_eq!(emulator.visible_bw(), expected.visible_bw(), "{step}");
+                assert_eq!(emulator.visible_red(), expected.visible_red(), "{step}");
+                assert_power_consistent(&display, &step);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_power_down_is_retried() -> TestResult {
+        let counts = {
+            let mut display = faulty_display(true)?;
+            display.deep_sleep(&mut MockDelay, DeepSleepMode::PreserveRam)?;
+            counts(&display)
+        };
+        assert_eq!(counts, (4, 3, 1));
+
+        for fault in faults_for(counts) {
+            let mut display = faulty_display(true)?;
+            display.interface_mut().set_fault(Some(fault));
+            let result = display.deep_sleep(&mut MockDelay, DeepSleepMode::PreserveRam);
+            assert!(result.is_err(), "{fault:?}");
+            assert!(!display.is_display_on(), "{fault:?}");
+
+            display.interface_mut().set_fault(None);
+            display.deep_sleep(&mut MockDelay, DeepSleepMode::PreserveRam)?;
+            let emulator = display.interface().inner();
+            assert!(!emulator.is_analog_on(), "{fault:?}: left powered");
+            assert!(emulator.is_asleep(), "{fault:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_clears_power_state() -> TestResult {
+        let mut display = faulty_display(true)?;
+        assert!(display.is_display_on());
+        display.reset(&mut MockDelay)?;
+        assert!(!display.is_display_on());
+
+        // The next refresh powers the (reset) controller back on
+        display.update(&black(), &[], &mut MockDelay)?;
+        assert!(shows(&display, &black()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_stuck_busy_persists_until_cleared() -> TestResult {
+        let mut display = faulty_display(false)?;
+        display.interface_mut().set_fault(Some(Fault::StuckBusy(0)));
+        for _ in 0..2 {
+            let result = display.update(&black(), &[], &mut MockDelay);
+            assert!(matches!(result, Err(Error::Interface(FaultError::Timeout))));
+        }
+        assert_eq!(display.interface().injected(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_data_is_forwarded() -> TestResult {
+        let mut display = faulty_display(false)?;
+        let image = black();
+        // Transfer 0 is DATA_ENTRY_MODE, 1-4 the RAM window, 5 the BW RAM
+        display.interface_mut().set_fault(Some(Fault::CorruptData {
+            transfer: 5,
+            mask: 0xFF,
+        }));
+        display.update(&image, &[], &mut MockDelay)?;
+        assert_eq!(display.interface().injected(), 1);
+
+        let mut corrupted = image;
+        corrupted[0] ^= 0xFF;
+        assert!(shows(&display, &corrupted));
+        assert_eq!(display.interface().inner().bw_ram()[1..], image[1..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unfaulted_operations_are_forwarded() -> TestResult {
+        let mut injector =
+            FaultInjector::new(Emulator::new(Dimensions::new(16, 16)?)).fault(Fault::Command(1));
+        injector.send_command(DISPLAY_UPDATE_CTRL2)?;
+        assert_eq!(
+            injector.send_command(MASTER_ACTIVATION),
+            Err(FaultError::Spi)
+        );
+        injector.send_data(&[0xC7])?;
+        assert_eq!(
+            injector.inner().register(DISPLAY_UPDATE_CTRL2),
+            Some(&[0xC7][..])
+        );
+        assert_eq!((injector.commands(), injector.data_transfers()), (2, 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_corrupt_data_is_one_transfer() -> TestResult {
+        let recorder = Recorder::new(Emulator::new(Dimensions::new(16, 16)?));
+        let mut injector = FaultInjector::new(recorder).fault(Fault::CorruptData {
+            transfer: 0,
+            mask: 0x0F,
+        });
+        injector.send_data(&[0xC7, 0x01, 0x02])?;
+        let events: Vec<Event> = injector
+            .inner()
+            .trace()
+            .entries()
+            .iter()
+            .map(|entry| entry.event.clone())
+            .collect();
+        assert_eq!(events, [Event::Data(alloc::vec![0xC8, 0x01, 0x02])]);
+        assert_eq!(injector.injected(), 1);
+        Ok(())
+    }
+}
@@ -10,6 +10,9 @@
 //! - Image support via embedded-graphics image modules
 //! - Rotation support
 //! - Efficient pixel buffer management
+//! - Reading pixels back via [`GetPixel`](embedded_graphics_core::image::GetPixel)
+//! - XOR/OR/AND drawing ([`RasterOp`]) and region inversion
+//! - Single-plane [`BwGraphicDisplay`] for black/white panels
 //!
 //! ## Example
 //!
@@ -93,15 +96,148 @@ use core::convert::Infallible;
 use embedded_graphics_core::{
     draw_target::DrawTarget,
     geometry::{OriginDimensions, Point, Size},
+    image::GetPixel,
+    pixelcolor::BinaryColor,
     prelude::Pixel,
+    primitives::Rectangle,
 };
 use embedded_hal::delay::DelayNs;
 
 use crate::color::Color;
-use crate::display::Display;
+use crate::config::{Config, Dimensions, MAX_GATE_OUTPUTS, MAX_SOURCE_OUTPUTS, Mirror, Rotation};
+use crate::display::{Display, PendingUpdate, RefreshMode, Region};
 use crate::error::Error;
 use crate::interface::DisplayInterface;
-use crate::rotation::apply_rotation;
+use crate::rotation::apply_orientation;
+
+/// Bytes per plane of a `width` x `height` [`Framebuffer`]
+///
+/// Use it for the `N` parameter: `Framebuffer<800, 480, { framebuffer_size(800, 480) }>`.
+pub const fn framebuffer_size(width: usize, height: usize) -> usize {
+    width / 8 * height
+}
+
+/// Black/white and red planes sized for a `W` x `H` panel at compile time
+///
+/// `W` and `H` are the panel's native width (columns) and height (rows). `N`
+/// is the size of each plane in bytes and must be
+/// [`framebuffer_size(W, H)`](framebuffer_size); stable Rust cannot compute it
+/// from `W` and `H` in the type, so it is spelled out and checked instead.
+/// A mismatch, or a size the controller doesn't support, fails the build:
+///
+/// ```compile_fail
+/// use ssd1677::graphics::Framebuffer;
+///
+/// let framebuffer = Framebuffer::<800, 480, 4800>::new();
+/// ```
+///
+/// Pass it to [`GraphicDisplay::with_framebuffer`]. A type alias keeps the
+/// size in one place:
+///
+/// ```
+/// use ssd1677::graphics::{Framebuffer, framebuffer_size};
+///
+/// type Screen = Framebuffer<800, 480, { framebuffer_size(800, 480) }>;
+///
+/// assert_eq!(Screen::DIMENSIONS.cols, 800);
+/// assert_eq!(Screen::new().black().len(), 48_000);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Framebuffer<const W: usize, const H: usize, const N: usize> {
+    black: [u8; N],
+    red: [u8; N],
+}
+
+impl<const W: usize, const H: usize, const N: usize> Framebuffer<W, H, N> {
+    /// Panel dimensions described by `W` and `H`
+    pub const DIMENSIONS: Dimensions = {
+        assert!(
+            W > 0 && W <= MAX_SOURCE_OUTPUTS as usize && W % 8 == 0,
+            "framebuffer width must be a multiple of 8 within the controller's source outputs"
+        );
+        assert!(
+            H > 0 && H <= MAX_GATE_OUTPUTS as usize,
+            "framebuffer height must be within the controller's gate outputs"
+        );
+        assert!(
+            N == framebuffer_size(W, H),
+            "framebuffer plane size N must equal framebuffer_size(W, H)"
+        );
+        Dimensions {
+            rows: H as u16,
+            cols: W as u16,
+        }
+    };
+
+    /// Create a framebuffer cleared to white
+    pub const fn new() -> Self {
+        let _ = Self::DIMENSIONS;
+        Self {
+            black: [0xFF; N],
+            red: [0x00; N],
+        }
+    }
+
+    /// Black/white plane
+    pub const fn black(&self) -> &[u8; N] {
+        &self.black
+    }
+
+    /// Red plane
+    pub const fn red(&self) -> &[u8; N] {
+        &self.red
+    }
+}
+
+impl<const W: usize, const H: usize, const N: usize> Default for Framebuffer<W, H, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How drawn pixels combine with the pixels already in the buffers
+///
+/// The operation is applied to each plane separately, with the drawing
+/// color's bit for that plane as the source: the BW bit is 1 for white and
+/// red, the RED bit is 1 only for red (see [`Color`]).
+///
+/// | Mode   | White   | Black     | Red                  |
+/// |--------|---------|-----------|----------------------|
+/// | `Copy` | white   | black     | red                  |
+/// | `Xor`  | invert  | unchanged | invert, toggle red   |
+/// | `Or`   | white   | unchanged | red                  |
+/// | `And`  | no red  | black     | unchanged            |
+///
+/// Since red takes precedence when rendered, red pixels stay red under `Xor`
+/// and `Or` with white; use `Copy` to paint over them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RasterOp {
+    /// Overwrite the pixel with the drawing color
+    #[default]
+    Copy,
+    /// Exclusive-or, e.g. drawing white inverts
+    Xor,
+    /// Bitwise or, e.g. drawing white lightens
+    Or,
+    /// Bitwise and, e.g. drawing black darkens
+    And,
+}
+
+impl RasterOp {
+    /// Combine the bits selected by `mask` in `byte` with `source`
+    ///
+    /// Returns whether `byte` changed.
+    fn apply(self, byte: &mut u8, mask: u8, source: bool) -> bool {
+        let before = *byte;
+        match (self, source) {
+            (Self::Copy | Self::Or, true) => *byte |= mask,
+            (Self::Copy | Self::And, false) => *byte &= !mask,
+            (Self::Xor, true) => *byte ^= mask,
+            (Self::Xor | Self::Or, false) | (Self::And, true) => {}
+        }
+        *byte != before
+    }
+}
 
 /// Display with graphics buffers
 ///
@@ -173,10 +309,16 @@ where
     black_buffer: B1,
     /// Buffer for red pixels
     red_buffer: B2,
+    /// How drawing combines with the buffers
+    raster_op: RasterOp,
 }
 
 type GraphicsResult<I> = core::result::Result<(), Error<I>>;
 type GraphicsNewResult<I, T> = core::result::Result<T, Error<I>>;
+/// Byte index and bit mask of one pixel in the buffers
+type BufferBit = (usize, u8);
+/// [`GraphicDisplay`] with a zero-sized red plane, backing [`BwGraphicDisplay`]
+type WithoutRed<I, B> = GraphicDisplay<I, B, [u8; 0]>;
 
 impl<I, B1, B2> GraphicDisplay<I, B1, B2>
 where
@@ -196,7 +338,9 @@ where
     ///
     /// Panics if either buffer is smaller than the required size based on
     /// **physical** (unrotated) dimensions. The buffer size is always calculated
-    /// from physical dimensions regardless of rotation setting.
+    /// from physical dimensions regardless of rotation setting. Use
+    /// [`with_framebuffer`](GraphicDisplay::with_framebuffer) to have the
+    /// sizes checked at compile time instead.
     ///
     /// ## Example
     ///
@@ -261,6 +405,7 @@ where
             display,
             black_buffer,
             red_buffer,
+            raster_op: RasterOp::Copy,
         }
     }
 
@@ -300,6 +445,7 @@ where
             display,
             black_buffer,
             red_buffer,
+            raster_op: RasterOp::Copy,
         })
     }
 
@@ -363,14 +509,33 @@ where
     /// graphic_display.clear(Color::Black);
     /// ```
     pub fn clear(&mut self, color: Color) {
+        self.clear_changed(color);
+    }
+
+    /// [`clear`](GraphicDisplay::clear), returning whether any buffer byte changed
+    pub(crate) fn clear_changed(&mut self, color: Color) -> bool {
         let (bw, red) = (color.bw_byte(), color.red_byte());
+        let mut changed = false;
 
         for byte in self.black_buffer.as_mut().iter_mut() {
+            changed |= *byte != bw;
             *byte = bw;
         }
         for byte in self.red_buffer.as_mut().iter_mut() {
+            changed |= *byte != red;
             *byte = red;
         }
+        changed
+    }
+
+    /// Draw `pixels`, returning whether any buffer bit changed
+    pub(crate) fn draw_changed(&mut self, pixels: impl IntoIterator<Item = Pixel<Color>>) -> bool {
+        self.canvas().draw_iter(pixels)
+    }
+
+    /// Fill `area` with `color`, returning whether any buffer bit changed
+    pub(crate) fn fill_changed(&mut self, area: &Rectangle, color: Color) -> bool {
+        self.canvas().fill_solid(area, color)
     }
 
     /// Update the display from buffers using full refresh
@@ -523,6 +688,43 @@ where
         )
     }
 
+    /// Start an update without waiting for the refresh to finish
+    ///
+    /// See [`Display::begin_update_with_mode`]. Don't draw until
+    /// [`finish_update`](GraphicDisplay::finish_update) has been called: a
+    /// fast refresh copies the buffers to the panel again when it ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Interface`] if there's a communication error.
+    pub fn begin_update_with_mode(
+        &mut self,
+        mode: RefreshMode,
+    ) -> GraphicsNewResult<I, PendingUpdate> {
+        self.display.begin_update_with_mode(
+            self.black_buffer.as_mut(),
+            self.red_buffer.as_mut(),
+            mode,
+        )
+    }
+
+    /// Wait for an update started by
+    /// [`begin_update_with_mode`](GraphicDisplay::begin_update_with_mode) to
+    /// finish
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Interface`] if there's a communication error or the
+    /// busy wait times out.
+    pub fn finish_update<D: DelayNs>(
+        &mut self,
+        pending: PendingUpdate,
+        delay: &mut D,
+    ) -> GraphicsResult<I> {
+        self.display
+            .finish_update(pending, self.black_buffer.as_mut(), delay)
+    }
+
     /// Access the underlying Display
     ///
     /// Returns an immutable reference to the wrapped [`Display`].
@@ -638,41 +840,422 @@ where
         &mut self.display
     }
 
-    /// Set a single pixel to a color
+    /// Packed BW plane, in the layout sent to the controller
+    ///
+    /// Bit 1 is white (or red); see [`Color`] for the encoding. Pixels are
+    /// stored in physical (unrotated, unmirrored) order.
+    pub fn black_buffer(&self) -> &[u8]
+    where
+        B1: AsRef<[u8]>,
+    {
+        self.black_buffer.as_ref()
+    }
+
+    /// Packed RED plane, in the layout sent to the controller
     ///
-    /// Internal method used by the [`DrawTarget`] implementation.
-    /// Applies rotation transformation and updates both buffers appropriately.
-    fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
-        let dims = self.display.dimensions();
-        let width = dims.cols as u32;
-        let height = dims.rows as u32;
+    /// Empty for BW-only displays.
+    pub fn red_buffer(&self) -> &[u8]
+    where
+        B2: AsRef<[u8]>,
+    {
+        self.red_buffer.as_ref()
+    }
 
-        if x >= width || y >= height {
-            return;
+    /// Packed BW plane, mutably
+    ///
+    /// Any byte pattern is a valid image, so the planes can be edited
+    /// directly, e.g. to invert a region.
+    pub fn black_buffer_mut(&mut self) -> &mut [u8] {
+        self.black_buffer.as_mut()
+    }
+
+    /// Packed RED plane, mutably
+    pub fn red_buffer_mut(&mut self) -> &mut [u8] {
+        self.red_buffer.as_mut()
+    }
+
+    /// Take the GraphicDisplay apart
+    ///
+    /// Returns the underlying [`Display`] together with the black and red
+    /// buffers, e.g. to reuse them for another panel or to hand the driver
+    /// to code that does not use embedded-graphics.
+    #[allow(clippy::type_complexity)] // a plain tuple of the three parts
+    pub fn into_parts(self) -> (Display<I>, B1, B2) {
+        (self.display, self.black_buffer, self.red_buffer)
+    }
+
+    /// Raster operation used for drawing
+    pub fn raster_op(&self) -> RasterOp {
+        self.raster_op
+    }
+
+    /// Choose how subsequent drawing combines with the buffers
+    ///
+    /// Applies to everything drawn through [`DrawTarget`] except
+    /// [`clear`](GraphicDisplay::clear), which always overwrites.
+    pub fn set_raster_op(&mut self, op: RasterOp) {
+        self.raster_op = op;
+    }
+
+    /// Invert black and white within `area` (drawing coordinates)
+    ///
+    /// Same as filling `area` with white in [`RasterOp::Xor`] mode, whatever
+    /// the current mode, so calling it twice restores the buffers. Red
+    /// pixels are left red. The area is clipped to the screen.
+    pub fn invert_region(&mut self, area: &Rectangle) {
+        let mut canvas = self.canvas();
+        canvas.raster_op = RasterOp::Xor;
+        canvas.fill_solid(area, Color::White);
+    }
+
+    /// Create an overlay layer for [`compose`](GraphicDisplay::compose)
+    ///
+    /// The layer has the display's size and orientation and starts out
+    /// transparent. Both buffers are needed even for BW-only images, since
+    /// transparency takes a bit pattern of its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BufferTooSmall` if either buffer is smaller than
+    /// `dimensions.buffer_size()`.
+    #[allow(clippy::type_complexity)] // a plain result wrapping the layer
+    pub fn new_layer<L1, L2>(&self, black: L1, red: L2) -> GraphicsNewResult<I, Layer<L1, L2>>
+    where
+        L1: AsMut<[u8]>,
+        L2: AsMut<[u8]>,
+    {
+        Layer::for_config(self.display.config(), black, red)
+    }
+
+    /// Paint `layers` over the buffers, bottom to top
+    ///
+    /// Opaque layer pixels replace what is below them; transparent ones let
+    /// it show through. The buffers are overwritten, so keep anything that
+    /// must survive recomposition in a layer too, typically a fully opaque
+    /// background at the bottom. Layers must come from
+    /// [`new_layer`](GraphicDisplay::new_layer) on this display.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDimensions`] with the layer's size if a layer
+    /// was made for a different panel. The layers below it have already been
+    /// painted.
+    pub fn compose<'l, L1, L2>(
+        &mut self,
+        layers: impl IntoIterator<Item = &'l Layer<L1, L2>>,
+    ) -> GraphicsResult<I>
+    where
+        L1: AsRef<[u8]> + 'l,
+        L2: AsRef<[u8]> + 'l,
+    {
+        let dimensions = *self.display.dimensions();
+        let size = dimensions.buffer_size();
+        let black = &mut self.black_buffer.as_mut()[..size];
+        let red = self.red_buffer.as_mut().get_mut(..size).unwrap_or_default();
+        for layer in layers {
+            if layer.dimensions != dimensions {
+                return Err(Error::InvalidDimensions {
+                    rows: layer.dimensions.rows,
+                    cols: layer.dimensions.cols,
+                });
+            }
+            layer.paint_over(black, red);
         }
+        Ok(())
+    }
 
-        let rotation = self.display.rotation();
-        let (index, bit) = apply_rotation(x, y, width, height, rotation);
+    /// [`compose`](GraphicDisplay::compose) the layers, then update the display
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDimensions`] if a layer was made for a
+    /// different panel (nothing is sent), or [`Error::Interface`] if there's a
+    /// communication error.
+    pub fn update_with_layers<'l, L1, L2, D>(
+        &mut self,
+        layers: impl IntoIterator<Item = &'l Layer<L1, L2>>,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> GraphicsResult<I>
+    where
+        L1: AsRef<[u8]> + 'l,
+        L2: AsRef<[u8]> + 'l,
+        D: DelayNs,
+    {
+        self.compose(layers)?;
+        self.update_with_mode(mode, delay)
+    }
 
-        if index >= self.black_buffer.as_mut().len() {
-            return;
+    /// Drawing view of the buffers in the current orientation and mode
+    fn canvas(&mut self) -> Canvas<'_> {
+        let config = self.display.config();
+        Canvas {
+            black: self.black_buffer.as_mut(),
+            red: self.red_buffer.as_mut(),
+            dimensions: config.dimensions,
+            rotation: config.rotation,
+            mirror: config.software_mirror(),
+            raster_op: self.raster_op,
         }
+    }
 
-        match color {
-            Color::Black => {
-                self.black_buffer.as_mut()[index] &= !bit;
-                self.red_buffer.as_mut()[index] &= !bit;
-            }
-            Color::White => {
-                self.black_buffer.as_mut()[index] |= bit;
-                self.red_buffer.as_mut()[index] &= !bit;
-            }
-            Color::Red => {
-                self.black_buffer.as_mut()[index] |= bit;
-                self.red_buffer.as_mut()[index] |= bit;
+    /// Capture the buffers as an image in drawing coordinates
+    ///
+    /// The snapshot honors the configured rotation and mirroring, so pixel
+    /// `(x, y)` of the snapshot is the pixel drawn at `Point::new(x, y)`. See
+    /// [`snapshot`](crate::snapshot) for exporting and comparing it.
+    #[cfg(any(test, feature = "std"))]
+    pub fn snapshot(&self) -> crate::snapshot::Snapshot
+    where
+        B1: AsRef<[u8]>,
+        B2: AsRef<[u8]>,
+    {
+        let config = self.display.config();
+        crate::snapshot::Snapshot::decode_buffers(
+            self.black_buffer(),
+            self.red_buffer(),
+            config.dimensions,
+            config.rotation,
+            config.software_mirror(),
+        )
+    }
+
+    /// Byte index and bit mask of drawing coordinates `(x, y)`
+    ///
+    /// Returns `None` outside the rotated dimensions.
+    fn buffer_bit(&self, x: u32, y: u32) -> Option<BufferBit> {
+        let config = self.display.config();
+        let logical = config.rotated_dimensions();
+        if x >= logical.cols as u32 || y >= logical.rows as u32 {
+            return None;
+        }
+
+        let physical = config.dimensions;
+        Some(apply_orientation(
+            x,
+            y,
+            physical.cols as u32,
+            physical.rows as u32,
+            config.rotation,
+            config.software_mirror(),
+        ))
+    }
+}
+
+impl<I, const N: usize> GraphicDisplay<I, [u8; N], [u8; N]>
+where
+    I: DisplayInterface,
+{
+    /// Create a GraphicDisplay backed by a statically sized [`Framebuffer`]
+    ///
+    /// The panel size is part of the framebuffer's type, so the buffers
+    /// always fit the configuration built from [`Framebuffer::DIMENSIONS`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDimensions`] with the configured size if
+    /// `config.dimensions` differs from [`Framebuffer::DIMENSIONS`], i.e. the
+    /// configuration was built for a different panel.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// use ssd1677::graphics::{Framebuffer, framebuffer_size};
+    /// use ssd1677::{Builder, Color, GraphicDisplay, Rotation};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::digital::{InputPin, OutputPin};
+    /// # use embedded_hal::spi::{Operation, SpiDevice};
+    /// # use ssd1677::Interface;
+    /// # struct MockSpi;
+    /// # impl embedded_hal::spi::ErrorType for MockSpi { type Error = Infallible; }
+    /// # impl SpiDevice for MockSpi {
+    /// #     fn transaction(
+    /// #         &mut self,
+    /// #         _operations: &mut [Operation<'_, u8>],
+    /// #     ) -> Result<(), Self::Error> {
+    /// #         Ok(())
+    /// #     }
+    /// # }
+    /// # struct MockPin;
+    /// # impl embedded_hal::digital::ErrorType for MockPin { type Error = Infallible; }
+    /// # impl OutputPin for MockPin {
+    /// #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    /// # }
+    /// # impl InputPin for MockPin {
+    /// #     fn is_high(&mut self) -> Result<bool, Self::Error> { Ok(false) }
+    /// #     fn is_low(&mut self) -> Result<bool, Self::Error> { Ok(true) }
+    /// # }
+    /// # let interface = Interface::new(MockSpi, MockPin, MockPin, MockPin);
+    /// type Screen = Framebuffer<800, 480, { framebuffer_size(800, 480) }>;
+    ///
+    /// let builder = Builder::new().dimensions(Screen::DIMENSIONS);
+    /// let Ok(config) = builder.rotation(Rotation::Rotate90).build() else {
+    ///     return;
+    /// };
+    /// let Ok(mut display) = GraphicDisplay::with_framebuffer(interface, config, Screen::new()) else {
+    ///     return;
+    /// };
+    /// display.clear(Color::White);
+    /// ```
+    #[allow(clippy::needless_pass_by_value)] // the planes move into the display
+    pub fn with_framebuffer<const W: usize, const H: usize>(
+        interface: I,
+        config: Config,
+        framebuffer: Framebuffer<W, H, N>,
+    ) -> GraphicsNewResult<I, Self> {
+        let Framebuffer { black, red } = framebuffer;
+        if config.dimensions != Framebuffer::<W, H, N>::DIMENSIONS {
+            return Err(Error::InvalidDimensions {
+                rows: config.dimensions.rows,
+                cols: config.dimensions.cols,
+            });
+        }
+        Ok(Self {
+            display: Display::new(interface, config),
+            black_buffer: black,
+            red_buffer: red,
+            raster_op: RasterOp::Copy,
+        })
+    }
+}
+
+/// Combine one pixel with `color` in both planes, returning whether it changed
+///
+/// Indexes outside a buffer are ignored, so an empty red buffer makes the
+/// image BW-only.
+fn paint(
+    black: &mut [u8],
+    red: &mut [u8],
+    index: usize,
+    bit: u8,
+    color: Color,
+    op: RasterOp,
+) -> bool {
+    let black = black
+        .get_mut(index)
+        .is_some_and(|byte| op.apply(byte, bit, color != Color::Black));
+    let red = red
+        .get_mut(index)
+        .is_some_and(|byte| op.apply(byte, bit, color == Color::Red));
+    black || red
+}
+
+/// Buffer location of the pixel to the right (in drawing coordinates) of `(index, bit)`
+///
+/// A horizontal mirror turns steps to the right into steps to the left,
+/// which are steps to the right at the opposite rotation.
+fn step_right(
+    index: usize,
+    bit: u8,
+    row_bytes: usize,
+    rotation: Rotation,
+    mirror: Mirror,
+) -> (usize, u8) {
+    let rotation = match (rotation, mirror.horizontal()) {
+        (rotation, false) => rotation,
+        (Rotation::Rotate0, true) => Rotation::Rotate180,
+        (Rotation::Rotate90, true) => Rotation::Rotate270,
+        (Rotation::Rotate180, true) => Rotation::Rotate0,
+        (Rotation::Rotate270, true) => Rotation::Rotate90,
+    };
+    match rotation {
+        Rotation::Rotate0 if bit == 0x01 => (index + 1, 0x80),
+        Rotation::Rotate0 => (index, bit >> 1),
+        Rotation::Rotate90 => (index + row_bytes, bit),
+        Rotation::Rotate180 if bit == 0x80 => (index.wrapping_sub(1), 0x01),
+        Rotation::Rotate180 => (index, bit << 1),
+        Rotation::Rotate270 => (index.wrapping_sub(row_bytes), bit),
+    }
+}
+
+/// Reflect a rectangle within `bounds` along the axes flipped by `mirror`
+fn mirror_area(area: &Rectangle, bounds: Size, mirror: Mirror) -> Rectangle {
+    // The far edges, reflected, become the near edges
+    let reflected = (Point::zero() + bounds) - (area.top_left + area.size);
+    let top_left = Point::new(
+        if mirror.horizontal() {
+            reflected.x
+        } else {
+            area.top_left.x
+        },
+        if mirror.vertical() {
+            reflected.y
+        } else {
+            area.top_left.y
+        },
+    );
+    Rectangle::new(top_left, area.size)
+}
+
+/// Map a rectangle in drawing coordinates to panel (unrotated) coordinates
+///
+/// `area` must lie within the rotated bounds.
+fn physical_area(
+    area: &Rectangle,
+    dims: Dimensions,
+    rotation: Rotation,
+    mirror: Mirror,
+) -> Rectangle {
+    let (cols, rows) = (i32::from(dims.cols), i32::from(dims.rows));
+    let logical = match rotation {
+        Rotation::Rotate0 | Rotation::Rotate180 => Size::new(cols as u32, rows as u32),
+        Rotation::Rotate90 | Rotation::Rotate270 => Size::new(rows as u32, cols as u32),
+    };
+    let Rectangle {
+        top_left: Point { x, y },
+        size,
+    } = mirror_area(area, logical, mirror);
+    let (w, h) = (i32::from(size.width as u16), i32::from(size.height as u16));
+    let transposed = Size::new(size.height, size.width);
+    match rotation {
+        Rotation::Rotate0 => Rectangle::new(Point::new(x, y), size),
+        Rotation::Rotate90 => Rectangle::new(Point::new(cols - y - h, x), transposed),
+        Rotation::Rotate180 => Rectangle::new(Point::new(cols - x - w, rows - y - h), size),
+        Rotation::Rotate270 => Rectangle::new(Point::new(y, rows - x - w), transposed),
+    }
+}
+
+/// Combine every bit of a panel rectangle in one plane with `source`
+///
+/// Works a byte at a time, masking the partial bytes at either end of each
+/// row. Rows outside the plane are ignored, as in [`paint`]. Returns whether
+/// any byte changed.
+fn fill_plane(
+    plane: &mut [u8],
+    row_bytes: usize,
+    area: &Rectangle,
+    op: RasterOp,
+    source: bool,
+) -> bool {
+    let mut changed = false;
+    let start = area.top_left.x as usize;
+    let end = start + area.size.width as usize - 1;
+    let (first, last) = (start / 8, end / 8);
+    let head = 0xFF >> (start % 8);
+    let tail = 0xFF << (7 - end % 8);
+    for y in area.rows() {
+        let offset = y as usize * row_bytes;
+        let Some(row) = plane.get_mut(offset + first..=offset + last) else {
+            continue;
+        };
+        if let [byte] = row {
+            changed |= op.apply(byte, head & tail, source);
+        } else if let [head_byte, middle @ .., tail_byte] = row {
+            changed |= op.apply(head_byte, head, source);
+            for byte in middle {
+                changed |= op.apply(byte, 0xFF, source);
             }
+            changed |= op.apply(tail_byte, tail, source);
         }
     }
+    changed
+}
+
+fn skip<T>(iter: &mut impl Iterator<Item = T>, count: u32) {
+    if count > 0 {
+        iter.nth(count as usize - 1);
+    }
 }
 
 impl<I, B1, B2> DrawTarget for GraphicDisplay<I, B1, B2>
@@ -688,24 +1271,52 @@ where
     where
         Iter: IntoIterator<Item = Pixel<Self::Color>>,
     {
-        let sz = self.size();
+        self.canvas().draw_iter(pixels);
+        Ok(())
+    }
 
-        for Pixel(Point { x, y }, color) in pixels {
-            if x < 0 || y < 0 {
-                continue;
-            }
+    fn fill_contiguous<Iter>(&mut self, area: &Rectangle, colors: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Self::Color>,
+    {
+        self.canvas().fill_contiguous(area, colors);
+        Ok(())
+    }
 
-            let x = x as u32;
-            let y = y as u32;
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.canvas().fill_solid(area, color);
+        Ok(())
+    }
 
-            if x >= sz.width || y >= sz.height {
-                continue;
-            }
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        GraphicDisplay::clear(self, color);
+        Ok(())
+    }
+}
 
-            self.set_pixel(x, y, color);
-        }
+impl<I, B1, B2> GetPixel for GraphicDisplay<I, B1, B2>
+where
+    I: DisplayInterface,
+    B1: AsMut<[u8]> + AsRef<[u8]>,
+    B2: AsMut<[u8]> + AsRef<[u8]>,
+{
+    type Color = Color;
 
-        Ok(())
+    /// Color in the buffers at drawing coordinates `p`
+    ///
+    /// Reads what has been drawn, not what the panel currently shows; the two
+    /// differ until the next update. An empty red buffer reads as BW-only.
+    fn pixel(&self, p: Point) -> Option<Color> {
+        let x = u32::try_from(p.x).ok()?;
+        let y = u32::try_from(p.y).ok()?;
+        let (index, bit) = self.buffer_bit(x, y)?;
+        let bw = self.black_buffer.as_ref().get(index)? & bit != 0;
+        let red = self
+            .red_buffer
+            .as_ref()
+            .get(index)
+            .is_some_and(|byte| byte & bit != 0);
+        Some(Color::from_bits(bw, red))
     }
 }
 
@@ -721,45 +1332,924 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::{Builder, Dimensions, Rotation};
-    use embedded_hal::delay::DelayNs;
+/// Pair of planes in panel layout, drawn in rotated drawing coordinates
+///
+/// Does the work behind the [`DrawTarget`] implementations of
+/// [`GraphicDisplay`] and [`Layer`].
+struct Canvas<'a> {
+    black: &'a mut [u8],
+    red: &'a mut [u8],
+    dimensions: Dimensions,
+    rotation: Rotation,
+    mirror: Mirror,
+    raster_op: RasterOp,
+}
 
-    #[derive(Debug)]
-    struct MockInterface;
+impl Canvas<'_> {
+    /// Drawing area, sized by the rotated dimensions
+    fn bounds(&self) -> Rectangle {
+        let (cols, rows) = (
+            u32::from(self.dimensions.cols),
+            u32::from(self.dimensions.rows),
+        );
+        let size = match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => Size::new(cols, rows),
+            Rotation::Rotate90 | Rotation::Rotate270 => Size::new(rows, cols),
+        };
+        Rectangle::new(Point::zero(), size)
+    }
 
-    impl DisplayInterface for MockInterface {
-        type Error = core::convert::Infallible;
+    /// Draw `pixels`, returning whether any bit changed
+    fn draw_iter(&mut self, pixels: impl IntoIterator<Item = Pixel<Color>>) -> bool {
+        let mut changed = false;
+        let bounds = self.bounds();
+        let (cols, rows) = (
+            u32::from(self.dimensions.cols),
+            u32::from(self.dimensions.rows),
+        );
+        for Pixel(point, color) in pixels {
+            if !bounds.contains(point) {
+                continue;
+            }
+            let (index, bit) = apply_orientation(
+                point.x as u32,
+                point.y as u32,
+                cols,
+                rows,
+                self.rotation,
+                self.mirror,
+            );
+            changed |= paint(self.black, self.red, index, bit, color, self.raster_op);
+        }
+        changed
+    }
 
-        fn send_command(&mut self, _command: u8) -> Result<(), Self::Error> {
-            Ok(())
+    fn fill_contiguous(&mut self, area: &Rectangle, colors: impl IntoIterator<Item = Color>) {
+        let clipped = area.intersection(&self.bounds());
+        if clipped.is_zero_sized() {
+            return;
         }
+        let (dims, rotation, mirror) = (self.dimensions, self.rotation, self.mirror);
+        let (op, black, red) = (self.raster_op, &mut *self.black, &mut *self.red);
 
-        fn send_data(&mut self, _data: &[u8]) -> Result<(), Self::Error> {
-            Ok(())
+        // Colors cover all of `area` row by row; skip the ones that are clipped
+        let mut colors = colors.into_iter();
+        let left = (clipped.top_left.x - area.top_left.x) as u32;
+        let right = area.size.width - left - clipped.size.width;
+        skip(
+            &mut colors,
+            (clipped.top_left.y - area.top_left.y) as u32 * area.size.width,
+        );
+        for y in clipped.rows() {
+            skip(&mut colors, left);
+            let (mut index, mut bit) = apply_orientation(
+                clipped.top_left.x as u32,
+                y as u32,
+                dims.cols as u32,
+                dims.rows as u32,
+                rotation,
+                mirror,
+            );
+            for color in colors.by_ref().take(clipped.size.width as usize) {
+                paint(black, red, index, bit, color, op);
+                (index, bit) = step_right(index, bit, dims.cols as usize / 8, rotation, mirror);
+            }
+            skip(&mut colors, right);
         }
+    }
 
-        fn reset<D: DelayNs>(&mut self, _delay: &mut D) {}
+    /// Fill a rectangle a byte at a time, returning whether any bit changed
+    fn fill_solid(&mut self, area: &Rectangle, color: Color) -> bool {
+        self.fill_planes(area, color != Color::Black, color == Color::Red)
+    }
 
-        fn busy_wait<D: DelayNs>(&mut self, _delay: &mut D) -> Result<(), Self::Error> {
-            Ok(())
+    /// Combine the bits of `area` with `black` and `red` in the two planes
+    fn fill_planes(&mut self, area: &Rectangle, black: bool, red: bool) -> bool {
+        let clipped = area.intersection(&self.bounds());
+        if clipped.is_zero_sized() {
+            return false;
         }
+        let physical = physical_area(&clipped, self.dimensions, self.rotation, self.mirror);
+        let row_bytes = self.dimensions.cols as usize / 8;
+        let black = fill_plane(self.black, row_bytes, &physical, self.raster_op, black);
+        let red = fill_plane(self.red, row_bytes, &physical, self.raster_op, red);
+        black || red
     }
+}
 
-    fn test_display(rotation: Rotation) -> Display<MockInterface> {
-        let config = Builder::new()
-            .dimensions(Dimensions::new(480, 480).unwrap())
-            .rotation(rotation)
-            .build()
-            .unwrap();
-        Display::new(MockInterface, config)
-    }
+/// BW byte of a fully transparent [`Layer`] span
+///
+/// Transparency is the bit pair no color uses: BW 0 with RED 1.
+const TRANSPARENT_BW: u8 = 0x00;
+/// RED byte of a fully transparent [`Layer`] span
+const TRANSPARENT_RED: u8 = 0xFF;
+
+/// Overlay with transparency, composed into a display's buffers
+///
+/// Created by [`GraphicDisplay::new_layer`], drawn like the display itself,
+/// and painted over the display's buffers by [`GraphicDisplay::compose`] or
+/// [`GraphicDisplay::update_with_layers`]. Keeping a static background and
+/// a frequently changing overlay in separate layers means only the overlay
+/// is redrawn between updates.
+///
+/// Pixels start out transparent; drawn pixels are opaque, and
+/// [`erase_region`](Layer::erase_region) makes them transparent again. A
+/// layer takes the same memory as the display's buffers.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use embedded_graphics::{
+///     prelude::*,
+///     primitives::{PrimitiveStyle, Rectangle},
+/// };
+/// use ssd1677::{Color, GraphicDisplay, RefreshMode};
+/// # use core::convert::Infallible;
+/// # use embedded_hal::delay::DelayNs;
+/// # use embedded_hal::digital::{InputPin, OutputPin};
+/// # use embedded_hal::spi::{Operation, SpiDevice};
+/// # use ssd1677::{Builder, Dimensions, Display, Interface};
+/// # struct MockSpi;
+/// # impl embedded_hal::spi::ErrorType for MockSpi { type Error = Infallible; }
+/// # impl SpiDevice for MockSpi {
+/// #     fn transaction(
+/// #         &mut self,
+/// #         _operations: &mut [Operation<'_, u8>],
+/// #     ) -> Result<(), Self::Error> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # struct MockPin;
+/// # impl embedded_hal::digital::ErrorType for MockPin { type Error = Infallible; }
+/// # impl OutputPin for MockPin {
+/// #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+/// # }
+/// # impl InputPin for MockPin {
+/// #     fn is_high(&mut self) -> Result<bool, Self::Error> { Ok(false) }
+/// #     fn is_low(&mut self) -> Result<bool, Self::Error> { Ok(true) }
+/// # }
+/// # struct MockDelay;
+/// # impl DelayNs for MockDelay { fn delay_ns(&mut self, _ns: u32) {} }
+/// # let interface = Interface::new(MockSpi, MockPin, MockPin, MockPin);
+/// # let dims = match Dimensions::new(480, 800) {
+/// #     Ok(dims) => dims,
+/// #     Err(_) => return,
+/// # };
+/// # let config = match Builder::new().dimensions(dims).build() {
+/// #     Ok(config) => config,
+/// #     Err(_) => return,
+/// # };
+/// # let display = Display::new(interface, config);
+/// # let buffer_size = dims.buffer_size();
+/// # let mut delay = MockDelay;
+/// let mut graphic_display = GraphicDisplay::new(
+///     display,
+///     vec![0u8; buffer_size],
+///     vec![0u8; buffer_size],
+/// );
+/// let layers = (
+///     graphic_display.new_layer(vec![0u8; buffer_size], vec![0u8; buffer_size]),
+///     graphic_display.new_layer(vec![0u8; buffer_size], vec![0u8; buffer_size]),
+/// );
+/// let (Ok(mut background), Ok(mut overlay)) = layers else {
+///     return;
+/// };
+///
+/// // Drawn once
+/// let _ = background.clear(Color::White);
+/// let _ = Rectangle::new(Point::new(0, 0), Size::new(800, 40))
+///     .into_styled(PrimitiveStyle::with_fill(Color::Red))
+///     .draw(&mut background);
+///
+/// // Redrawn every update, leaving the background untouched
+/// for x in (0..800).step_by(40) {
+///     overlay.erase();
+///     let _ = Rectangle::new(Point::new(x, 200), Size::new(40, 40))
+///         .into_styled(PrimitiveStyle::with_fill(Color::Black))
+///         .draw(&mut overlay);
+///     let _ = graphic_display.update_with_layers(
+///         [&background, &overlay],
+///         RefreshMode::Fast,
+///         &mut delay,
+///     );
+/// }
+/// ```
+pub struct Layer<B1, B2> {
+    black: B1,
+    red: B2,
+    dimensions: Dimensions,
+    rotation: Rotation,
+    mirror: Mirror,
+}
+
+impl<B1, B2> Layer<B1, B2>
+where
+    B1: AsMut<[u8]>,
+    B2: AsMut<[u8]>,
+{
+    /// Transparent layer drawn like a display with `config`
+    fn for_config<I: DisplayInterface>(
+        config: &Config,
+        mut black: B1,
+        mut red: B2,
+    ) -> GraphicsNewResult<I, Self> {
+        let required = config.dimensions.buffer_size();
+        for provided in [black.as_mut().len(), red.as_mut().len()] {
+            if provided < required {
+                return Err(Error::BufferTooSmall { required, provided });
+            }
+        }
+        let mut layer = Self {
+            black,
+            red,
+            dimensions: config.dimensions,
+            rotation: config.rotation,
+            mirror: config.software_mirror(),
+        };
+        layer.erase();
+        Ok(layer)
+    }
+
+    /// Make the whole layer transparent
+    pub fn erase(&mut self) {
+        self.black.as_mut().fill(TRANSPARENT_BW);
+        self.red.as_mut().fill(TRANSPARENT_RED);
+    }
+
+    /// Make `area` (drawing coordinates) transparent
+    ///
+    /// The area is clipped to the layer.
+    pub fn erase_region(&mut self, area: &Rectangle) {
+        self.canvas()
+            .fill_planes(area, TRANSPARENT_BW != 0, TRANSPARENT_RED != 0);
+    }
+
+    /// Take the layer apart into its black and red buffers
+    pub fn into_parts(self) -> (B1, B2) {
+        (self.black, self.red)
+    }
+
+    fn canvas(&mut self) -> Canvas<'_> {
+        Canvas {
+            black: self.black.as_mut(),
+            red: self.red.as_mut(),
+            dimensions: self.dimensions,
+            rotation: self.rotation,
+            mirror: self.mirror,
+            raster_op: RasterOp::Copy,
+        }
+    }
+}
+
+impl<B1, B2> Layer<B1, B2>
+where
+    B1: AsRef<[u8]>,
+    B2: AsRef<[u8]>,
+{
+    /// Paint the opaque pixels over a pair of planes
+    ///
+    /// Works a byte at a time: a bit is kept where the layer is transparent
+    /// and replaced everywhere else. An empty `red` is left empty.
+    fn paint_over(&self, black: &mut [u8], red: &mut [u8]) {
+        let planes = || {
+            self.black
+                .as_ref()
+                .iter()
+                .zip(self.red.as_ref())
+                .map(|(b, r)| (*b, *r))
+        };
+        for (byte, (layer_black, layer_red)) in black.iter_mut().zip(planes()) {
+            let transparent = !layer_black & layer_red;
+            *byte = (*byte & transparent) | (layer_black & !transparent);
+        }
+        for (byte, (layer_black, layer_red)) in red.iter_mut().zip(planes()) {
+            let transparent = !layer_black & layer_red;
+            *byte = (*byte & transparent) | (layer_red & !transparent);
+        }
+    }
+}
+
+impl<B1, B2> DrawTarget for Layer<B1, B2>
+where
+    B1: AsMut<[u8]>,
+    B2: AsMut<[u8]>,
+{
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<Iter>(&mut self, pixels: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        self.canvas().draw_iter(pixels);
+        Ok(())
+    }
+
+    fn fill_contiguous<Iter>(&mut self, area: &Rectangle, colors: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Self::Color>,
+    {
+        self.canvas().fill_contiguous(area, colors);
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        self.canvas().fill_solid(area, color);
+        Ok(())
+    }
+}
+
+impl<B1, B2> OriginDimensions for Layer<B1, B2> {
+    fn size(&self) -> Size {
+        let (cols, rows) = (
+            u32::from(self.dimensions.cols),
+            u32::from(self.dimensions.rows),
+        );
+        match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => Size::new(cols, rows),
+            Rotation::Rotate90 | Rotation::Rotate270 => Size::new(rows, cols),
+        }
+    }
+}
+
+/// Display with a single black/white framebuffer
+///
+/// For BW-only panels: like [`GraphicDisplay`], but without a red plane, so
+/// an 800x480 panel needs 48 KB instead of 96 KB. Drawing uses
+/// [`BinaryColor`], with `On` as ink (black) and `Off` as paper (white), so
+/// monochrome fonts and images draw as expected.
+///
+/// Updates go through [`Display::update_with_mode`] without a red buffer:
+/// full and partial refreshes bypass RED RAM (`CTRL1_BYPASS_RED`) and copy
+/// the image into it, so [`RefreshMode::Fast`] stays differential.
+///
+/// ## Example
+///
+/// ```rust,no_run
+/// use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::*};
+/// use ssd1677::BwGraphicDisplay;
+/// # use core::convert::Infallible;
+/// # use embedded_hal::digital::{InputPin, OutputPin};
+/// # use embedded_hal::spi::{Operation, SpiDevice};
+/// # use ssd1677::{Builder, Dimensions, Display, Interface};
+/// # struct MockSpi;
+/// # impl embedded_hal::spi::ErrorType for MockSpi { type Error = Infallible; }
+/// # impl SpiDevice for MockSpi {
+/// #     fn transaction(
+/// #         &mut self,
+/// #         _operations: &mut [Operation<'_, u8>],
+/// #     ) -> Result<(), Self::Error> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # struct MockPin;
+/// # impl embedded_hal::digital::ErrorType for MockPin { type Error = Infallible; }
+/// # impl OutputPin for MockPin {
+/// #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+/// #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+/// # }
+/// # impl InputPin for MockPin {
+/// #     fn is_high(&mut self) -> Result<bool, Self::Error> { Ok(false) }
+/// #     fn is_low(&mut self) -> Result<bool, Self::Error> { Ok(true) }
+/// # }
+/// # let interface = Interface::new(MockSpi, MockPin, MockPin, MockPin);
+/// # let dims = match Dimensions::new(480, 800) {
+/// #     Ok(dims) => dims,
+/// #     Err(_) => return,
+/// # };
+/// # let config = match Builder::new().dimensions(dims).build() {
+/// #     Ok(config) => config,
+/// #     Err(_) => return,
+/// # };
+/// # let display = Display::new(interface, config);
+/// let mut bw_display = BwGraphicDisplay::new(display, [0u8; 800 / 8 * 480]);
+/// bw_display.clear(BinaryColor::Off);
+///
+/// let _ = Circle::new(Point::new(10, 10), 40)
+///     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 2))
+///     .draw(&mut bw_display);
+/// ```
+pub struct BwGraphicDisplay<I, B>
+where
+    I: DisplayInterface,
+    B: AsMut<[u8]>,
+{
+    /// Tri-color display with an empty red plane
+    inner: WithoutRed<I, B>,
+    /// Raster operation as seen by the caller, on ink rather than BW bits
+    raster_op: RasterOp,
+}
+
+impl<I, B> BwGraphicDisplay<I, B>
+where
+    I: DisplayInterface,
+    B: AsMut<[u8]>,
+{
+    /// Create a new BwGraphicDisplay
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is smaller than `dimensions.buffer_size()`, based
+    /// on the physical (unrotated) dimensions.
+    pub fn new(display: Display<I>, mut buffer: B) -> Self {
+        let required = display.dimensions().buffer_size();
+        assert!(
+            buffer.as_mut().len() >= required,
+            "buffer too small: required {} bytes, got {}",
+            required,
+            buffer.as_mut().len()
+        );
+        Self::from_parts(display, buffer)
+    }
+
+    /// Try to create a new BwGraphicDisplay
+    ///
+    /// This is the fallible version of [`new`](Self::new).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BufferTooSmall` if `buffer` is smaller than the
+    /// required size.
+    pub fn try_new(display: Display<I>, mut buffer: B) -> GraphicsNewResult<I, Self> {
+        let required = display.dimensions().buffer_size();
+        if buffer.as_mut().len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                provided: buffer.as_mut().len(),
+            });
+        }
+        Ok(Self::from_parts(display, buffer))
+    }
+
+    fn from_parts(display: Display<I>, buffer: B) -> Self {
+        Self {
+            inner: GraphicDisplay {
+                display,
+                black_buffer: buffer,
+                red_buffer: [],
+                raster_op: RasterOp::Copy,
+            },
+            raster_op: RasterOp::Copy,
+        }
+    }
+
+    /// Clear the buffer to a single color
+    pub fn clear(&mut self, color: BinaryColor) {
+        self.inner.clear(Color::from(color));
+    }
+
+    /// Update the display from the buffer using full refresh
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Interface`] if there's a communication error.
+    pub fn update<D: DelayNs>(&mut self, delay: &mut D) -> GraphicsResult<I> {
+        self.inner.update(delay)
+    }
+
+    /// Update the display from the buffer using the given refresh mode
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Interface`] if there's a communication error.
+    pub fn update_with_mode<D: DelayNs>(
+        &mut self,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> GraphicsResult<I> {
+        self.inner.update_with_mode(mode, delay)
+    }
+
+    /// Access the underlying Display
+    pub fn display(&self) -> &Display<I> {
+        self.inner.display()
+    }
+
+    /// Access the underlying Display mutably
+    pub fn display_mut(&mut self) -> &mut Display<I> {
+        self.inner.display_mut()
+    }
+
+    /// Packed BW plane, in the layout sent to the controller
+    ///
+    /// Bit 0 is ink (`BinaryColor::On`).
+    pub fn buffer(&self) -> &[u8]
+    where
+        B: AsRef<[u8]>,
+    {
+        self.inner.black_buffer()
+    }
+
+    /// Packed BW plane, mutably
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        self.inner.black_buffer_mut()
+    }
+
+    /// Take the BwGraphicDisplay apart into the [`Display`] and the buffer
+    #[allow(clippy::type_complexity)] // a plain tuple of the two parts
+    pub fn into_parts(self) -> (Display<I>, B) {
+        let (display, buffer, _) = self.inner.into_parts();
+        (display, buffer)
+    }
+
+    /// Raster operation used for drawing
+    pub fn raster_op(&self) -> RasterOp {
+        self.raster_op
+    }
+
+    /// Choose how subsequent drawing combines with the buffer
+    ///
+    /// The operation applies to the ink: `Xor` with `On` inverts, `Or` with
+    /// `On` draws and `And` with `Off` erases; the other color leaves pixels
+    /// unchanged.
+    pub fn set_raster_op(&mut self, op: RasterOp) {
+        self.raster_op = op;
+        // Ink is a cleared BW bit, so or and and swap places
+        self.inner.set_raster_op(match op {
+            RasterOp::Or => RasterOp::And,
+            RasterOp::And => RasterOp::Or,
+            RasterOp::Copy | RasterOp::Xor => op,
+        });
+    }
+
+    /// Invert the pixels within `area` (drawing coordinates)
+    ///
+    /// See [`GraphicDisplay::invert_region`].
+    pub fn invert_region(&mut self, area: &Rectangle) {
+        self.inner.invert_region(area);
+    }
+
+    /// Capture the buffer as an image in drawing coordinates
+    ///
+    /// See [`GraphicDisplay::snapshot`].
+    #[cfg(any(test, feature = "std"))]
+    pub fn snapshot(&self) -> crate::snapshot::Snapshot
+    where
+        B: AsRef<[u8]>,
+    {
+        self.inner.snapshot()
+    }
+
+    /// Create an overlay layer, see [`GraphicDisplay::new_layer`]
+    ///
+    /// Layers draw in [`Color`]; draw `BinaryColor` content through
+    /// `color_converted()`. Only the BW plane reaches the buffer, so red
+    /// layer pixels come out white.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BufferTooSmall` if either buffer is smaller than
+    /// `dimensions.buffer_size()`.
+    #[allow(clippy::type_complexity)] // a plain result wrapping the layer
+    pub fn new_layer<L1, L2>(&self, black: L1, red: L2) -> GraphicsNewResult<I, Layer<L1, L2>>
+    where
+        L1: AsMut<[u8]>,
+        L2: AsMut<[u8]>,
+    {
+        self.inner.new_layer(black, red)
+    }
+
+    /// Paint `layers` over the buffer, see [`GraphicDisplay::compose`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDimensions`] if a layer was made for a
+    /// different panel.
+    pub fn compose<'l, L1, L2>(
+        &mut self,
+        layers: impl IntoIterator<Item = &'l Layer<L1, L2>>,
+    ) -> GraphicsResult<I>
+    where
+        L1: AsRef<[u8]> + 'l,
+        L2: AsRef<[u8]> + 'l,
+    {
+        self.inner.compose(layers)
+    }
+
+    /// Compose the layers, then update the display
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDimensions`] if a layer was made for a
+    /// different panel, or [`Error::Interface`] if there's a communication
+    /// error.
+    pub fn update_with_layers<'l, L1, L2, D>(
+        &mut self,
+        layers: impl IntoIterator<Item = &'l Layer<L1, L2>>,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> GraphicsResult<I>
+    where
+        L1: AsRef<[u8]> + 'l,
+        L2: AsRef<[u8]> + 'l,
+        D: DelayNs,
+    {
+        self.inner.update_with_layers(layers, mode, delay)
+    }
+
+    /// Tri-color equivalent of drawing `color` in the current mode
+    fn ink(&self, color: BinaryColor) -> Color {
+        match (self.raster_op, color) {
+            // Inverting means xor-ing the BW bit with 1, i.e. white
+            (RasterOp::Xor, BinaryColor::On) => Color::White,
+            (RasterOp::Xor, BinaryColor::Off) => Color::Black,
+            (_, color) => Color::from(color),
+        }
+    }
+}
+
+impl<I, B> DrawTarget for BwGraphicDisplay<I, B>
+where
+    I: DisplayInterface,
+    B: AsMut<[u8]>,
+{
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<Iter>(&mut self, pixels: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (on, off) = (self.ink(BinaryColor::On), self.ink(BinaryColor::Off));
+        self.inner.draw_iter(
+            pixels
+                .into_iter()
+                .map(|Pixel(point, color)| Pixel(point, if color.is_on() { on } else { off })),
+        )
+    }
+
+    fn fill_contiguous<Iter>(&mut self, area: &Rectangle, colors: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Self::Color>,
+    {
+        let (on, off) = (self.ink(BinaryColor::On), self.ink(BinaryColor::Off));
+        self.inner.fill_contiguous(
+            area,
+            colors
+                .into_iter()
+                .map(|color| if color.is_on() { on } else { off }),
+        )
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        let color = self.ink(color);
+        self.inner.fill_solid(area, color)
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        BwGraphicDisplay::clear(self, color);
+        Ok(())
+    }
+}
+
+impl<I, B> GetPixel for BwGraphicDisplay<I, B>
+where
+    I: DisplayInterface,
+    B: AsMut<[u8]> + AsRef<[u8]>,
+{
+    type Color = BinaryColor;
+
+    fn pixel(&self, p: Point) -> Option<BinaryColor> {
+        self.inner.pixel(p).map(BinaryColor::from)
+    }
+}
+
+impl<I, B> OriginDimensions for BwGraphicDisplay<I, B>
+where
+    I: DisplayInterface,
+    B: AsMut<[u8]>,
+{
+    fn size(&self) -> Size {
+        self.inner.size()
+    }
+}
+
+/// Strip of the screen drawn by [`Display::draw_banded`]
+///
+/// Accepts drawing coordinates for the whole (rotated) screen, like
+/// [`GraphicDisplay`], but only keeps the pixels that fall into the current
+/// band; everything else is clipped. Draw the full scene for every band, or
+/// skip items outside [`BandTarget::drawing_area`] to save time.
+pub struct BandTarget<'a> {
+    black: &'a mut [u8],
+    red: &'a mut [u8],
+    band: Region,
+    dimensions: Dimensions,
+    rotation: Rotation,
+    mirror: Mirror,
+}
+
+impl BandTarget<'_> {
+    /// Panel rows covered by this band, in native orientation
+    pub fn band(&self) -> Region {
+        self.band
+    }
+
+    /// Area of the drawing coordinates covered by this band
+    pub fn drawing_area(&self) -> Rectangle {
+        let Region { y, h, .. } = self.band;
+        // First band row counted from the other edge, for the flipped rotations
+        let flipped = self.dimensions.rows - y - h;
+        let (cols, h) = (self.dimensions.cols as u32, h as u32);
+        let area = match self.rotation {
+            Rotation::Rotate0 => Rectangle::new(Point::new(0, y as i32), Size::new(cols, h)),
+            Rotation::Rotate180 => {
+                Rectangle::new(Point::new(0, flipped as i32), Size::new(cols, h))
+            }
+            Rotation::Rotate90 => Rectangle::new(Point::new(y as i32, 0), Size::new(h, cols)),
+            Rotation::Rotate270 => {
+                Rectangle::new(Point::new(flipped as i32, 0), Size::new(h, cols))
+            }
+        };
+        mirror_area(&area, self.size(), self.mirror)
+    }
+}
+
+impl DrawTarget for BandTarget<'_> {
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<Iter>(&mut self, pixels: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let size = self.size();
+        let (width, height) = (self.dimensions.cols as u32, self.dimensions.rows as u32);
+        let start = self.band.y as usize * (width as usize / 8);
+
+        for Pixel(point, color) in pixels {
+            let (Ok(x), Ok(y)) = (u32::try_from(point.x), u32::try_from(point.y)) else {
+                continue;
+            };
+            if x >= size.width || y >= size.height {
+                continue;
+            }
+            let (index, bit) = apply_orientation(x, y, width, height, self.rotation, self.mirror);
+            if let Some(index) = index.checked_sub(start) {
+                paint(self.black, self.red, index, bit, color, RasterOp::Copy);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl OriginDimensions for BandTarget<'_> {
+    fn size(&self) -> Size {
+        let (cols, rows) = (self.dimensions.cols as u32, self.dimensions.rows as u32);
+        match self.rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => Size::new(cols, rows),
+            Rotation::Rotate90 | Rotation::Rotate270 => Size::new(rows, cols),
+        }
+    }
+}
+
+impl<I> Display<I>
+where
+    I: DisplayInterface,
+{
+    /// Draw and update the display in horizontal bands ("paged drawing")
+    ///
+    /// Calls `draw` once per band with a [`BandTarget`] covering the whole
+    /// screen in drawing coordinates, streams each band into controller RAM
+    /// and refreshes once at the end, so only `black_band` and `red_band`
+    /// need to be in memory. `draw` must draw the same scene every time. See
+    /// [`Display::update_banded`] for sizing the buffers, BW-only images and
+    /// fast refreshes.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,no_run
+    /// use embedded_graphics::{prelude::*, primitives::{Circle, PrimitiveStyle}};
+    /// use ssd1677::{Color, RefreshMode};
+    /// # use core::convert::Infallible;
+    /// # use embedded_hal::delay::DelayNs;
+    /// # use embedded_hal::digital::{InputPin, OutputPin};
+    /// # use embedded_hal::spi::{Operation, SpiDevice};
+    /// # use ssd1677::{Builder, Dimensions, Display, Interface};
+    /// # struct MockSpi;
+    /// # impl embedded_hal::spi::ErrorType for MockSpi { type Error = Infallible; }
+    /// # impl SpiDevice for MockSpi {
+    /// #     fn transaction(
+    /// #         &mut self,
+    /// #         _operations: &mut [Operation<'_, u8>],
+    /// #     ) -> Result<(), Self::Error> {
+    /// #         Ok(())
+    /// #     }
+    /// # }
+    /// # struct MockPin;
+    /// # impl embedded_hal::digital::ErrorType for MockPin { type Error = Infallible; }
+    /// # impl OutputPin for MockPin {
+    /// #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    /// #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+    /// # }
+    /// # impl InputPin for MockPin {
+    /// #     fn is_high(&mut self) -> Result<bool, Self::Error> { Ok(false) }
+    /// #     fn is_low(&mut self) -> Result<bool, Self::Error> { Ok(true) }
+    /// # }
+    /// # struct MockDelay;
+    /// # impl DelayNs for MockDelay { fn delay_ns(&mut self, _ns: u32) {} }
+    /// # let interface = Interface::new(MockSpi, MockPin, MockPin, MockPin);
+    /// # let dims = match Dimensions::new(480, 800) {
+    /// #     Ok(dims) => dims,
+    /// #     Err(_) => return,
+    /// # };
+    /// # let config = match Builder::new().dimensions(dims).build() {
+    /// #     Ok(config) => config,
+    /// #     Err(_) => return,
+    /// # };
+    /// # let mut display = Display::new(interface, config);
+    /// # let mut delay = MockDelay;
+    /// // 40 rows of an 800-pixel-wide panel per band: 4 KB per plane
+    /// let mut black_band = [0u8; 40 * 800 / 8];
+    /// let mut red_band = [0u8; 40 * 800 / 8];
+    /// let result = display.draw_banded(
+    ///     &mut black_band,
+    ///     &mut red_band,
+    ///     RefreshMode::Full,
+    ///     &mut delay,
+    ///     |target| {
+    ///         let _ = Circle::new(Point::new(300, 140), 200)
+    ///             .into_styled(PrimitiveStyle::with_fill(Color::Red))
+    ///             .draw(target);
+    ///     },
+    /// );
+    /// # let _ = result;
+    /// ```
+    pub fn draw_banded<D, F>(
+        &mut self,
+        black_band: &mut [u8],
+        red_band: &mut [u8],
+        mode: RefreshMode,
+        delay: &mut D,
+        mut draw: F,
+    ) -> GraphicsResult<I>
+    where
+        D: DelayNs,
+        F: FnMut(&mut BandTarget<'_>),
+    {
+        let config = self.config();
+        let (dimensions, rotation) = (config.dimensions, config.rotation);
+        let mirror = config.software_mirror();
+        self.update_banded(black_band, red_band, mode, delay, |band, black, red| {
+            draw(&mut BandTarget {
+                black,
+                red,
+                band,
+                dimensions,
+                rotation,
+                mirror,
+            });
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::command::{CTRL1_BYPASS_RED, CTRL1_NORMAL, DISPLAY_UPDATE_CTRL1};
+    use crate::config::Builder;
+    use crate::emulator::Emulator;
+    use crate::rotation::apply_rotation;
+    use embedded_graphics::primitives::PointsIter;
+    use embedded_graphics_core::pixelcolor::{PixelColor, Rgb565, Rgb888};
+
+    #[derive(Debug)]
+    struct MockInterface;
+
+    impl DisplayInterface for MockInterface {
+        type Error = core::convert::Infallible;
+
+        fn send_command(&mut self, _command: u8) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn send_data(&mut self, _data: &[u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn reset<D: DelayNs>(&mut self, _delay: &mut D) {}
+
+        fn busy_wait<D: DelayNs>(&mut self, _delay: &mut D) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    type MockDisplay = Display<MockInterface>;
+
+    fn test_display(rotation: Rotation) -> TestResult<MockDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(480, 480)?)
+            .rotation(rotation)
+            .build()?;
+        Ok(Display::new(MockInterface, config))
+    }
 
     #[test]
-    fn test_graphic_display_buffer_size_uses_physical_dimensions() {
-        let display = test_display(Rotation::Rotate0);
+    fn test_graphic_display_buffer_size_uses_physical_dimensions() -> TestResult {
+        let display = test_display(Rotation::Rotate0)?;
         let required = display.dimensions().buffer_size();
         assert_eq!(required, 480 * 480 / 8);
 
@@ -767,11 +2257,12 @@ mod tests {
         let red_buf = alloc::vec![0u8; required];
         let gd = GraphicDisplay::new(display, black_buf, red_buf);
         assert_eq!(gd.size(), Size::new(480, 480));
+        Ok(())
     }
 
     #[test]
-    fn test_graphic_display_rotated_uses_physical_buffer_size() {
-        let display = test_display(Rotation::Rotate90);
+    fn test_graphic_display_rotated_uses_physical_buffer_size() -> TestResult {
+        let display = test_display(Rotation::Rotate90)?;
         let required = display.dimensions().buffer_size();
         assert_eq!(required, 480 * 480 / 8);
 
@@ -779,45 +2270,51 @@ mod tests {
         let red_buf = alloc::vec![0u8; required];
         let gd = GraphicDisplay::new(display, black_buf, red_buf);
         assert_eq!(gd.size(), Size::new(480, 480));
+        Ok(())
     }
 
     #[test]
-    fn test_try_new_small_black_buffer_returns_error() {
-        let display = test_display(Rotation::Rotate0);
+    fn test_try_new_small_black_buffer_returns_error() -> TestResult {
+        let display = test_display(Rotation::Rotate0)?;
         let required = display.dimensions().buffer_size();
 
         let black_buf = alloc::vec![0u8; required - 1];
         let red_buf = alloc::vec![0u8; required];
         let result = GraphicDisplay::try_new(display, black_buf, red_buf);
         assert!(matches!(result, Err(Error::BufferTooSmall { .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_try_new_small_red_buffer_returns_error() {
-        let display = test_display(Rotation::Rotate0);
+    fn test_try_new_small_red_buffer_returns_error() -> TestResult {
+        let display = test_display(Rotation::Rotate0)?;
         let required = display.dimensions().buffer_size();
 
         let black_buf = alloc::vec![0u8; required];
         let red_buf = alloc::vec![0u8; required - 1];
         let result = GraphicDisplay::try_new(display, black_buf, red_buf);
         assert!(matches!(result, Err(Error::BufferTooSmall { .. })));
+        Ok(())
     }
 
     #[test]
-    fn test_try_new_valid_buffers_succeeds() {
-        let display = test_display(Rotation::Rotate0);
+    fn test_try_new_valid_buffers_succeeds() -> TestResult {
+        let display = test_display(Rotation::Rotate0)?;
         let required = display.dimensions().buffer_size();
 
         let black_buf = alloc::vec![0u8; required];
         let red_buf = alloc::vec![0u8; required];
         let result = GraphicDisplay::try_new(display, black_buf, red_buf);
         assert!(result.is_ok());
+        Ok(())
     }
 
     #[test]
     #[should_panic(expected = "black_buffer too small")]
     fn test_new_panics_on_small_black_buffer() {
-        let display = test_display(Rotation::Rotate0);
+        let Ok(display) = test_display(Rotation::Rotate0) else {
+            return;
+        };
         let required = display.dimensions().buffer_size();
 
         let black_buf = alloc::vec![0u8; required - 1];
@@ -828,11 +2325,958 @@ mod tests {
     #[test]
     #[should_panic(expected = "red_buffer too small")]
     fn test_new_panics_on_small_red_buffer() {
-        let display = test_display(Rotation::Rotate0);
+        let Ok(display) = test_display(Rotation::Rotate0) else {
+            return;
+        };
         let required = display.dimensions().buffer_size();
 
         let black_buf = alloc::vec![0u8; required];
         let red_buf = alloc::vec![0u8; required - 1];
         let _ = GraphicDisplay::new(display, black_buf, red_buf);
     }
+
+    #[test]
+    fn test_snapshot_uses_drawing_coordinates() -> TestResult {
+        for rotation in [
+            Rotation::Rotate0,
+            Rotation::Rotate90,
+            Rotation::Rotate180,
+            Rotation::Rotate270,
+        ] {
+            let display = test_display(rotation)?;
+            let required = display.dimensions().buffer_size();
+            let mut graphic_display = GraphicDisplay::new(
+                display,
+                alloc::vec![0u8; required],
+                alloc::vec![0u8; required],
+            );
+            graphic_display.clear(Color::White);
+            graphic_display.draw_iter([Pixel(Point::new(3, 5), Color::Red)])?;
+
+            let snapshot = graphic_display.snapshot();
+            assert_eq!(snapshot.pixel(3, 5), Some(Color::Red), "{:?}", rotation);
+            let red_count = snapshot
+                .pixels()
+                .iter()
+                .filter(|color| **color == Color::Red)
+                .count();
+            assert_eq!(red_count, 1, "{:?}", rotation);
+        }
+        Ok(())
+    }
+
+    struct MockDelay;
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    type EmulatedDisplay = Display<Emulator>;
+
+    fn emulated_display(rotation: Rotation) -> TestResult<EmulatedDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(40, 40)?)
+            .rotation(rotation)
+            .build()?;
+        let mut display = Display::new(Emulator::from_config(&config), config);
+        display.reset(&mut MockDelay)?;
+        Ok(display)
+    }
+
+    /// Scene spanning several bands, with red if `with_red`
+    fn draw_scene<T: DrawTarget<Color = Color>>(target: &mut T, with_red: bool) {
+        let accent = if with_red { Color::Red } else { Color::Black };
+        let pixels = (0..40)
+            .map(|i| Pixel(Point::new(i, i), Color::Black))
+            .chain((0..16).map(|i| Pixel(Point::new(30 - i, 3 + i * 2), accent)))
+            .chain([Pixel(Point::new(-1, 5), Color::Black)]);
+        let _ = target.draw_iter(pixels);
+    }
+
+    fn assert_banded_matches_framebuffer(
+        rotation: Rotation,
+        mode: RefreshMode,
+        with_red: bool,
+    ) -> TestResult {
+        let display = emulated_display(rotation)?;
+        let size = display.dimensions().buffer_size();
+        let mut full = GraphicDisplay::new(display, alloc::vec![0u8; size], alloc::vec![0u8; size]);
+        full.clear(Color::White);
+        draw_scene(&mut full, with_red);
+        full.update_with_mode(mode, &mut MockDelay)?;
+
+        // 6 rows of 5 bytes per band: 7 bands, the last one 4 rows
+        let mut banded = emulated_display(rotation)?;
+        let mut black_band = [0u8; 30];
+        let mut red_storage = [0u8; 30];
+        let red_band: &mut [u8] = if with_red { &mut red_storage } else { &mut [] };
+        banded.draw_banded(&mut black_band, red_band, mode, &mut MockDelay, |target| {
+            draw_scene(target, with_red);
+        })?;
+
+        let (expected, actual) = (full.display().interface(), banded.interface());
+        let context = alloc::format!("{rotation:?} {mode:?} red={with_red}");
+        assert_eq!(actual.visible_bw(), expected.visible_bw(), "{context}");
+        assert_eq!(actual.visible_red(), expected.visible_red(), "{context}");
+        assert_eq!(actual.red_ram(), expected.red_ram(), "{context}");
+        assert_eq!(
+            actual.refresh_count(),
+            expected.refresh_count(),
+            "{context}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_banded_matches_framebuffer_update() -> TestResult {
+        let rotations = [
+            Rotation::Rotate0,
+            Rotation::Rotate90,
+            Rotation::Rotate180,
+            Rotation::Rotate270,
+        ];
+        for rotation in rotations {
+            for mode in [RefreshMode::Full, RefreshMode::Partial, RefreshMode::Fast] {
+                assert_banded_matches_framebuffer(rotation, mode, false)?;
+                assert_banded_matches_framebuffer(rotation, mode, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_band_drawing_area_matches_clipping() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(24, 16)?)
+            .build()?;
+        let orientations = ROTATIONS
+            .into_iter()
+            .flat_map(|rotation| MIRRORS.map(|mirror| (rotation, mirror)));
+        for (rotation, mirror) in orientations {
+            let (mut black, mut red) = ([0xFFu8; 10], [0u8; 10]);
+            let mut target = BandTarget {
+                black: &mut black,
+                red: &mut red,
+                band: Region::new(0, 7, 16, 5),
+                dimensions: config.dimensions,
+                rotation,
+                mirror,
+            };
+            let area = target.drawing_area();
+            let bounds = Rectangle::new(Point::zero(), target.size());
+            for point in bounds.points() {
+                target.black.fill(0xFF);
+                target.draw_iter([Pixel(point, Color::Black)])?;
+                let drawn = target.black.iter().any(|byte| *byte != 0xFF);
+                let context = alloc::format!("{rotation:?} {mirror:?} {point:?}");
+                assert_eq!(drawn, area.contains(point), "{context}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_banded_rejects_small_buffers() -> TestResult {
+        let mut display = emulated_display(Rotation::Rotate0)?;
+        let result = display.update_banded(
+            &mut [0u8; 4],
+            &mut [],
+            RefreshMode::Full,
+            &mut MockDelay,
+            |_, _, _| {},
+        );
+        assert!(matches!(
+            result,
+            Err(Error::BufferTooSmall {
+                required: 5,
+                provided: 4
+            })
+        ));
+
+        let result = display.update_banded(
+            &mut [0u8; 10],
+            &mut [0u8; 9],
+            RefreshMode::Full,
+            &mut MockDelay,
+            |_, _, _| {},
+        );
+        assert!(matches!(
+            result,
+            Err(Error::BufferTooSmall {
+                required: 10,
+                provided: 9
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_framebuffer_uses_config() -> TestResult {
+        type Screen = Framebuffer<16, 8, { framebuffer_size(16, 8) }>;
+
+        let config = Builder::new()
+            .dimensions(Screen::DIMENSIONS)
+            .rotation(Rotation::Rotate90)
+            .build()?;
+        let mut display = GraphicDisplay::with_framebuffer(
+            Emulator::from_config(&config),
+            config,
+            Screen::new(),
+        )?;
+        assert_eq!(*display.display().dimensions(), Dimensions::new(8, 16)?);
+        assert_eq!(display.display().rotation(), Rotation::Rotate90);
+        assert_eq!(display.size(), Size::new(8, 16));
+
+        display.draw_iter([Pixel(Point::new(0, 0), Color::Black)])?;
+        let mut expected = Screen::new();
+        let (index, bit) = apply_rotation(0, 0, 16, 8, Rotation::Rotate90);
+        expected.black[index] &= !bit;
+        assert_eq!(display.black_buffer.as_slice(), expected.black());
+        assert!(display.red_buffer.iter().all(|byte| *byte == 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_framebuffer_rejects_other_panel_config() -> TestResult {
+        type Screen = Framebuffer<16, 8, { framebuffer_size(16, 8) }>;
+
+        let other = Builder::new()
+            .dimensions(Dimensions::new(40, 40)?)
+            .build()?;
+        let result =
+            GraphicDisplay::with_framebuffer(Emulator::from_config(&other), other, Screen::new());
+        assert!(matches!(
+            result,
+            Err(Error::InvalidDimensions { rows: 40, cols: 40 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_framebuffer_starts_white() -> TestResult {
+        let framebuffer = Framebuffer::<8, 2, 2>::default();
+        assert_eq!(framebuffer.black(), &[0xFF; 2]);
+        assert_eq!(framebuffer.red(), &[0x00; 2]);
+        assert_eq!(Framebuffer::<8, 2, 2>::DIMENSIONS, Dimensions::new(2, 8)?);
+        Ok(())
+    }
+
+    const ROTATIONS: [Rotation; 4] = [
+        Rotation::Rotate0,
+        Rotation::Rotate90,
+        Rotation::Rotate180,
+        Rotation::Rotate270,
+    ];
+
+    const MIRRORS: [Mirror; 4] = [
+        Mirror::None,
+        Mirror::Horizontal,
+        Mirror::Vertical,
+        Mirror::Both,
+    ];
+
+    type VecDisplay = GraphicDisplay<MockInterface, alloc::vec::Vec<u8>, alloc::vec::Vec<u8>>;
+
+    /// Panel size and orientation for the pixel path equivalence tests
+    type Layout = (Dimensions, Rotation, Mirror);
+    type Layouts = alloc::vec::Vec<Layout>;
+
+    /// Square and non-square panels (rows x cols) in every orientation
+    fn layouts() -> TestResult<Layouts> {
+        let mut layouts = alloc::vec::Vec::new();
+        for (rows, cols) in [(24, 24), (40, 16), (16, 40)] {
+            let dims = Dimensions::new(rows, cols)?;
+            layouts.extend(
+                ROTATIONS
+                    .into_iter()
+                    .flat_map(|rotation| MIRRORS.map(|mirror| (dims, rotation, mirror))),
+            );
+        }
+        Ok(layouts)
+    }
+
+    /// Panel with a non-uniform starting image in both planes
+    fn patterned_display((dims, rotation, mirror): Layout) -> TestResult<VecDisplay> {
+        let config = Builder::new()
+            .dimensions(dims)
+            .rotation(rotation)
+            .mirror(mirror)
+            .build()?;
+        let size = dims.buffer_size();
+        let black = (0..size).map(|i| (i as u8).wrapping_mul(113)).collect();
+        let red = (0..size)
+            .map(|i| (i as u8).wrapping_mul(59) & !0x11)
+            .collect();
+        Ok(GraphicDisplay::new(
+            Display::new(MockInterface, config),
+            black,
+            red,
+        ))
+    }
+
+    fn fill_areas() -> [Rectangle; 9] {
+        [
+            Rectangle::new(Point::new(0, 0), Size::new(24, 24)),
+            Rectangle::new(Point::new(3, 2), Size::new(1, 1)),
+            Rectangle::new(Point::new(2, 5), Size::new(5, 7)),
+            Rectangle::new(Point::new(8, 1), Size::new(8, 3)),
+            Rectangle::new(Point::new(7, 9), Size::new(10, 13)),
+            Rectangle::new(Point::new(-4, -3), Size::new(9, 6)),
+            Rectangle::new(Point::new(19, 20), Size::new(10, 10)),
+            Rectangle::new(Point::new(30, 2), Size::new(4, 4)),
+            Rectangle::new(Point::new(4, 4), Size::new(0, 9)),
+        ]
+    }
+
+    fn assert_same_buffers(actual: &VecDisplay, expected: &VecDisplay, context: &str) {
+        assert_eq!(
+            actual.black_buffer, expected.black_buffer,
+            "black {context}"
+        );
+        assert_eq!(actual.red_buffer, expected.red_buffer, "red {context}");
+    }
+
+    const RASTER_OPS: [RasterOp; 4] = [RasterOp::Copy, RasterOp::Xor, RasterOp::Or, RasterOp::And];
+
+    /// Every drawing color in every raster mode
+    fn inks() -> impl Iterator<Item = (Color, RasterOp)> {
+        [Color::Black, Color::White, Color::Red]
+            .into_iter()
+            .flat_map(|color| RASTER_OPS.map(|op| (color, op)))
+    }
+
+    /// `patterned_display` drawing with `op`
+    fn raster_display(layout: Layout, op: RasterOp) -> TestResult<VecDisplay> {
+        let mut display = patterned_display(layout)?;
+        display.set_raster_op(op);
+        Ok(display)
+    }
+
+    #[test]
+    fn test_raster_ops_combine_bits() {
+        for op in RASTER_OPS {
+            for (dest, source) in [(false, false), (false, true), (true, false), (true, true)] {
+                let expected = match op {
+                    RasterOp::Copy => source,
+                    RasterOp::Xor => dest != source,
+                    RasterOp::Or => dest || source,
+                    RasterOp::And => dest && source,
+                };
+                let mut byte = 0b1010_0101 | (u8::from(dest) << 4);
+                op.apply(&mut byte, 0x10, source);
+                assert_eq!(byte & 0x10 != 0, expected, "{op:?} {dest} {source}");
+                assert_eq!(byte & !0x10, 0b1010_0101, "{op:?} touched other bits");
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_solid_matches_pixel_path() -> TestResult {
+        for (layout, area) in layouts()?
+            .into_iter()
+            .flat_map(|layout| fill_areas().map(|area| (layout, area)))
+        {
+            for (color, op) in inks() {
+                let mut fast = raster_display(layout, op)?;
+                fast.fill_solid(&area, color)?;
+                let mut slow = raster_display(layout, op)?;
+                slow.draw_iter(area.points().map(|point| Pixel(point, color)))?;
+                assert_same_buffers(
+                    &fast,
+                    &slow,
+                    &alloc::format!("{layout:?} {area:?} {color:?} {op:?}"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_contiguous_matches_pixel_path() -> TestResult {
+        const PALETTE: [Color; 3] = [Color::Black, Color::White, Color::Red];
+        let colors = |area: &Rectangle| {
+            (0..area.size.width * area.size.height).map(|i| PALETTE[(i * 7 % 11 % 3) as usize])
+        };
+        for (layout, op) in layouts()?
+            .into_iter()
+            .flat_map(|layout| RASTER_OPS.map(|op| (layout, op)))
+        {
+            for area in fill_areas() {
+                let mut fast = raster_display(layout, op)?;
+                fast.fill_contiguous(&area, colors(&area))?;
+                let mut slow = raster_display(layout, op)?;
+                slow.draw_iter(
+                    area.points()
+                        .zip(colors(&area))
+                        .map(|(point, color)| Pixel(point, color)),
+                )?;
+                assert_same_buffers(&fast, &slow, &alloc::format!("{layout:?} {area:?} {op:?}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_draw_target_clear_matches_pixel_path() -> TestResult {
+        for layout in layouts()? {
+            for color in [Color::Black, Color::White, Color::Red] {
+                let mut fast = patterned_display(layout)?;
+                DrawTarget::clear(&mut fast, color)?;
+                let mut slow = patterned_display(layout)?;
+                let bounds = Rectangle::new(Point::zero(), slow.size());
+                slow.draw_iter(bounds.points().map(|point| Pixel(point, color)))?;
+                assert_same_buffers(&fast, &slow, &alloc::format!("{layout:?} {color:?}"));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear_ignores_raster_op() -> TestResult {
+        let layout = (Dimensions::new(16, 24)?, Rotation::Rotate0, Mirror::None);
+        let mut display = raster_display(layout, RasterOp::Xor)?;
+        DrawTarget::clear(&mut display, Color::Red)?;
+        assert!(display.black_buffer().iter().all(|byte| *byte == 0xFF));
+        assert!(display.red_buffer().iter().all(|byte| *byte == 0xFF));
+        assert_eq!(display.raster_op(), RasterOp::Xor);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invert_region_swaps_black_and_white() -> TestResult {
+        for (layout, area) in layouts()?
+            .into_iter()
+            .flat_map(|layout| fill_areas().map(|area| (layout, area)))
+        {
+            let original = patterned_display(layout)?;
+            let mut display = raster_display(layout, RasterOp::And)?;
+            display.invert_region(&area);
+
+            let bounds = Rectangle::new(Point::zero(), display.size());
+            for point in bounds.points() {
+                let expected = match original.pixel(point) {
+                    Some(Color::Black) if area.contains(point) => Some(Color::White),
+                    Some(Color::White) if area.contains(point) => Some(Color::Black),
+                    other => other,
+                };
+                assert_eq!(
+                    display.pixel(point),
+                    expected,
+                    "{layout:?} {area:?} {point}"
+                );
+            }
+
+            display.invert_region(&area);
+            assert_same_buffers(&display, &original, &alloc::format!("{layout:?} {area:?}"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_xor_drawing_twice_restores_buffers() -> TestResult {
+        use embedded_graphics::Drawable;
+        use embedded_graphics::primitives::{Circle, Primitive, PrimitiveStyle};
+
+        let layout = (
+            Dimensions::new(40, 16)?,
+            Rotation::Rotate90,
+            Mirror::Vertical,
+        );
+        let mut display = raster_display(layout, RasterOp::Xor)?;
+        let original = patterned_display(layout)?;
+        let shapes = [
+            PrimitiveStyle::with_fill(Color::Red),
+            PrimitiveStyle::with_stroke(Color::White, 3),
+        ];
+        for style in shapes {
+            let circle = Circle::new(Point::new(-3, 5), 30).into_styled(style);
+            circle.draw(&mut display)?;
+            assert_ne!(display.black_buffer(), original.black_buffer(), "{style:?}");
+            circle.draw(&mut display)?;
+            assert_same_buffers(&display, &original, &alloc::format!("{style:?}"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_pixel_matches_snapshot() -> TestResult {
+        for layout in layouts()? {
+            let mut display = patterned_display(layout)?;
+            draw_scene(&mut display, true);
+            let snapshot = display.snapshot();
+            let bounds = Rectangle::new(Point::zero(), display.size());
+            for point in bounds.points() {
+                let (x, y) = (point.x as u32, point.y as u32);
+                assert_eq!(
+                    display.pixel(point),
+                    snapshot.pixel(x, y),
+                    "{layout:?} {point}"
+                );
+            }
+            let corner = bounds.bottom_right().ok_or("empty bounds")?;
+            for outside in [
+                Point::new(-1, 0),
+                Point::new(0, -1),
+                Point::new(corner.x + 1, 0),
+                Point::new(0, corner.y + 1),
+            ] {
+                assert_eq!(display.pixel(outside), None, "{layout:?} {outside}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_pixel_reads_back_drawn_colors() -> TestResult {
+        let dims = Dimensions::new(16, 24)?;
+        let mut display = patterned_display((dims, Rotation::Rotate90, Mirror::Horizontal))?;
+        for (point, color) in [
+            (Point::new(0, 0), Color::Black),
+            (Point::new(15, 23), Color::Red),
+            (Point::new(7, 11), Color::White),
+        ] {
+            display.draw_iter([Pixel(point, color)])?;
+            assert_eq!(display.pixel(point), Some(color), "{point}");
+        }
+
+        // A red pixel reads back as white once the RED plane is cleared
+        display.red_buffer_mut().fill(0);
+        assert_eq!(display.pixel(Point::new(15, 23)), Some(Color::White));
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_parts_returns_display_and_buffers() -> TestResult {
+        let mut display =
+            patterned_display((Dimensions::new(16, 24)?, Rotation::Rotate180, Mirror::None))?;
+        display.clear(Color::White);
+        display.draw_iter([Pixel(Point::new(0, 0), Color::Black)])?;
+        let (black, red) = (
+            display.black_buffer().to_vec(),
+            display.red_buffer().to_vec(),
+        );
+
+        let (driver, black_buffer, red_buffer) = display.into_parts();
+        assert_eq!(black_buffer, black);
+        assert_eq!(red_buffer, red);
+        assert_eq!(black_buffer[black_buffer.len() - 1], 0xFE);
+        assert_eq!(driver.rotation(), Rotation::Rotate180);
+        Ok(())
+    }
+
+    type BwVecDisplay = BwGraphicDisplay<MockInterface, alloc::vec::Vec<u8>>;
+
+    /// `patterned_display` without the red plane
+    fn patterned_bw_display(layout: Layout) -> TestResult<BwVecDisplay> {
+        let (display, black, _) = patterned_display(layout)?.into_parts();
+        Ok(BwGraphicDisplay::new(display, black))
+    }
+
+    /// Pattern exercising the pixel, contiguous and solid fill paths
+    fn draw_ink<C, T>(target: &mut T, ink: C, paper: C)
+    where
+        C: PixelColor,
+        T: DrawTarget<Color = C>,
+    {
+        let _ = target.draw_iter((0..30).map(|i| Pixel(Point::new(i, i / 2), ink)));
+        let area = Rectangle::new(Point::new(2, 3), Size::new(13, 5));
+        let pattern = (0..65).map(|i| if i % 3 == 0 { ink } else { paper });
+        let _ = target.fill_contiguous(&area, pattern);
+        let _ = target.fill_solid(&Rectangle::new(Point::new(9, 1), Size::new(20, 4)), paper);
+        let _ = target.fill_solid(&Rectangle::new(Point::new(5, 12), Size::new(7, 9)), ink);
+    }
+
+    #[test]
+    fn test_bw_display_matches_black_plane() -> TestResult {
+        for layout in layouts()? {
+            let mut bw = patterned_bw_display(layout)?;
+            draw_ink(&mut bw, BinaryColor::On, BinaryColor::Off);
+            let mut tri = patterned_display(layout)?;
+            draw_ink(&mut tri, Color::Black, Color::White);
+            assert_eq!(bw.buffer(), tri.black_buffer(), "{layout:?}");
+            assert_eq!(
+                bw.pixel(Point::new(5, 12)),
+                Some(BinaryColor::On),
+                "{layout:?}"
+            );
+            assert_eq!(
+                bw.pixel(Point::new(9, 1)),
+                Some(BinaryColor::Off),
+                "{layout:?}"
+            );
+            assert_eq!(bw.pixel(Point::new(-1, 1)), None, "{layout:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bw_raster_ops_apply_to_ink() -> TestResult {
+        let layout = (Dimensions::new(16, 24)?, Rotation::Rotate270, Mirror::None);
+        let point = Point::new(4, 7);
+        for (op, (dest, source)) in RASTER_OPS.into_iter().flat_map(|op| {
+            [(false, false), (false, true), (true, false), (true, true)].map(|bits| (op, bits))
+        }) {
+            let expected = match op {
+                RasterOp::Copy => source,
+                RasterOp::Xor => dest != source,
+                RasterOp::Or => dest || source,
+                RasterOp::And => dest && source,
+            };
+            let mut display = patterned_bw_display(layout)?;
+            display.draw_iter([Pixel(point, BinaryColor::from(dest))])?;
+            display.set_raster_op(op);
+            assert_eq!(display.raster_op(), op);
+            display.draw_iter([Pixel(point, BinaryColor::from(source))])?;
+            let context = alloc::format!("{op:?} {dest} {source}");
+            assert_eq!(
+                display.pixel(point),
+                Some(BinaryColor::from(expected)),
+                "{context}"
+            );
+
+            // The fill path agrees with the pixel path
+            let mut filled = patterned_bw_display(layout)?;
+            filled.draw_iter([Pixel(point, BinaryColor::from(dest))])?;
+            filled.set_raster_op(op);
+            filled.fill_solid(
+                &Rectangle::new(point, Size::new(1, 1)),
+                BinaryColor::from(source),
+            )?;
+            assert_eq!(filled.buffer(), display.buffer(), "{context}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bw_display_rejects_small_buffer() -> TestResult {
+        let (display, black, _) =
+            patterned_display((Dimensions::new(16, 24)?, Rotation::Rotate0, Mirror::None))?
+                .into_parts();
+        let result = BwGraphicDisplay::try_new(display, black[1..].to_vec());
+        assert!(matches!(
+            result.map(|_| ()),
+            Err(Error::BufferTooSmall {
+                required: 48,
+                provided: 47
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_bw_updates_bypass_red_and_keep_fast_refresh_differential() -> TestResult {
+        let display = emulated_display(Rotation::Rotate90)?;
+        let size = display.dimensions().buffer_size();
+        let mut bw = BwGraphicDisplay::new(display, alloc::vec![0u8; size]);
+        bw.clear(BinaryColor::Off);
+        draw_ink(&mut bw, BinaryColor::On, BinaryColor::Off);
+        bw.update(&mut MockDelay)?;
+
+        let panel = bw.display().interface();
+        assert_eq!(
+            panel.register(DISPLAY_UPDATE_CTRL1),
+            Some(&[CTRL1_BYPASS_RED][..])
+        );
+        assert_eq!(panel.visible_bw(), bw.buffer());
+        assert!(panel.visible_red().iter().all(|byte| *byte == 0));
+        assert_eq!(panel.red_ram(), panel.bw_ram());
+
+        bw.invert_region(&Rectangle::new(Point::new(3, 3), Size::new(20, 10)));
+        bw.update_with_mode(RefreshMode::Fast, &mut MockDelay)?;
+        let panel = bw.display().interface();
+        assert_eq!(
+            panel.register(DISPLAY_UPDATE_CTRL1),
+            Some(&[CTRL1_NORMAL][..])
+        );
+        assert_eq!(panel.visible_bw(), bw.buffer());
+        assert_eq!(panel.red_ram(), panel.bw_ram());
+        assert_eq!(panel.refresh_count(), 2);
+        Ok(())
+    }
+
+    type VecLayer = Layer<alloc::vec::Vec<u8>, alloc::vec::Vec<u8>>;
+
+    fn vec_layer(display: &VecDisplay) -> TestResult<VecLayer> {
+        let size = display.display().dimensions().buffer_size();
+        Ok(display.new_layer(alloc::vec![0; size], alloc::vec![0; size])?)
+    }
+
+    /// Opaque layer: white with a red and black pattern
+    fn background_layer(display: &VecDisplay) -> TestResult<VecLayer> {
+        let mut layer = vec_layer(display)?;
+        layer.fill_solid(&Rectangle::new(Point::zero(), layer.size()), Color::White)?;
+        draw_ink(&mut layer, Color::Red, Color::Black);
+        Ok(layer)
+    }
+
+    #[test]
+    fn test_compose_matches_drawing_in_order() -> TestResult {
+        for layout in layouts()? {
+            let mut composed = patterned_display(layout)?;
+            let mut overlay = vec_layer(&composed)?;
+            draw_ink(&mut overlay, Color::Black, Color::Red);
+            composed.compose([&overlay])?;
+            let mut drawn = patterned_display(layout)?;
+            draw_ink(&mut drawn, Color::Black, Color::Red);
+            assert_same_buffers(&composed, &drawn, &alloc::format!("{layout:?} overlay"));
+
+            let background = background_layer(&composed)?;
+            composed.compose([&background, &overlay])?;
+            drawn.fill_solid(&Rectangle::new(Point::zero(), drawn.size()), Color::White)?;
+            draw_ink(&mut drawn, Color::Red, Color::Black);
+            draw_ink(&mut drawn, Color::Black, Color::Red);
+            assert_same_buffers(&composed, &drawn, &alloc::format!("{layout:?} stack"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_erased_layer_regions_show_lower_layers() -> TestResult {
+        let area = Rectangle::new(Point::new(3, 5), Size::new(11, 6));
+        for layout in layouts()? {
+            let mut reference = patterned_display(layout)?;
+            let background = background_layer(&reference)?;
+            reference.compose([&background])?;
+
+            let mut display = patterned_display(layout)?;
+            let mut overlay = vec_layer(&display)?;
+            overlay.fill_solid(&Rectangle::new(Point::zero(), overlay.size()), Color::Black)?;
+            overlay.erase_region(&area);
+            display.compose([&background, &overlay])?;
+            for point in Rectangle::new(Point::zero(), display.size()).points() {
+                // Erased pixels show the background, the rest the overlay
+                let expected = area.contains(point).then(|| reference.pixel(point));
+                let expected = expected.unwrap_or(Some(Color::Black));
+                assert_eq!(display.pixel(point), expected, "{layout:?} {point:?}");
+            }
+
+            overlay.erase();
+            display.compose([&background, &overlay])?;
+            assert_same_buffers(&display, &reference, &alloc::format!("{layout:?}"));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_compose_rejects_layers_for_other_panels() -> TestResult {
+        let mut display =
+            patterned_display((Dimensions::new(16, 40)?, Rotation::Rotate0, Mirror::None))?;
+        let other = patterned_display((Dimensions::new(40, 16)?, Rotation::Rotate0, Mirror::None))?;
+        // Same buffer size, different panel
+        let layer = background_layer(&other)?;
+        let (black, red) = (display.black_buffer.clone(), display.red_buffer.clone());
+
+        let result = display.compose([&layer]);
+        assert!(matches!(
+            result,
+            Err(Error::InvalidDimensions { rows: 40, cols: 16 })
+        ));
+        assert_eq!((display.black_buffer, display.red_buffer), (black, red));
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_layer_rejects_small_buffers() -> TestResult {
+        let display = patterned_display(layouts()?.into_iter().next().ok_or("no layouts")?)?;
+        let required = display.display().dimensions().buffer_size();
+        for (black, red) in [(required - 1, required), (required, required - 1)] {
+            let result = display.new_layer(alloc::vec![0; black], alloc::vec![0; red]);
+            assert!(
+                matches!(result, Err(Error::BufferTooSmall { provided, .. }) if provided == required - 1),
+                "{black} {red}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bw_display_composes_layers() -> TestResult {
+        use embedded_graphics::draw_target::DrawTargetExt;
+
+        for layout in layouts()? {
+            let mut composed = patterned_bw_display(layout)?;
+            let size = layout.0.buffer_size();
+            let mut overlay = composed.new_layer(alloc::vec![0; size], alloc::vec![0; size])?;
+            draw_ink(
+                &mut overlay.color_converted(),
+                BinaryColor::On,
+                BinaryColor::Off,
+            );
+            composed.compose([&overlay])?;
+            let mut drawn = patterned_bw_display(layout)?;
+            draw_ink(&mut drawn, BinaryColor::On, BinaryColor::Off);
+            assert_eq!(composed.buffer(), drawn.buffer(), "{layout:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_colors_round_trip_through_embedded_graphics_colors() {
+        for color in [Color::Black, Color::White, Color::Red] {
+            assert_eq!(Color::from(Rgb888::from(color)), color);
+            assert_eq!(Color::from(Rgb565::from(color)), color);
+        }
+        for color in [BinaryColor::On, BinaryColor::Off] {
+            assert_eq!(BinaryColor::from(Color::from(color)), color);
+        }
+        assert_eq!(BinaryColor::from(Color::Red), BinaryColor::On);
+        assert_eq!(Color::from(Rgb565::new(31, 8, 4)), Color::Red);
+        assert_eq!(Color::from(Rgb565::new(31, 40, 4)), Color::White);
+        assert_eq!(Color::from(Rgb565::new(8, 16, 8)), Color::Black);
+    }
+
+    #[test]
+    fn test_color_converted_drawables_match_native_colors() -> TestResult {
+        use embedded_graphics::Drawable;
+        use embedded_graphics::draw_target::DrawTargetExt;
+        use embedded_graphics::pixelcolor::RgbColor;
+        use embedded_graphics::primitives::{Circle, Primitive, PrimitiveStyle};
+
+        let layout = (Dimensions::new(40, 16)?, Rotation::Rotate180, Mirror::None);
+        let circle = Circle::new(Point::new(2, 2), 12);
+        let rectangle = Rectangle::new(Point::new(5, 20), Size::new(9, 7));
+
+        let mut converted = patterned_display(layout)?;
+        circle
+            .into_styled(PrimitiveStyle::with_fill(Rgb565::RED))
+            .draw(&mut converted.color_converted())?;
+        circle
+            .into_styled(PrimitiveStyle::with_stroke(Rgb888::new(40, 40, 40), 2))
+            .draw(&mut converted.color_converted())?;
+        rectangle
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+            .draw(&mut converted.color_converted())?;
+
+        let mut native = patterned_display(layout)?;
+        circle
+            .into_styled(PrimitiveStyle::with_fill(Color::Red))
+            .draw(&mut native)?;
+        circle
+            .into_styled(PrimitiveStyle::with_stroke(Color::Black, 2))
+            .draw(&mut native)?;
+        rectangle
+            .into_styled(PrimitiveStyle::with_fill(Color::Black))
+            .draw(&mut native)?;
+
+        assert_same_buffers(&converted, &native, "");
+        assert_eq!(converted.pixel(Point::new(8, 8)), Some(Color::Red));
+        assert_eq!(converted.pixel(Point::new(8, 22)), Some(Color::Black));
+
+        // Tri-color drawables on a BW-only display: red becomes ink
+        let mut bw = patterned_bw_display(layout)?;
+        circle
+            .into_styled(PrimitiveStyle::with_fill(Color::Red))
+            .draw(&mut bw.color_converted())?;
+        assert_eq!(bw.pixel(Point::new(8, 8)), Some(BinaryColor::On));
+        Ok(())
+    }
+
+    /// Visible BW and RED planes
+    type Planes = [alloc::vec::Vec<u8>; 2];
+
+    /// What the panel shows of `draw_scene` in the given orientation
+    fn mirrored_panel(
+        (dims, rotation, mirror): Layout,
+        hardware_mirror: bool,
+    ) -> TestResult<Planes> {
+        let config = Builder::new()
+            .dimensions(dims)
+            .rotation(rotation)
+            .mirror(mirror)
+            .hardware_mirror(hardware_mirror)
+            .build()?;
+        let mut display = Display::new(Emulator::from_config(&config), config);
+        display.reset(&mut MockDelay)?;
+        let size = dims.buffer_size();
+        let mut display =
+            GraphicDisplay::new(display, alloc::vec![0u8; size], alloc::vec![0u8; size]);
+        display.clear(Color::White);
+        draw_scene(&mut display, true);
+        display.update(&mut MockDelay)?;
+        let panel = display.display().interface();
+        Ok([panel.visible_bw().to_vec(), panel.visible_red().to_vec()])
+    }
+
+    #[test]
+    fn test_hardware_mirror_shows_the_same_image() -> TestResult {
+        for layout in layouts()? {
+            assert_eq!(
+                mirrored_panel(layout, true)?,
+                mirrored_panel(layout, false)?,
+                "{layout:?}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_mirror_flips_drawing_axes() -> TestResult {
+        let dims = Dimensions::new(16, 24)?;
+        for rotation in ROTATIONS {
+            let size = patterned_display((dims, rotation, Mirror::None))?.size();
+            let corner = Rectangle::new(Point::zero(), size).bottom_right();
+            let Point {
+                x: right,
+                y: bottom,
+            } = corner.ok_or("empty bounds")?;
+            let cases = [
+                (Mirror::Horizontal, Point::new(right, 2)),
+                (Mirror::Vertical, Point::new(3, bottom - 2)),
+                (Mirror::Both, Point::new(right - 3, bottom - 2)),
+            ];
+            for (mirror, plain) in cases {
+                let mut mirrored = patterned_display((dims, rotation, mirror))?;
+                let mut unmirrored = patterned_display((dims, rotation, Mirror::None))?;
+                let point = match mirror {
+                    Mirror::Horizontal => Point::new(0, 2),
+                    _ => Point::new(3, 2),
+                };
+                mirrored.draw_iter([Pixel(point, Color::Red)])?;
+                unmirrored.draw_iter([Pixel(plain, Color::Red)])?;
+                assert_same_buffers(
+                    &mirrored,
+                    &unmirrored,
+                    &alloc::format!("{rotation:?} {mirror:?}"),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_every_logical_pixel_is_drawable_on_non_square_panels() -> TestResult {
+        for layout in layouts()? {
+            let mut display = patterned_display(layout)?;
+            display.clear(Color::White);
+            let size = display.size();
+            let (rows, cols) = (layout.0.rows as u32, layout.0.cols as u32);
+            let expected = match layout.1 {
+                Rotation::Rotate0 | Rotation::Rotate180 => Size::new(cols, rows),
+                Rotation::Rotate90 | Rotation::Rotate270 => Size::new(rows, cols),
+            };
+            assert_eq!(size, expected, "{layout:?}");
+
+            let bounds = Rectangle::new(Point::zero(), size);
+            display.draw_iter(bounds.points().map(|point| Pixel(point, Color::Black)))?;
+            assert!(
+                display.black_buffer.iter().all(|byte| *byte == 0),
+                "{layout:?}: some pixels were dropped"
+            );
+
+            // Just outside the rotated bounds, in either direction
+            display.clear(Color::White);
+            let corner = bounds.bottom_right().ok_or("empty bounds")?;
+            let outside = [
+                Point::new(corner.x + 1, 0),
+                Point::new(0, corner.y + 1),
+                Point::new(-1, 0),
+                Point::new(0, -1),
+            ];
+            display.draw_iter(outside.map(|point| Pixel(point, Color::Black)))?;
+            assert!(
+                display.black_buffer.iter().all(|byte| *byte == 0xFF),
+                "{layout:?}: out-of-bounds pixel was drawn"
+            );
+        }
+        Ok(())
+    }
 }
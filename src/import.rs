@@ -0,0 +1,552 @@
+//! Import waveforms from vendor sources (requires `std` feature)
+//!
+//! Panel vendors publish waveforms as C arrays (GxEPD2, GoodDisplay sample code)
+//! or as raw OTP dumps. This module parses both and renders them as Rust
+//! `const` arrays, so vendor waveforms can be adopted without hand-copying hex.
+//! It backs the `ssd1677-lut` binary and can also be called from a `build.rs`.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::import::{parse_c_arrays, to_rust_const};
+//!
+//! let header = "
+//!     // Partial update waveform
+//!     const unsigned char GxEPD2_Panel::lut_partial[] PROGMEM = { 0x80, 0x40, 12 };
+//! ";
+//! let arrays = parse_c_arrays(header).unwrap_or_default();
+//! assert_eq!(arrays[0].name, "lut_partial");
+//! assert_eq!(arrays[0].bytes, [0x80, 0x40, 0x0C]);
+//!
+//! let source = to_rust_const("LUT_PARTIAL", &arrays[0].bytes, None);
+//! assert!(source.contains("pub const LUT_PARTIAL: [u8; 3] = ["));
+//! ```
+
+use std::fmt::Write as _;
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::lut::{LUT_SHORT_SIZE, LUT_SIZE};
+use crate::waveform::{LUT_GROUPS, LUT_PHASES, LUT_TRANSITIONS, Waveform};
+
+type ImportResult<T> = core::result::Result<T, ImportError>;
+
+/// Errors that can occur while importing a waveform
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    /// No array initializers were found in the source text
+    NoArrays,
+    /// An array element is not a byte-sized integer literal
+    InvalidLiteral {
+        /// Name of the array containing the literal
+        array: String,
+        /// The offending token
+        token: String,
+    },
+    /// An array initializer is missing its closing brace
+    Unterminated {
+        /// Name of the unterminated array
+        array: String,
+    },
+    /// No array with the requested name exists
+    UnknownArray {
+        /// Requested name
+        name: String,
+    },
+    /// Not enough bytes for a full LUT
+    TooShort {
+        /// Bytes needed from the start of the input
+        required: usize,
+        /// Bytes available
+        provided: usize,
+    },
+    /// A LUT offset is past the largest addressable byte
+    OffsetOverflow {
+        /// Requested offset of the first LUT
+        offset: usize,
+    },
+}
+
+impl core::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoArrays => write!(f, "No array initializers found"),
+            Self::InvalidLiteral { array, token } => {
+                write!(f, "Invalid byte literal `{token}` in array {array}")
+            }
+            Self::Unterminated { array } => write!(f, "Array {array} is not terminated"),
+            Self::UnknownArray { name } => write!(f, "No array named {name}"),
+            Self::TooShort { required, provided } => write!(
+                f,
+                "Input too short: required {required} bytes, provided {provided}"
+            ),
+            Self::OffsetOverflow { offset } => write!(f, "Offset {offset} is out of range"),
+        }
+    }
+}
+
+impl core::error::Error for ImportError {}
+
+/// Byte array extracted from C source
+#[derive(Clone, Debug, PartialEq)]
+pub struct CArray {
+    /// Array name, without any `Class::` qualifier
+    pub name: String,
+    /// Array contents
+    pub bytes: Vec<u8>,
+}
+
+impl CArray {
+    /// Decode the array as a full 112-byte waveform, starting at `offset`
+    ///
+    /// Use a non-zero offset for vendor arrays that embed the `WRITE_LUT`
+    /// command byte or other data ahead of the LUT.
+    pub fn waveform(&self, offset: usize) -> ImportResult<Waveform> {
+        lut_at(&self.bytes, offset).map(|lut| Waveform::from_bytes(&lut))
+    }
+}
+
+/// Parse every byte array initializer in C/C++ source text
+///
+/// Recognises declarations of the form `type name[] = { ... };`, with `//` and
+/// `/* */` comments, qualifiers such as `PROGMEM`, and hex, binary or decimal
+/// literals (integer suffixes are ignored). Declarations inside function or
+/// struct bodies are found too, and nested initializers such as
+/// `lut[5][12] = {{...}, {...}}` are flattened in order.
+#[allow(clippy::type_complexity)]
+pub fn parse_c_arrays(source: &str) -> ImportResult<Vec<CArray>> {
+    let source = strip_comments(source);
+    let mut arrays = Vec::new();
+    let mut rest = source.as_str();
+
+    while let Some(open) = rest.find('{') {
+        let declaration = &rest[..open];
+        let declaration = &declaration[declaration.rfind([';', '{', '}']).map_or(0, |i| i + 1)..];
+        let Some(name) = initializer_name(declaration) else {
+            // Step into other blocks, which may declare arrays of their own
+            rest = &rest[open + 1..];
+            continue;
+        };
+        let body = &rest[open + 1..];
+        let Some(close) = closing_brace(body) else {
+            return Err(ImportError::Unterminated { array: name });
+        };
+
+        let elements = body[..close].replace(['{', '}'], ",");
+        let bytes = parse_elements(&name, &elements)?;
+        arrays.push(CArray { name, bytes });
+        rest = &body[close + 1..];
+    }
+
+    if arrays.is_empty() {
+        return Err(ImportError::NoArrays);
+    }
+    Ok(arrays)
+}
+
+/// Find an array by name (exact match, ignoring any `Class::` qualifier)
+pub fn find_array<'a>(arrays: &'a [CArray], name: &str) -> ImportResult<&'a CArray> {
+    arrays
+        .iter()
+        .find(|array| array.name == name)
+        .ok_or_else(|| ImportError::UnknownArray {
+            name: String::from(name),
+        })
+}
+
+/// Extract consecutive 112-byte LUTs from a binary OTP dump
+///
+/// Reads `count` LUTs starting at `offset`, each immediately following the last.
+#[allow(clippy::type_complexity)]
+pub fn parse_otp_dump(
+    dump: &[u8],
+    offset: usize,
+    count: usize,
+) -> ImportResult<Vec<[u8; LUT_SIZE]>> {
+    (0..count)
+        .map(|index| {
+            let start = index
+                .checked_mul(LUT_SIZE)
+                .and_then(|skip| offset.checked_add(skip))
+                .ok_or(ImportError::OffsetOverflow { offset })?;
+            lut_at(dump, start)
+        })
+        .collect()
+}
+
+/// Convert a C identifier into a Rust constant name (`lut_20_vcomDC` -> `LUT_20_VCOM_DC`)
+pub fn const_name(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() && prev_lower {
+            out.push('_');
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        out.push(if c.is_ascii_alphanumeric() {
+            c.to_ascii_uppercase()
+        } else {
+            '_'
+        });
+    }
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert_str(0, "LUT_");
+    }
+    out
+}
+
+/// Render bytes as a `pub const NAME: [u8; N]` item
+///
+/// When `waveform` is given, a comment describing its groups, voltages and
+/// estimated timing is emitted above the constant. Voltages are left out for a
+/// 105-byte short LUT, which does not carry them.
+pub fn to_rust_const(name: &str, bytes: &[u8], waveform: Option<&Waveform>) -> String {
+    let mut out = String::new();
+    if let Some(waveform) = waveform {
+        out.push_str(&describe(waveform, bytes.len() != LUT_SHORT_SIZE));
+    }
+    let _ = writeln!(out, "pub const {name}: [u8; {}] = [", bytes.len());
+    for row in bytes.chunks(16) {
+        out.push_str("    ");
+        let hex: Vec<String> = row.iter().map(|byte| format!("0x{byte:02X}")).collect();
+        out.push_str(&hex.join(", "));
+        out.push_str(",\n");
+    }
+    out.push_str("];\n");
+    out
+}
+
+/// Describe a waveform as Rust line comments, one phase group per line
+///
+/// Only groups with frames are listed. Voltages are abbreviated as
+/// `0` (VSS), `H` (VSH1), `L` (VSL) and `2` (VSH2), phases A-D left to right.
+pub fn describe_waveform(waveform: &Waveform) -> String {
+    describe(waveform, true)
+}
+
+/// [`describe_waveform`], optionally without the voltages of a short LUT
+fn describe(waveform: &Waveform, with_voltages: bool) -> String {
+    let mut out = String::new();
+    let analysis = waveform.analyze();
+    let _ = write!(
+        out,
+        "// {} frames, ~{} ms; ",
+        analysis.total_frames, analysis.duration_ms
+    );
+    let voltages = &waveform.voltages;
+    let _ = if with_voltages {
+        writeln!(
+            out,
+            "VGH=0x{:02X} VSH1=0x{:02X} VSH2=0x{:02X} VSL=0x{:02X} VCOM=0x{:02X}",
+            voltages.vgh, voltages.vsh1, voltages.vsh2, voltages.vsl, voltages.vcom,
+        )
+    } else {
+        writeln!(out, "voltages programmed separately")
+    };
+    let _ = writeln!(out, "// group  LUT0 LUT1 LUT2 LUT3 VCOM  TP           RP");
+
+    for group in 0..LUT_GROUPS {
+        let timing = &waveform.timing[group];
+        if waveform.group_frames(group) == 0 {
+            continue;
+        }
+        let _ = write!(out, "// {group:>5}  ");
+        for lut in 0..=LUT_TRANSITIONS {
+            let voltages = waveform
+                .transitions
+                .get(lut)
+                .map_or(&waveform.vcom[group], |transition| &transition[group]);
+            let codes: String = voltages
+                .iter()
+                .map(|v| ['0', 'H', 'L', '2'][v.bits() as usize])
+                .collect();
+            let _ = write!(out, "{codes} ");
+        }
+        let phases: Vec<String> = timing.phases[..LUT_PHASES]
+            .iter()
+            .map(|tp| format!("{tp:>2}"))
+            .collect();
+        let _ = writeln!(out, " {}  {:>3}", phases.join(" "), timing.repeat);
+    }
+
+    waveform.for_each_issue(|issue| {
+        let _ = writeln!(out, "// warning: {issue}");
+    });
+    out
+}
+
+/// Copy a full LUT out of `bytes` at `offset`
+#[allow(clippy::type_complexity)]
+fn lut_at(bytes: &[u8], offset: usize) -> ImportResult<[u8; LUT_SIZE]> {
+    let required = offset
+        .checked_add(LUT_SIZE)
+        .ok_or(ImportError::OffsetOverflow { offset })?;
+    let Some(slice) = bytes.get(offset..required) else {
+        return Err(ImportError::TooShort {
+            required,
+            provided: bytes.len(),
+        });
+    };
+    let mut lut = [0u8; LUT_SIZE];
+    lut.copy_from_slice(slice);
+    Ok(lut)
+}
+
+/// Replace `//` and `/* */` comments with whitespace
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    loop {
+        let line = rest.find("//");
+        let block = rest.find("/*");
+        let (start, end_marker) = match (line, block) {
+            (Some(l), Some(b)) if b < l => (b, "*/"),
+            (Some(l), _) => (l, "\n"),
+            (None, Some(b)) => (b, "*/"),
+            (None, None) => break,
+        };
+        out.push_str(&rest[..start]);
+        out.push(' ');
+        rest = &rest[start + 2..];
+        rest = rest.find(end_marker).map_or("", |end| {
+            if end_marker == "\n" {
+                &rest[end..]
+            } else {
+                &rest[end + 2..]
+            }
+        });
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Get the array name of a declaration that ends in `= ` before its initializer
+fn initializer_name(declaration: &str) -> Option<String> {
+    let (head, tail) = declaration.rsplit_once('=')?;
+    if !tail.trim().is_empty() {
+        return None;
+    }
+    array_name(head)
+}
+
+/// Find the `}` closing a block whose `{` was just before `body`
+fn closing_brace(body: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in body.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Get the identifier immediately before the first `[` of a declaration
+fn array_name(declaration: &str) -> Option<String> {
+    let head = &declaration[..declaration.find('[')?];
+    let head = head.trim_end();
+    let start = head
+        .rfind(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .map_or(0, |i| i + 1);
+    let name = &head[start..];
+    (!name.is_empty()).then(|| String::from(name))
+}
+
+/// Parse comma-separated byte literals
+#[allow(clippy::type_complexity)]
+fn parse_elements(array: &str, body: &str) -> ImportResult<Vec<u8>> {
+    body.split(',')
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            parse_literal(token).ok_or_else(|| ImportError::InvalidLiteral {
+                array: String::from(array),
+                token: String::from(token),
+            })
+        })
+        .collect()
+}
+
+/// Parse a single hex, binary or decimal literal that fits in a byte
+fn parse_literal(token: &str) -> Option<u8> {
+    let digits = token.trim_end_matches(['u', 'U', 'l', 'L']);
+    let lower = digits.to_ascii_lowercase();
+    let value = if let Some(hex) = lower.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = lower.strip_prefix("0b") {
+        u32::from_str_radix(bin, 2).ok()?
+    } else {
+        lower.parse::<u32>().ok()?
+    };
+    u8::try_from(value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::lut::{LUT_GRAYSCALE, LUT_PARTIAL};
+
+    const HEADER: &str = r"
+        // GxEPD2-style waveforms
+        const unsigned char GxEPD2_Panel::lut_20_vcomDC[] PROGMEM =
+        {
+            0x00, 0x08, /* inline */ 0x00, 0x00, 0x00, 0x02,
+            0x60, 0x28, 0x28, 0x00, 0x00, 0x01, // trailing
+        };
+
+        static const uint8_t LUT_DATA_part[] = {0b1000, 255u, 12};
+        struct point { int x; int y; };
+    ";
+
+    #[test]
+    fn test_parse_c_arrays() -> TestResult {
+        let arrays = parse_c_arrays(HEADER)?;
+        assert_eq!(arrays.len(), 2);
+        assert_eq!(arrays[0].name, "lut_20_vcomDC");
+        assert_eq!(
+            arrays[0].bytes,
+            [
+                0x00, 0x08, 0x00, 0x00, 0x00, 0x02, 0x60, 0x28, 0x28, 0x00, 0x00, 0x01
+            ]
+        );
+        assert_eq!(arrays[1].name, "LUT_DATA_part");
+        assert_eq!(arrays[1].bytes, [8, 255, 12]);
+        assert!(find_array(&arrays, "LUT_DATA_part").is_ok());
+        assert!(matches!(
+            find_array(&arrays, "missing"),
+            Err(ImportError::UnknownArray { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_nested_initializers() -> TestResult {
+        let source = "
+            const uint8_t lut[2][3] = {{0x01, 0x02, 0x03}, {4, 5, 6}};
+            uint8_t next[] = {7};
+        ";
+        let arrays = parse_c_arrays(source)?;
+        assert_eq!(arrays.len(), 2);
+        assert_eq!(arrays[0].name, "lut");
+        assert_eq!(arrays[0].bytes, [1, 2, 3, 4, 5, 6]);
+        assert_eq!(arrays[1].bytes, [7]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_arrays_inside_function_bodies() -> TestResult {
+        let source = "
+            void Panel::init(uint8_t mode[]) {
+                if (mode[0] == 1) { return; }
+                static const uint8_t lut_full[] = { 0x80, 0x40 };
+                writeLut(lut_full);
+            }
+            const uint8_t lut_fast[] = { 0x20 };
+        ";
+        let arrays = parse_c_arrays(source)?;
+        assert_eq!(arrays.len(), 2);
+        assert_eq!(arrays[0].name, "lut_full");
+        assert_eq!(arrays[0].bytes, [0x80, 0x40]);
+        assert_eq!(arrays[1].name, "lut_fast");
+        assert_eq!(arrays[1].bytes, [0x20]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_c_arrays("int x = 3;"), Err(ImportError::NoArrays));
+        assert!(matches!(
+            parse_c_arrays("uint8_t lut[] = { 0x100 };"),
+            Err(ImportError::InvalidLiteral { .. })
+        ));
+        assert!(matches!(
+            parse_c_arrays("uint8_t lut[] = { 0x10, "),
+            Err(ImportError::Unterminated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_generated_rust_round_trips() -> TestResult {
+        let waveform = crate::waveform::Waveform::from_bytes(&LUT_GRAYSCALE);
+        let source = to_rust_const("LUT_GRAYSCALE", &LUT_GRAYSCALE, Some(&waveform));
+        assert!(source.contains("pub const LUT_GRAYSCALE: [u8; 112] = ["));
+        assert!(source.contains("// 12 frames"));
+
+        // The generated initializer is itself parseable C-like source
+        let c_like = source.replace(
+            "pub const LUT_GRAYSCALE: [u8; 112] = [",
+            "uint8_t lut[] = {",
+        );
+        let c_like = c_like.replace("];", "};");
+        let arrays = parse_c_arrays(&c_like)?;
+        assert_eq!(arrays[0].waveform(0)?, waveform);
+        Ok(())
+    }
+
+    #[test]
+    fn test_short_lut_described_without_voltages() {
+        let short = crate::waveform::Waveform::from_bytes(&LUT_GRAYSCALE).to_short_bytes();
+        let voltages = crate::waveform::LutVoltages::default();
+        let waveform = crate::waveform::Waveform::from_short_bytes(&short, voltages);
+        let source = to_rust_const("LUT_GRAYSCALE_SHORT", &short, Some(&waveform));
+        assert!(source.starts_with("// 12 frames, ~"));
+        assert!(source.contains("; voltages programmed separately\n"));
+        assert!(!source.contains("VGH="));
+        assert!(source.contains("pub const LUT_GRAYSCALE_SHORT: [u8; 105] = ["));
+    }
+
+    #[test]
+    fn test_waveform_with_offset() -> TestResult {
+        let mut bytes = alloc::vec![crate::command::WRITE_LUT];
+        bytes.extend_from_slice(&LUT_PARTIAL);
+        let array = CArray {
+            name: String::from("lut"),
+            bytes,
+        };
+        assert_eq!(array.waveform(1)?.to_bytes(), LUT_PARTIAL);
+        assert_eq!(
+            array.waveform(2),
+            Err(ImportError::TooShort {
+                required: 114,
+                provided: 113
+            })
+        );
+        assert_eq!(
+            array.waveform(usize::MAX),
+            Err(ImportError::OffsetOverflow { offset: usize::MAX })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_otp_dump() -> TestResult {
+        let mut dump = alloc::vec![0xFFu8; 16];
+        dump.extend_from_slice(&LUT_PARTIAL);
+        dump.extend_from_slice(&LUT_GRAYSCALE);
+
+        let luts = parse_otp_dump(&dump, 16, 2)?;
+        assert_eq!(luts, [LUT_PARTIAL, LUT_GRAYSCALE]);
+        assert!(matches!(
+            parse_otp_dump(&dump, 16, 3),
+            Err(ImportError::TooShort { .. })
+        ));
+        assert_eq!(
+            parse_otp_dump(&dump, usize::MAX - 8, 1),
+            Err(ImportError::OffsetOverflow {
+                offset: usize::MAX - 8
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_const_name() {
+        assert_eq!(const_name("lut_20_vcomDC"), "LUT_20_VCOM_DC");
+        assert_eq!(const_name("LUT_DATA_part"), "LUT_DATA_PART");
+        assert_eq!(const_name("2nd"), "LUT_2ND");
+    }
+}
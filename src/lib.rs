@@ -66,6 +66,8 @@
 
 #[cfg(any(test, feature = "alloc"))]
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
 
 /// Color types for tri-color e-paper displays
 pub mod color;
@@ -81,21 +83,73 @@ pub mod error;
 pub mod interface;
 /// Look-Up Tables for refresh modes
 pub mod lut;
+/// Run-length encoded image planes
+pub mod rle;
 /// Coordinate rotation utilities
 pub mod rotation;
+/// Typed waveform model for LUT encoding and decoding
+pub mod waveform;
+
+/// Software SSD1677 emulator (requires `alloc` feature)
+#[cfg(any(test, feature = "alloc"))]
+pub mod emulator;
+
+/// Protocol conformance harness (requires `alloc` feature)
+#[cfg(any(test, feature = "alloc"))]
+pub mod conformance;
+
+/// Fault injection for error-path testing (requires `alloc` feature)
+#[cfg(any(test, feature = "alloc"))]
+pub mod fault;
+
+/// Simulated refresh timing for tests (requires `alloc` feature)
+#[cfg(any(test, feature = "alloc"))]
+pub mod timing;
+
+/// Command trace recording and decoding (requires `alloc` feature)
+#[cfg(any(test, feature = "alloc"))]
+pub mod trace;
+
+/// Waveform import from vendor C headers and OTP dumps (requires `std` feature)
+#[cfg(any(test, feature = "std"))]
+pub mod import;
+
+/// Image snapshots of framebuffers and emulated panels (requires `std` feature)
+#[cfg(any(test, feature = "std"))]
+pub mod snapshot;
+
+/// Image to framebuffer conversion (requires `std` feature)
+#[cfg(any(test, feature = "std"))]
+pub mod convert;
 
 /// Graphics support via embedded-graphics (requires `graphics` feature)
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
+/// Multi-panel displays drawn as one (requires `graphics` feature)
+#[cfg(feature = "graphics")]
+pub mod tiled;
+
 pub use color::Color;
 pub use config::{
-    Builder, Config, Dimensions, MAX_GATE_OUTPUTS, MAX_SOURCE_OUTPUTS, RamXAddressing, Rotation,
+    Builder, Config, Dimensions, LutCheck, MAX_GATE_OUTPUTS, MAX_SOURCE_OUTPUTS, Mirror,
+    RamXAddressing, Rotation,
+};
+pub use display::{
+    DeepSleepMode, Display, OtpMode, PendingUpdate, RefreshMode, Region, ScrollCanvas,
+    TemperatureSource, UpdateRegion,
 };
-pub use display::{DeepSleepMode, Display, RefreshMode, Region, UpdateRegion};
 pub use error::{BuilderError, Error};
 pub use interface::InterfaceError;
 pub use interface::{DEFAULT_BUSY_TIMEOUT_MS, DisplayInterface, Interface};
+pub use waveform::Waveform;
 
 #[cfg(feature = "graphics")]
-pub use graphics::GraphicDisplay;
+pub use graphics::{BwGraphicDisplay, Framebuffer, GraphicDisplay, Layer, RasterOp};
+#[cfg(feature = "graphics")]
+pub use tiled::{RefreshSchedule, TiledDisplay};
+
+/// Result type for unit tests, so fixtures propagate failures with `?`
+/// instead of unwrapping under the crate's `unwrap_used` lint.
+#[cfg(test)]
+type TestResult<T = ()> = Result<T, alloc::boxed::Box<dyn core::error::Error>>;
@@ -9,12 +9,17 @@
 //!
 //! ## LUT Structure
 //!
-//! The SSD1677 LUT is 112 bytes (7 phases x 16 bytes each):
+//! The SSD1677 LUT is 112 bytes:
 //!
-//! - Phase 0: VCOM waveform
-//! - Phase 1-4: Pixel transition waveforms (WW, BW, WB, BB)
-//! - Phase 5: VCOM DC level
-//! - Phase 6: Timing configuration
+//! - Bytes 0-49: Voltage selections (LUT0-LUT3 transitions, LUT4 VCOM; 10 groups each)
+//! - Bytes 50-99: TP/RP timing (10 groups x 5 bytes)
+//! - Bytes 100-104: Frame rate
+//! - Bytes 105-109: Voltages (VGH, VSH1, VSH2, VSL, VCOM)
+//! - Bytes 110-111: Reserved
+//!
+//! Panels that program voltages separately use only the first 105 bytes
+//! ([`crate::lut::LUT_SHORT_SIZE`]). See [`crate::waveform::Waveform`] for a typed view
+//! that encodes and decodes this layout.
 //!
 //! ## Built-in LUTs
 //!
@@ -23,6 +28,15 @@
 //! - [`crate::lut::LUT_GRAYSCALE`]: 4-level grayscale using both RAM planes
 //! - [`crate::lut::LUT_CLEANUP`]: Transitions grayscale back to clean B/W
 //!
+//! ## Temperature Compensation
+//!
+//! Waveforms are tuned for a temperature range. A [`crate::lut::LutSet`] maps
+//! ranges to LUTs; configure one per refresh mode with
+//! [`Builder::partial_lut_set`](crate::config::Builder::partial_lut_set) and
+//! [`Builder::fast_lut_set`](crate::config::Builder::fast_lut_set), then report the
+//! ambient temperature with [`Display::set_temperature`](crate::display::Display::set_temperature).
+//! The built-in tables are used when no entry matches.
+//!
 //! ## Usage
 //!
 //! ```rust,no_run
@@ -80,9 +94,14 @@
 //! let _ = display.update(&black_buf, &red_buf, &mut delay);
 //! ```
 
+use crate::waveform::LutVoltages;
+
 /// LUT size in bytes for SSD1677
 pub const LUT_SIZE: usize = 112;
 
+/// Short LUT size in bytes (voltages programmed through separate registers)
+pub const LUT_SHORT_SIZE: usize = 105;
+
 /// Fast refresh LUT (~300ms, minimal ghosting)
 ///
 /// Optimized for speed with single-phase transitions.
@@ -92,20 +111,29 @@ pub const LUT_SIZE: usize = 112;
 /// - May have slight ghosting on high-contrast transitions
 /// - Not suitable for images requiring full contrast
 pub const LUT_FAST: [u8; LUT_SIZE] = [
-    // Phase 0: VCOM
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 1: White -> White
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 2: Black -> White
-    0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 3: White -> Black
-    0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 4: Black -> Black
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 5: VCOM DC
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 6: Timing
-    0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // Voltage selections (50 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT0
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT1
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT2
+    0x00, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, // LUT4 (VCOM)
+    // TP/RP timing groups (50 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // G0: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G1: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G2: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G3: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G4: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G5: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G6: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G7: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G8: 0 frames
+    0x00, 0x0A, 0x00, 0x00, 0x00, // G9: 10 frames
+    // Frame rate (5 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // FR for groups 0-1, 2-3, 4-5, 6-7, 8-9
+    // Voltages (5 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // VGH, VSH1, VSH2, VSL, VCOM
+    // Reserved (2 bytes)
+    0x00, 0x00,
 ];
 
 /// Partial/half refresh LUT (~1720ms, balanced quality)
@@ -114,20 +142,29 @@ pub const LUT_FAST: [u8; LUT_SIZE] = [
 /// while still being faster than full refresh.
 /// Best for: Reading, page turns, moderate update frequency.
 pub const LUT_PARTIAL: [u8; LUT_SIZE] = [
-    // Phase 0: VCOM
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 1: White -> White (light pulse)
-    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 2: Black -> White
-    0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 3: White -> Black
-    0x40, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 4: Black -> Black (light pulse)
-    0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 5: VCOM DC
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 6: Timing
-    0x14, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // Voltage selections (50 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT0
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, // LUT1
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT2
+    0x00, 0x00, 0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x40, // LUT4 (VCOM)
+    // TP/RP timing groups (50 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // G0: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G1: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x02, // G2: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G3: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G4: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G5: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G6: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G7: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G8: 0 frames
+    0x00, 0x14, 0x14, 0x00, 0x00, // G9: 40 frames
+    // Frame rate (5 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // FR for groups 0-1, 2-3, 4-5, 6-7, 8-9
+    // Voltages (5 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // VGH, VSH1, VSH2, VSL, VCOM
+    // Reserved (2 bytes)
+    0x00, 0x00,
 ];
 
 /// Cleanup LUT for transitioning from grayscale to clean B/W
@@ -135,20 +172,29 @@ pub const LUT_PARTIAL: [u8; LUT_SIZE] = [
 /// Use after grayscale operations to restore full contrast.
 /// Three-phase transitions ensure complete pixel state reset.
 pub const LUT_CLEANUP: [u8; LUT_SIZE] = [
-    // Phase 0: VCOM
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 1: White -> White
-    0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 2: Any -> White
-    0x80, 0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 3: Any -> Black
-    0x40, 0x40, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 4: Black -> Black
-    0x02, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 5: VCOM DC
-    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-    // Phase 6: Timing
-    0x14, 0x14, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    // Voltage selections (50 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT0
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, // LUT1
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT2
+    0x00, 0x00, 0x80, 0x80, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, // LUT3
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x40, // LUT4 (VCOM)
+    // TP/RP timing groups (50 bytes)
+    0x40, 0x00, 0x00, 0x00, 0x00, // G0: 64 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G1: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x02, // G2: 0 frames
+    0x02, 0x00, 0x00, 0x00, 0x00, // G3: 2 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G4: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G5: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G6: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G7: 0 frames
+    0x00, 0x00, 0x00, 0x00, 0x00, // G8: 0 frames
+    0x00, 0x14, 0x14, 0x14, 0x00, // G9: 60 frames
+    // Frame rate (5 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // FR for groups 0-1, 2-3, 4-5, 6-7, 8-9
+    // Voltages (5 bytes)
+    0x00, 0x00, 0x00, 0x00, 0x00, // VGH, VSH1, VSH2, VSL, VCOM
+    // Reserved (2 bytes)
+    0x00, 0x00,
 ];
 
 /// Grayscale LUT for fast refresh with 4 gray levels
@@ -184,8 +230,10 @@ pub const LUT_GRAYSCALE: [u8; 112] = [
     0x00, 0x00, 0x00, 0x00, 0x00, // G8: 0 frames
     0x00, 0x00, 0x00, 0x00, 0x00, // G9: 0 frames
     // Frame rate (5 bytes)
-    0x8F, 0x8F, 0x8F, 0x8F, 0x8F, // Voltages: VGH, VSH1, VSH2, VSL, VCOM (5 bytes)
-    0x17, 0x41, 0xA8, 0x32, 0x30, // Reserved (2 bytes)
+    0x8F, 0x8F, 0x8F, 0x8F, 0x8F, // FR for groups 0-1, 2-3, 4-5, 6-7, 8-9
+    // Voltages (5 bytes)
+    0x17, 0x41, 0xA8, 0x32, 0x30, // VGH, VSH1, VSH2, VSL, VCOM
+    // Reserved (2 bytes)
     0x00, 0x00,
 ];
 
@@ -218,7 +266,90 @@ pub const LUT_GRAYSCALE_REVERT: [u8; 112] = [
     0x00, 0x00, 0x00, 0x00, 0x00, // G8
     0x00, 0x00, 0x00, 0x00, 0x00, // G9
     // Frame rate (5 bytes)
-    0x8F, 0x8F, 0x8F, 0x8F, 0x8F, // Voltages (5 bytes)
-    0x17, 0x41, 0xA8, 0x32, 0x30, // Reserved (2 bytes)
+    0x8F, 0x8F, 0x8F, 0x8F, 0x8F, // FR for groups 0-1, 2-3, 4-5, 6-7, 8-9
+    // Voltages (5 bytes)
+    0x17, 0x41, 0xA8, 0x32, 0x30, // VGH, VSH1, VSH2, VSL, VCOM
+    // Reserved (2 bytes)
     0x00, 0x00,
 ];
+
+/// LUT tuned for a temperature range
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LutEntry {
+    /// Lowest temperature covered, in °C (inclusive)
+    pub min_celsius: i16,
+    /// Highest temperature covered, in °C (exclusive)
+    pub max_celsius: i16,
+    /// Waveform to load
+    pub lut: &'static [u8; LUT_SIZE],
+    /// Voltage registers to program after the LUT, if the panel needs them
+    pub voltages: Option<LutVoltages>,
+}
+
+impl LutEntry {
+    /// Create an entry without voltage overrides
+    pub const fn new(min_celsius: i16, max_celsius: i16, lut: &'static [u8; LUT_SIZE]) -> Self {
+        Self {
+            min_celsius,
+            max_celsius,
+            lut,
+            voltages: None,
+        }
+    }
+
+    /// Program the given voltages after loading this entry's LUT
+    ///
+    /// The display returns to its default voltages and configured VCOM the next
+    /// time a refresh uses a LUT without voltages.
+    pub const fn with_voltages(mut self, voltages: LutVoltages) -> Self {
+        self.voltages = Some(voltages);
+        self
+    }
+
+    /// Whether `celsius` falls within this entry's range
+    pub fn contains(&self, celsius: i16) -> bool {
+        self.min_celsius <= celsius && celsius < self.max_celsius
+    }
+}
+
+/// Temperature-indexed set of LUTs for one refresh mode
+///
+/// ## Example
+///
+/// ```
+/// use ssd1677::lut::{LUT_FAST, LUT_PARTIAL, LutEntry, LutSet};
+///
+/// static FAST_BY_TEMP: [LutEntry; 2] = [
+///     LutEntry::new(-20, 10, &LUT_PARTIAL),
+///     LutEntry::new(10, 50, &LUT_FAST),
+/// ];
+/// let set = LutSet::new(&FAST_BY_TEMP);
+///
+/// assert_eq!(set.select(0).map(|entry| entry.lut), Some(&LUT_PARTIAL));
+/// assert_eq!(set.select(25).map(|entry| entry.lut), Some(&LUT_FAST));
+/// assert!(set.select(60).is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LutSet {
+    /// Entries, searched in order
+    entries: &'static [LutEntry],
+}
+
+impl LutSet {
+    /// Create a set from a list of entries
+    ///
+    /// Entries are searched in order, so the first matching range wins.
+    pub const fn new(entries: &'static [LutEntry]) -> Self {
+        Self { entries }
+    }
+
+    /// Get the entries in this set
+    pub fn entries(&self) -> &'static [LutEntry] {
+        self.entries
+    }
+
+    /// Find the entry covering `celsius`
+    pub fn select(&self, celsius: i16) -> Option<&'static LutEntry> {
+        self.entries.iter().find(|entry| entry.contains(celsius))
+    }
+}
@@ -0,0 +1,584 @@
+//! Run-length encoded image planes
+//!
+//! E-paper screens are mostly large areas of one color, so packed BW/RED
+//! planes compress well with a simple run-length code. Encoded planes can be
+//! stored in flash and shown with
+//! [`Display::update_compressed`](crate::display::Display::update_compressed),
+//! which decodes them straight into controller RAM in small chunks instead of
+//! expanding them into a framebuffer first.
+//!
+//! ## Format
+//!
+//! An encoded plane is a sequence of tokens, each starting with a control byte:
+//!
+//! | Control       | Meaning                                              |
+//! |---------------|------------------------------------------------------|
+//! | `0x00`-`0x7F` | Literal: the next `control + 1` bytes (1-128) are copied |
+//! | `0x80`-`0xFF` | Run: the next byte is repeated `control - 0x80 + 3` times (3-130) |
+//!
+//! There is no header; a plane ends with its last token. An empty plane
+//! decodes to nothing, which the display treats like an empty red buffer.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::rle::{Decoder, decoded_len, encode_into, encoded_len};
+//!
+//! let plane = [0xFF; 200];
+//! let mut encoded = [0u8; 8];
+//! let len = encode_into(&plane, &mut encoded).unwrap();
+//! assert_eq!(len, encoded_len(&plane));
+//! assert_eq!(encoded[..len], [0xFF, 0xFF, 0xC3, 0xFF]);
+//! assert_eq!(decoded_len(&encoded[..len]), Ok(200));
+//!
+//! // Decode in chunks, as the display driver does
+//! let mut decoder = Decoder::new(&encoded[..len]);
+//! let mut chunk = [0u8; 64];
+//! let mut total = 0;
+//! while let Ok(count @ 1..) = decoder.read(&mut chunk) {
+//!     assert!(chunk[..count].iter().all(|byte| *byte == 0xFF));
+//!     total += count;
+//! }
+//! assert_eq!(total, 200);
+//! ```
+
+#[cfg(any(test, feature = "alloc"))]
+use alloc::vec::Vec;
+
+/// Longest literal token in bytes
+pub const MAX_LITERAL: usize = 128;
+/// Shortest run token in bytes (shorter runs are stored as literals)
+pub const MIN_RUN: usize = 3;
+/// Longest run token in bytes
+pub const MAX_RUN: usize = 130;
+
+type RleResult<T> = core::result::Result<T, RleError>;
+
+/// Control bytes at or above this value start a run
+const RUN_FLAG: u8 = 0x80;
+
+/// Errors that can occur while encoding or decoding a plane
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RleError {
+    /// The encoded data ends inside a token
+    Truncated {
+        /// Offset of the token's control byte
+        offset: usize,
+    },
+    /// The output buffer cannot hold the encoded data
+    OutputTooSmall {
+        /// Required size in bytes
+        required: usize,
+        /// Provided size in bytes
+        provided: usize,
+    },
+}
+
+impl core::fmt::Display for RleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Truncated { offset } => write!(f, "Truncated RLE token at offset {offset}"),
+            Self::OutputTooSmall { required, provided } => write!(
+                f,
+                "RLE output too small: required {required} bytes, provided {provided}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for RleError {}
+
+/// Pair of encoded planes for [`Display::update_compressed`](crate::display::Display::update_compressed)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CompressedImage<'a> {
+    /// Encoded BW plane (bit 0 = black)
+    pub black: &'a [u8],
+    /// Encoded RED plane (bit 1 = red); empty for BW-only images
+    pub red: &'a [u8],
+}
+
+impl<'a> CompressedImage<'a> {
+    /// Create an image from encoded BW and RED planes
+    pub fn new(black: &'a [u8], red: &'a [u8]) -> Self {
+        Self { black, red }
+    }
+
+    /// Create a BW-only image
+    pub fn black_white(black: &'a [u8]) -> Self {
+        Self { black, red: &[] }
+    }
+}
+
+/// Walk the tokens of `data`, passing each control byte and payload to `sink`
+fn encode_tokens(data: &[u8], mut sink: impl FnMut(u8, &[u8])) {
+    let mut literal_start = 0;
+    let mut pos = 0;
+    while pos < data.len() {
+        let byte = data[pos];
+        let run = data[pos..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|b| **b == byte)
+            .count();
+        if run >= MIN_RUN {
+            if literal_start < pos {
+                sink((pos - literal_start - 1) as u8, &data[literal_start..pos]);
+            }
+            sink(
+                RUN_FLAG + (run - MIN_RUN) as u8,
+                core::slice::from_ref(&data[pos]),
+            );
+            pos += run;
+            literal_start = pos;
+        } else {
+            pos += 1;
+            if pos - literal_start == MAX_LITERAL {
+                sink((MAX_LITERAL - 1) as u8, &data[literal_start..pos]);
+                literal_start = pos;
+            }
+        }
+    }
+    if literal_start < pos {
+        sink((pos - literal_start - 1) as u8, &data[literal_start..pos]);
+    }
+}
+
+/// Size of `data` once encoded, in bytes
+pub fn encoded_len(data: &[u8]) -> usize {
+    let mut len = 0;
+    encode_tokens(data, |_, payload| len += 1 + payload.len());
+    len
+}
+
+/// Encode `data` into `out`, returning the number of bytes written
+///
+/// # Errors
+///
+/// Returns [`RleError::OutputTooSmall`] if `out` is shorter than
+/// [`encoded_len`]; `out` is left partially written.
+pub fn encode_into(data: &[u8], out: &mut [u8]) -> RleResult<usize> {
+    let required = encoded_len(data);
+    if out.len() < required {
+        return Err(RleError::OutputTooSmall {
+            required,
+            provided: out.len(),
+        });
+    }
+    let mut len = 0;
+    encode_tokens(data, |control, payload| {
+        out[len] = control;
+        out[len + 1..len + 1 + payload.len()].copy_from_slice(payload);
+        len += 1 + payload.len();
+    });
+    Ok(len)
+}
+
+/// Encode `data` into a new vector (requires `alloc` feature)
+#[cfg(any(test, feature = "alloc"))]
+pub fn encode(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(encoded_len(data));
+    encode_tokens(data, |control, payload| {
+        out.push(control);
+        out.extend_from_slice(payload);
+    });
+    out
+}
+
+/// Size of `encoded` once decoded, in bytes
+///
+/// # Errors
+///
+/// Returns [`RleError::Truncated`] if the data ends inside a token.
+pub fn decoded_len(encoded: &[u8]) -> RleResult<usize> {
+    scan(encoded).map(|(len, _)| len)
+}
+
+/// Decoded length of an encoded plane and whether any decoded byte is non-zero
+type Scan = (usize, bool);
+
+/// Measure an encoded plane without decoding it
+pub(crate) fn scan(encoded: &[u8]) -> RleResult<Scan> {
+    let mut decoder = Decoder::new(encoded);
+    let (mut len, mut nonzero) = (0, false);
+    while let Some(token) = decoder.next_token()? {
+        len += token.len();
+        nonzero |= token.has_ink();
+    }
+    Ok((len, nonzero))
+}
+
+/// Decoded bytes of one token that have not been read yet
+#[derive(Clone, Copy, Debug)]
+enum Token<'a> {
+    /// `len` copies of `byte`
+    Run { byte: u8, len: usize },
+    /// Bytes copied verbatim
+    Literal(&'a [u8]),
+}
+
+impl Token<'_> {
+    fn len(&self) -> usize {
+        match self {
+            Self::Run { len, .. } => *len,
+            Self::Literal(bytes) => bytes.len(),
+        }
+    }
+
+    fn has_ink(&self) -> bool {
+        match self {
+            Self::Run { byte, .. } => *byte != 0,
+            Self::Literal(bytes) => bytes.iter().any(|byte| *byte != 0),
+        }
+    }
+
+    /// Fill `dest` from the front of the token and drop those bytes
+    fn take_into(&mut self, dest: &mut [u8]) {
+        match self {
+            Self::Run { byte, len } => {
+                dest.fill(*byte);
+                *len -= dest.len();
+            }
+            Self::Literal(bytes) => {
+                let (head, tail) = bytes.split_at(dest.len());
+                dest.copy_from_slice(head);
+                *bytes = tail;
+            }
+        }
+    }
+}
+
+/// Streaming decoder for an encoded plane
+///
+/// Decodes into caller-provided buffers of any size, so a plane can be
+/// expanded a few bytes at a time.
+#[derive(Clone, Debug)]
+pub struct Decoder<'a> {
+    encoded: &'a [u8],
+    pos: usize,
+    pending: Token<'a>,
+}
+
+impl<'a> Decoder<'a> {
+    /// Create a decoder for an encoded plane
+    pub fn new(encoded: &'a [u8]) -> Self {
+        Self {
+            encoded,
+            pos: 0,
+            pending: Token::Literal(&[]),
+        }
+    }
+
+    /// Whether every byte has been decoded
+    pub fn is_finished(&self) -> bool {
+        self.pending.len() == 0 && self.pos >= self.encoded.len()
+    }
+
+    /// Decode up to `out.len()` bytes, returning how many were written
+    ///
+    /// Returns `Ok(0)` once the plane is exhausted (or if `out` is empty).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RleError::Truncated`] if the data ends inside a token.
+    pub fn read(&mut self, out: &mut [u8]) -> RleResult<usize> {
+        let mut written = 0;
+        while written < out.len() {
+            if self.pending.len() == 0 && !self.refill()? {
+                break;
+            }
+            let count = self.pending.len().min(out.len() - written);
+            self.pending.take_into(&mut out[written..written + count]);
+            written += count;
+        }
+        Ok(written)
+    }
+
+    /// Load the next token, returning `false` at the end of the data
+    fn refill(&mut self) -> RleResult<bool> {
+        let token = self.next_token()?;
+        if let Some(token) = token {
+            self.pending = token;
+        }
+        Ok(token.is_some())
+    }
+
+    /// Read the next token, checking that its payload is complete
+    #[allow(clippy::type_complexity)]
+    fn next_token(&mut self) -> RleResult<Option<Token<'a>>> {
+        let offset = self.pos;
+        let Some(&control) = self.encoded.get(offset) else {
+            return Ok(None);
+        };
+        let truncated = RleError::Truncated { offset };
+        let token = if control >= RUN_FLAG {
+            let byte = *self.encoded.get(offset + 1).ok_or(truncated)?;
+            Token::Run {
+                byte,
+                len: (control - RUN_FLAG) as usize + MIN_RUN,
+            }
+        } else {
+            let end = offset + 1 + control as usize + 1;
+            Token::Literal(self.encoded.get(offset + 1..end).ok_or(truncated)?)
+        };
+        self.pos += match token {
+            Token::Run { .. } => 2,
+            Token::Literal(bytes) => 1 + bytes.len(),
+        };
+        Ok(Some(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::color::Color;
+    use crate::config::{Builder, Dimensions};
+    use crate::conformance::HarnessDisplay;
+    use crate::display::{Display, RefreshMode, Region, STREAM_CHUNK_SIZE, UpdateRegion};
+    use crate::emulator::Emulator;
+    use crate::trace::{Event, Recorder};
+    use alloc::vec;
+    use embedded_hal::delay::DelayNs;
+
+    type Plane = Vec<u8>;
+    type PlanePair = (Plane, Plane);
+
+    struct MockDelay;
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn decode_all(encoded: &[u8], chunk: usize) -> TestResult<Plane> {
+        let mut decoder = Decoder::new(encoded);
+        let mut buffer = vec![0u8; chunk];
+        let mut out = Vec::new();
+        loop {
+            let count = decoder.read(&mut buffer)?;
+            if count == 0 {
+                break;
+            }
+            out.extend_from_slice(&buffer[..count]);
+        }
+        assert!(decoder.is_finished());
+        Ok(out)
+    }
+
+    fn sample_plane() -> Vec<u8> {
+        let mut plane = vec![0xFF; 1000];
+        plane[100..110].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        plane[200..203].fill(0x00);
+        plane[300..302].fill(0x0F);
+        plane[400..700]
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, byte)| *byte = (i * 7) as u8);
+        plane
+    }
+
+    #[test]
+    fn test_encoding_tokens() {
+        assert_eq!(encode(&[]), []);
+        assert_eq!(encode(&[7]), [0x00, 7]);
+        // Runs of two stay literal, runs of three become runs
+        assert_eq!(encode(&[1, 1, 2]), [0x02, 1, 1, 2]);
+        assert_eq!(encode(&[1, 2, 2, 2]), [0x00, 1, 0x80, 2]);
+        // Long runs are split at MAX_RUN
+        assert_eq!(encode(&[9; MAX_RUN + 4]), [0xFF, 9, 0x81, 9]);
+        // Long literals are split at MAX_LITERAL
+        let literal: Vec<u8> = (0..=MAX_LITERAL as u8).collect();
+        let encoded = encode(&literal);
+        assert_eq!(encoded[0], 0x7F);
+        assert_eq!(encoded[MAX_LITERAL + 1..], [0x00, MAX_LITERAL as u8]);
+    }
+
+    #[test]
+    fn test_round_trip_in_any_chunk_size() -> TestResult {
+        let plane = sample_plane();
+        let encoded = encode(&plane);
+        assert!(encoded.len() < plane.len() / 2);
+        assert_eq!(decoded_len(&encoded), Ok(plane.len()));
+        for chunk in [1, 2, 3, 7, 64, 129, 4096] {
+            assert_eq!(decode_all(&encoded, chunk)?, plane, "chunk {chunk}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_into_checks_size() {
+        let plane = sample_plane();
+        let required = encoded_len(&plane);
+        let mut out = vec![0u8; required];
+        assert_eq!(encode_into(&plane, &mut out), Ok(required));
+        assert_eq!(out, encode(&plane));
+        assert_eq!(
+            encode_into(&plane, &mut out[..required - 1]),
+            Err(RleError::OutputTooSmall {
+                required,
+                provided: required - 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncated_data_is_rejected() {
+        // Literal of 4 with only 3 bytes
+        assert_eq!(
+            decoded_len(&[0x00, 1, 0x03, 1, 2, 3]),
+            Err(RleError::Truncated { offset: 2 })
+        );
+        // Run without its byte
+        let mut decoder = Decoder::new(&[0x80, 5, 0x85]);
+        let mut out = [0u8; 16];
+        assert_eq!(
+            decoder.read(&mut out),
+            Err(RleError::Truncated { offset: 2 })
+        );
+    }
+
+    #[test]
+    fn test_scan_finds_ink() {
+        assert_eq!(scan(&encode(&[0; 300])), Ok((300, false)));
+        let mut plane = vec![0; 300];
+        plane[150] = 0x10;
+        assert_eq!(scan(&encode(&plane)), Ok((300, true)));
+        assert_eq!(scan(&[]), Ok((0, false)));
+    }
+
+    fn emulated_display() -> TestResult<HarnessDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(48, 64)?)
+            .build()?;
+        let mut display = Display::new(Recorder::new(Emulator::from_config(&config)), config);
+        display.reset(&mut MockDelay)?;
+        Ok(display)
+    }
+
+    fn test_planes() -> TestResult<PlanePair> {
+        let size = Dimensions::new(48, 64)?.buffer_size();
+        let mut black = vec![0xFF; size];
+        black[10..40].fill(0x00);
+        black[100] = 0x5A;
+        let mut red = vec![0x00; size];
+        red[200..260].fill(0xFF);
+        Ok((black, red))
+    }
+
+    #[test]
+    fn test_update_compressed_matches_raw_update() -> TestResult {
+        let (black, red) = test_planes()?;
+        let (encoded_black, encoded_red) = (encode(&black), encode(&red));
+        let cases = [(&[][..], &[][..]), (&red[..], &encoded_red[..])];
+        for mode in [RefreshMode::Full, RefreshMode::Partial, RefreshMode::Fast] {
+            for (red_plane, encoded_red) in cases {
+                let with_red = !red_plane.is_empty();
+                let mut raw = emulated_display()?;
+                raw.update_with_mode(&black, red_plane, mode, &mut MockDelay)?;
+                let mut streamed = emulated_display()?;
+                let image = CompressedImage::new(&encoded_black, encoded_red);
+                streamed.update_compressed(image, mode, &mut MockDelay)?;
+
+                let (raw, streamed) = (raw.interface().inner(), streamed.interface().inner());
+                assert_eq!(raw.bw_ram(), streamed.bw_ram(), "{mode:?} red={with_red}");
+                assert_eq!(raw.red_ram(), streamed.red_ram(), "{mode:?} red={with_red}");
+                assert_eq!(
+                    raw.visible_pixel(0, 0),
+                    streamed.visible_pixel(0, 0),
+                    "{mode:?} red={with_red}"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_compressed_streams_in_chunks() -> TestResult {
+        let (black, red) = test_planes()?;
+        let (black, red) = (encode(&black), encode(&red));
+        let mut display = emulated_display()?;
+        display.interface_mut().take_trace();
+        display.update_compressed(
+            CompressedImage::new(&black, &red),
+            RefreshMode::Full,
+            &mut MockDelay,
+        )?;
+
+        let data_sizes: Vec<usize> = display
+            .interface()
+            .trace()
+            .entries()
+            .iter()
+            .filter_map(|entry| match &entry.event {
+                Event::Data(data) => Some(data.len()),
+                _ => None,
+            })
+            .collect();
+        assert!(data_sizes.iter().all(|len| *len <= STREAM_CHUNK_SIZE));
+        assert_eq!(
+            display.interface().inner().visible_pixel(16, 1),
+            Some(Color::Black)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_region_compressed() -> TestResult {
+        let region = Region::new(16, 8, 32, 16);
+        let black = vec![0x00; region.buffer_size()];
+        let mut raw = emulated_display()?;
+        raw.update_region(
+            UpdateRegion {
+                region,
+                black_buffer: &black,
+                red_buffer: &[],
+                mode: RefreshMode::Partial,
+            },
+            &mut MockDelay,
+        )?;
+        let mut streamed = emulated_display()?;
+        streamed.update_region_compressed(
+            region,
+            CompressedImage::black_white(&encode(&black)),
+            RefreshMode::Partial,
+            &mut MockDelay,
+        )?;
+        assert_eq!(
+            raw.interface().inner().bw_ram(),
+            streamed.interface().inner().bw_ram()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_compressed_validates_before_sending() -> TestResult {
+        let (black, _) = test_planes()?;
+        let mut display = emulated_display()?;
+        display.interface_mut().take_trace();
+
+        let short = encode(&black[..100]);
+        let result = display.update_compressed(
+            CompressedImage::black_white(&short),
+            RefreshMode::Full,
+            &mut MockDelay,
+        );
+        assert!(matches!(
+            result,
+            Err(crate::Error::BufferTooSmall {
+                required: 384,
+                provided: 100
+            })
+        ));
+
+        let result = display.update_compressed(
+            CompressedImage::black_white(&[0x05, 1, 2]),
+            RefreshMode::Full,
+            &mut MockDelay,
+        );
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidRle(RleError::Truncated { offset: 0 }))
+        ));
+        assert!(display.interface().trace().is_empty());
+        Ok(())
+    }
+}
@@ -14,6 +14,10 @@
 //! - **Rotate180**: 180° rotation, origin at bottom-right
 //! - **Rotate270**: 270° clockwise (or 90° counter-clockwise)
 //!
+//! [`apply_orientation`](crate::rotation::apply_orientation) additionally
+//! mirrors the drawing first (see [`Mirror`]), covering all eight
+//! orientations of the panel.
+//!
 //! ## Example
 //!
 //! ```
@@ -30,7 +34,7 @@
 //! assert_eq!(bit, 0x01);
 //! ```
 
-use crate::config::Rotation;
+use crate::config::{Mirror, Rotation};
 
 /// Apply rotation transformation to get buffer index and bit mask
 ///
@@ -39,12 +43,17 @@ use crate::config::Rotation;
 ///
 /// # Arguments
 ///
-/// * `x` - X coordinate (column), 0 to width-1
-/// * `y` - Y coordinate (row), 0 to height-1
-/// * `width` - Display width in pixels (must be multiple of 8)
-/// * `height` - Display height in pixels
+/// * `x` - X coordinate in drawing coordinates: 0 to width-1, or 0 to
+///   height-1 for [`Rotation::Rotate90`] and [`Rotation::Rotate270`]
+/// * `y` - Y coordinate in drawing coordinates: 0 to height-1, or 0 to
+///   width-1 for [`Rotation::Rotate90`] and [`Rotation::Rotate270`]
+/// * `width` - Physical (unrotated) display width in pixels (must be multiple of 8)
+/// * `height` - Physical (unrotated) display height in pixels
 /// * `rotation` - Rotation mode
 ///
+/// Coordinates outside the rotated bounds produce meaningless (possibly
+/// out-of-range) indices; callers check bounds first.
+///
 /// # Returns
 ///
 /// Returns a tuple of (byte_index, bit_mask):
@@ -93,6 +102,47 @@ pub fn apply_rotation(x: u32, y: u32, width: u32, height: u32, rotation: Rotatio
     }
 }
 
+/// Apply mirroring and then rotation to get buffer index and bit mask
+///
+/// Like [`apply_rotation`], with `x` and `y` first reflected within the
+/// drawing according to `mirror`. `width` and `height` are physical, as for
+/// [`apply_rotation`].
+///
+/// # Example
+///
+/// ```
+/// use ssd1677::{rotation::apply_orientation, Mirror, Rotation};
+///
+/// // Mirrored horizontally, the drawing's top-left pixel is the panel's top-right
+/// let (idx, bit) = apply_orientation(0, 0, 16, 8, Rotation::Rotate0, Mirror::Horizontal);
+/// assert_eq!(idx, 1);
+/// assert_eq!(bit, 0x01);
+/// ```
+pub fn apply_orientation(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    rotation: Rotation,
+    mirror: Mirror,
+) -> (usize, u8) {
+    let (logical_width, logical_height) = match rotation {
+        Rotation::Rotate0 | Rotation::Rotate180 => (width, height),
+        Rotation::Rotate90 | Rotation::Rotate270 => (height, width),
+    };
+    let x = if mirror.horizontal() {
+        logical_width - 1 - x
+    } else {
+        x
+    };
+    let y = if mirror.vertical() {
+        logical_height - 1 - y
+    } else {
+        y
+    };
+    apply_rotation(x, y, width, height, rotation)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,4 +202,177 @@ mod tests {
         assert_eq!(idx, 30);
         assert_eq!(bit, 0x80);
     }
+
+    const ROTATIONS: [Rotation; 4] = [
+        Rotation::Rotate0,
+        Rotation::Rotate90,
+        Rotation::Rotate180,
+        Rotation::Rotate270,
+    ];
+
+    /// Pixel coordinates or a (width, height) size
+    type Pair = (u32, u32);
+
+    /// Physical sizes (width, height), mostly non-square
+    const SIZES: [Pair; 6] = [(8, 1), (8, 3), (16, 5), (24, 40), (40, 24), (64, 17)];
+
+    /// Every pixel in drawing coordinates of a `width` x `height` panel
+    fn logical_pixels(width: u32, height: u32, rotation: Rotation) -> impl Iterator<Item = Pair> {
+        let (logical_w, logical_h) = match rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => (width, height),
+            Rotation::Rotate90 | Rotation::Rotate270 => (height, width),
+        };
+        (0..logical_h).flat_map(move |y| (0..logical_w).map(move |x| (x, y)))
+    }
+
+    #[test]
+    fn test_every_logical_pixel_maps_to_a_unique_bit() {
+        for ((width, height), rotation) in SIZES
+            .into_iter()
+            .flat_map(|size| ROTATIONS.map(|rotation| (size, rotation)))
+        {
+            let mut seen = alloc::vec![0u8; (width / 8 * height) as usize];
+            for (x, y) in logical_pixels(width, height, rotation) {
+                let (index, bit) = apply_rotation(x, y, width, height, rotation);
+                let context = alloc::format!("{width}x{height} {rotation:?} ({x}, {y})");
+                assert_eq!(bit.count_ones(), 1, "{context}");
+                assert!(index < seen.len(), "{context} is out of range");
+                assert_eq!(seen[index] & bit, 0, "{context} maps to a bit already used");
+                seen[index] |= bit;
+            }
+            assert!(
+                seen.iter().all(|byte| *byte == 0xFF),
+                "{width}x{height} {rotation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotations_place_origin_in_the_expected_corner() {
+        for (width, height) in SIZES {
+            let last_row = (width / 8 * height - width / 8) as usize;
+            let last_byte = (width / 8 * height - 1) as usize;
+            let cases = [
+                (Rotation::Rotate0, (0, 0x80)),
+                (Rotation::Rotate90, ((width / 8 - 1) as usize, 0x01)),
+                (Rotation::Rotate180, (last_byte, 0x01)),
+                (Rotation::Rotate270, (last_row, 0x80)),
+            ];
+            for (rotation, expected) in cases {
+                assert_eq!(
+                    apply_rotation(0, 0, width, height, rotation),
+                    expected,
+                    "{width}x{height} {rotation:?}"
+                );
+            }
+        }
+    }
+
+    /// Physical pixel shown at drawing coordinates `(x, y)`
+    fn physical_pixel((x, y): Pair, (width, height): Pair, rotation: Rotation) -> Pair {
+        match rotation {
+            Rotation::Rotate0 => (x, y),
+            Rotation::Rotate90 => (width - 1 - y, x),
+            Rotation::Rotate180 => (width - 1 - x, height - 1 - y),
+            Rotation::Rotate270 => (y, height - 1 - x),
+        }
+    }
+
+    #[test]
+    fn test_rotations_match_physical_pixel() {
+        for ((width, height), rotation) in SIZES
+            .into_iter()
+            .flat_map(|size| ROTATIONS.map(|rotation| (size, rotation)))
+        {
+            for (x, y) in logical_pixels(width, height, rotation) {
+                let (px, py) = physical_pixel((x, y), (width, height), rotation);
+                assert_eq!(
+                    apply_rotation(x, y, width, height, rotation),
+                    apply_rotation(px, py, width, height, Rotation::Rotate0),
+                    "{width}x{height} {rotation:?} ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    const MIRRORS: [Mirror; 4] = [
+        Mirror::None,
+        Mirror::Horizontal,
+        Mirror::Vertical,
+        Mirror::Both,
+    ];
+
+    /// Rotation turned a further 180 degrees
+    const fn half_turn(rotation: Rotation) -> Rotation {
+        match rotation {
+            Rotation::Rotate0 => Rotation::Rotate180,
+            Rotation::Rotate90 => Rotation::Rotate270,
+            Rotation::Rotate180 => Rotation::Rotate0,
+            Rotation::Rotate270 => Rotation::Rotate90,
+        }
+    }
+
+    #[test]
+    fn test_every_orientation_maps_to_a_unique_bit() {
+        for (((width, height), rotation), mirror) in SIZES
+            .into_iter()
+            .flat_map(|size| ROTATIONS.map(|rotation| (size, rotation)))
+            .flat_map(|orientation| MIRRORS.map(|mirror| (orientation, mirror)))
+        {
+            let mut seen = alloc::vec![0u8; (width / 8 * height) as usize];
+            for (x, y) in logical_pixels(width, height, rotation) {
+                let (index, bit) = apply_orientation(x, y, width, height, rotation, mirror);
+                assert_eq!(
+                    seen[index] & bit,
+                    0,
+                    "{width}x{height} {rotation:?} {mirror:?}"
+                );
+                seen[index] |= bit;
+            }
+            assert!(seen.iter().all(|byte| *byte == 0xFF));
+        }
+    }
+
+    #[test]
+    fn test_mirrors_compose_with_rotations() {
+        for ((width, height), rotation) in SIZES
+            .into_iter()
+            .flat_map(|size| ROTATIONS.map(|rotation| (size, rotation)))
+        {
+            let turned = half_turn(rotation);
+            for (x, y) in logical_pixels(width, height, rotation) {
+                let oriented =
+                    |rotation, mirror| apply_orientation(x, y, width, height, rotation, mirror);
+                let context = alloc::format!("{width}x{height} {rotation:?} ({x}, {y})");
+                assert_eq!(
+                    oriented(rotation, Mirror::None),
+                    apply_rotation(x, y, width, height, rotation),
+                    "{context}"
+                );
+                assert_eq!(
+                    oriented(rotation, Mirror::Both),
+                    oriented(turned, Mirror::None),
+                    "{context}"
+                );
+                assert_eq!(
+                    oriented(rotation, Mirror::Vertical),
+                    oriented(turned, Mirror::Horizontal),
+                    "{context}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_horizontal_mirror_swaps_row_ends() {
+        // 16x2 panel: (0, 0) lands where (15, 0) would without mirroring
+        assert_eq!(
+            apply_orientation(0, 0, 16, 2, Rotation::Rotate0, Mirror::Horizontal),
+            (1, 0x01)
+        );
+        assert_eq!(
+            apply_orientation(0, 0, 16, 2, Rotation::Rotate0, Mirror::Vertical),
+            (2, 0x80)
+        );
+    }
 }
@@ -0,0 +1,942 @@
+//! Image snapshots of framebuffers and emulated panels (requires `std` feature)
+//!
+//! A [`Snapshot`](crate::snapshot::Snapshot) is a decoded tri-color image in
+//! logical (rotated) orientation. It can be taken from a pair of driver
+//! buffers, from a [`GraphicDisplay`](crate::graphics::GraphicDisplay) or from
+//! the visible image of an [`Emulator`](crate::emulator::Emulator), written as
+//! PBM/PGM/PPM or PNG, and compared against a golden image.
+//!
+//! ## Palette
+//!
+//! | Color | PBM | PGM | PPM / PNG       |
+//! |-------|-----|-----|-----------------|
+//! | Black | 1   | 0   | `#000000`       |
+//! | White | 0   | 255 | `#FFFFFF`       |
+//! | Red   | 1   | 128 | `#FF0000`       |
+//!
+//! PBM has no room for red, so red is written as ink (1). Golden images are
+//! read back with [`Snapshot::decode_pnm`](crate::snapshot::Snapshot::decode_pnm),
+//! so PPM is the lossless choice for golden files; PNG is write-only.
+//!
+//! ## Golden-Image Tests
+//!
+//! ```no_run
+//! use ssd1677::snapshot::Snapshot;
+//! use ssd1677::{Color, Dimensions, Rotation};
+//!
+//! let dims = Dimensions::new(16, 16).unwrap();
+//! let black = [0xFF; 32];
+//! let red = [0x00; 32];
+//! let snapshot = Snapshot::from_buffers(&black, &red, dims, Rotation::Rotate0).unwrap();
+//! assert_eq!(snapshot.pixel(0, 0), Some(Color::White));
+//!
+//! // Fails with a pixel report on mismatch. Set SSD1677_UPDATE_GOLDEN=1 to
+//! // (re)write the golden file instead.
+//! snapshot.check_golden("tests/golden/blank.ppm").unwrap();
+//! ```
+
+use std::fmt;
+use std::format;
+use std::io;
+use std::path::Path;
+use std::vec;
+use std::vec::Vec;
+
+use crate::color::Color;
+use crate::config::{Dimensions, Mirror, Rotation};
+use crate::emulator::Emulator;
+use crate::rotation::apply_orientation;
+
+type SnapshotResult<T> = core::result::Result<T, SnapshotError>;
+type Bytes = Vec<u8>;
+
+/// Environment variable that makes [`Snapshot::check_golden`] rewrite golden files
+pub const UPDATE_GOLDEN_ENV: &str = "SSD1677_UPDATE_GOLDEN";
+
+/// Number of differing pixels listed by the [`ImageDiff`] `Display` impl
+const DIFF_REPORT_LIMIT: usize = 8;
+
+/// Errors that can occur while creating, reading or checking snapshots
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// A buffer is smaller than the panel requires
+    BufferTooSmall {
+        /// Required size in bytes
+        required: usize,
+        /// Provided size in bytes
+        provided: usize,
+    },
+    /// The image data is not a supported PBM/PGM/PPM file
+    InvalidImage(&'static str),
+    /// The file extension does not name a supported format
+    UnknownFormat,
+    /// Reading or writing a file failed
+    Io(io::Error),
+    /// The snapshot differs from the golden image
+    Mismatch(ImageDiff),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BufferTooSmall { required, provided } => write!(
+                f,
+                "Buffer too small: required {} bytes, provided {}",
+                required, provided
+            ),
+            Self::InvalidImage(reason) => write!(f, "Invalid image: {}", reason),
+            Self::UnknownFormat => write!(f, "Unknown image format"),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Mismatch(diff) => write!(f, "{}", diff),
+        }
+    }
+}
+
+impl core::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// Image file formats supported by [`Snapshot::encode`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageFormat {
+    /// Binary PBM (`P4`), 1 bit per pixel
+    Pbm,
+    /// Binary PGM (`P5`), 8-bit gray
+    Pgm,
+    /// Binary PPM (`P6`), 8-bit RGB
+    Ppm,
+    /// PNG with a 3-entry palette
+    Png,
+}
+
+impl ImageFormat {
+    /// Pick a format from a file extension (case-insensitive)
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "pbm" => Some(Self::Pbm),
+            "pgm" => Some(Self::Pgm),
+            "ppm" => Some(Self::Ppm),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    fn from_path(path: &Path) -> SnapshotResult<Self> {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+            .ok_or(SnapshotError::UnknownFormat)
+    }
+}
+
+/// A pixel that differs between two snapshots
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PixelDiff {
+    /// X coordinate
+    pub x: u32,
+    /// Y coordinate
+    pub y: u32,
+    /// Color in the snapshot under test
+    pub actual: Color,
+    /// Color in the reference snapshot
+    pub expected: Color,
+}
+
+/// Result of comparing two snapshots
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageDiff {
+    /// Size of the snapshot under test as (width, height)
+    pub actual_size: (u32, u32),
+    /// Size of the reference snapshot as (width, height)
+    pub expected_size: (u32, u32),
+    /// Differing pixels in row-major order (empty if the sizes differ)
+    pub pixels: Vec<PixelDiff>,
+}
+
+impl ImageDiff {
+    /// Whether the snapshots are identical
+    pub fn is_empty(&self) -> bool {
+        self.actual_size == self.expected_size && self.pixels.is_empty()
+    }
+
+    /// Smallest rectangle containing every differing pixel as (x, y, w, h)
+    #[allow(clippy::type_complexity)]
+    pub fn bounding_box(&self) -> Option<(u32, u32, u32, u32)> {
+        let first = self.pixels.first()?;
+        let (mut x0, mut y0, mut x1, mut y1) = (first.x, first.y, first.x, first.y);
+        for pixel in &self.pixels {
+            x0 = x0.min(pixel.x);
+            y0 = y0.min(pixel.y);
+            x1 = x1.max(pixel.x);
+            y1 = y1.max(pixel.y);
+        }
+        Some((x0, y0, x1 - x0 + 1, y1 - y0 + 1))
+    }
+}
+
+impl fmt::Display for ImageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.actual_size != self.expected_size {
+            let (aw, ah) = self.actual_size;
+            let (ew, eh) = self.expected_size;
+            return write!(
+                f,
+                "Image size {}x{} differs from expected {}x{}",
+                aw, ah, ew, eh
+            );
+        }
+        if self.pixels.is_empty() {
+            return write!(f, "Images match");
+        }
+        write!(f, "{} pixels differ", self.pixels.len())?;
+        if let Some((x, y, w, h)) = self.bounding_box() {
+            write!(f, " within {}x{} at ({}, {})", w, h, x, y)?;
+        }
+        for pixel in self.pixels.iter().take(DIFF_REPORT_LIMIT) {
+            write!(
+                f,
+                "\n  ({}, {}): {:?}, expected {:?}",
+                pixel.x, pixel.y, pixel.actual, pixel.expected
+            )?;
+        }
+        if self.pixels.len() > DIFF_REPORT_LIMIT {
+            write!(f, "\n  ...")?;
+        }
+        Ok(())
+    }
+}
+
+/// A tri-color image in logical (rotated) orientation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+impl Snapshot {
+    /// Create a snapshot filled with a single color
+    pub fn new(width: u32, height: u32, color: Color) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; width as usize * height as usize],
+        }
+    }
+
+    /// Decode a pair of driver buffers
+    ///
+    /// `dimensions` are the physical (unrotated) panel dimensions; the
+    /// snapshot is laid out as seen through `rotation`, matching the
+    /// coordinates used by [`GraphicDisplay`](crate::graphics::GraphicDisplay).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SnapshotError::BufferTooSmall`] if either buffer is shorter
+    /// than `dimensions.buffer_size()`.
+    pub fn from_buffers(
+        black: &[u8],
+        red: &[u8],
+        dimensions: Dimensions,
+        rotation: Rotation,
+    ) -> SnapshotResult<Self> {
+        Self::from_buffers_mirrored(black, red, dimensions, rotation, Mirror::None)
+    }
+
+    /// Decode a pair of driver buffers drawn with mirroring
+    ///
+    /// Like [`Snapshot::from_buffers`], for buffers laid out with `mirror`
+    /// (see [`Builder::mirror`](crate::config::Builder::mirror)). The
+    /// mirroring is undone, so the snapshot shows the image as drawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SnapshotError::BufferTooSmall`] if either buffer is shorter
+    /// than `dimensions.buffer_size()`.
+    pub fn from_buffers_mirrored(
+        black: &[u8],
+        red: &[u8],
+        dimensions: Dimensions,
+        rotation: Rotation,
+        mirror: Mirror,
+    ) -> SnapshotResult<Self> {
+        let required = dimensions.buffer_size();
+        for provided in [black.len(), red.len()] {
+            if provided < required {
+                return Err(SnapshotError::BufferTooSmall { required, provided });
+            }
+        }
+        Ok(Self::decode_buffers(
+            black, red, dimensions, rotation, mirror,
+        ))
+    }
+
+    /// Capture the visible image of an emulated panel
+    ///
+    /// Pass the rotation the application draws with to get the image in
+    /// drawing coordinates, or [`Rotation::Rotate0`] for panel coordinates.
+    /// Mirroring is not undone: the image is the panel as a viewer looking
+    /// straight at it would see it.
+    pub fn from_emulator(emulator: &Emulator, rotation: Rotation) -> Self {
+        Self::decode_buffers(
+            emulator.visible_bw(),
+            emulator.visible_red(),
+            *emulator.dimensions(),
+            rotation,
+            Mirror::None,
+        )
+    }
+
+    /// Decode buffers already known to be large enough
+    pub(crate) fn decode_buffers(
+        black: &[u8],
+        red: &[u8],
+        dimensions: Dimensions,
+        rotation: Rotation,
+        mirror: Mirror,
+    ) -> Self {
+        let (cols, rows) = (dimensions.cols as u32, dimensions.rows as u32);
+        let (width, height) = match rotation {
+            Rotation::Rotate0 | Rotation::Rotate180 => (cols, rows),
+            Rotation::Rotate90 | Rotation::Rotate270 => (rows, cols),
+        };
+        let mut pixels = Vec::with_capacity(width as usize * height as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let (index, bit) = apply_orientation(x, y, cols, rows, rotation, mirror);
+                let bw = black.get(index).is_some_and(|byte| byte & bit != 0);
+                let is_red = red.get(index).is_some_and(|byte| byte & bit != 0);
+                pixels.push(Color::from_bits(bw, is_red));
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Image width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Image height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Color at `(x, y)`, or `None` outside the image
+    pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+        self.index(x, y).map(|index| self.pixels[index])
+    }
+
+    /// Set the color at `(x, y)`; coordinates outside the image are ignored
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        if let Some(index) = self.index(x, y) {
+            self.pixels[index] = color;
+        }
+    }
+
+    /// All pixels in row-major order
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    fn index(&self, x: u32, y: u32) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y as usize * self.width as usize + x as usize)
+    }
+
+    /// Compare against a reference snapshot
+    pub fn diff(&self, expected: &Self) -> ImageDiff {
+        let actual_size = (self.width, self.height);
+        let expected_size = (expected.width, expected.height);
+        let mut pixels = Vec::new();
+        if actual_size == expected_size {
+            let width = self.width as usize;
+            pixels.extend(
+                self.pixels
+                    .iter()
+                    .zip(&expected.pixels)
+                    .enumerate()
+                    .filter(|(_, (actual, wanted))| actual != wanted)
+                    .map(|(index, (&actual, &wanted))| PixelDiff {
+                        x: (index % width) as u32,
+                        y: (index / width) as u32,
+                        actual,
+                        expected: wanted,
+                    }),
+            );
+        }
+        ImageDiff {
+            actual_size,
+            expected_size,
+            pixels,
+        }
+    }
+
+    /// Encode the snapshot in the given format
+    pub fn encode(&self, format: ImageFormat) -> Bytes {
+        match format {
+            ImageFormat::Pbm => self.encode_pbm(),
+            ImageFormat::Pgm => self.encode_pgm(),
+            ImageFormat::Ppm => self.encode_ppm(),
+            ImageFormat::Png => self.encode_png(),
+        }
+    }
+
+    /// Write the snapshot to a file, picking the format from its extension
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> SnapshotResult<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path)?;
+        std::fs::write(path, self.encode(format))?;
+        Ok(())
+    }
+
+    /// Read a PBM/PGM/PPM file
+    pub fn load<P: AsRef<Path>>(path: P) -> SnapshotResult<Self> {
+        Self::decode_pnm(&std::fs::read(path)?)
+    }
+
+    /// Compare against a golden image file
+    ///
+    /// If the [`UPDATE_GOLDEN_ENV`] environment variable is set, the golden
+    /// file is (re)written from this snapshot instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SnapshotError::Mismatch`] if the images differ, or an I/O or
+    /// format error if the golden file cannot be read.
+    pub fn check_golden<P: AsRef<Path>>(&self, path: P) -> SnapshotResult<()> {
+        let path = path.as_ref();
+        if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            return self.save(path);
+        }
+        let diff = self.diff(&Self::load(path)?);
+        if diff.is_empty() {
+            Ok(())
+        } else {
+            Err(SnapshotError::Mismatch(diff))
+        }
+    }
+
+    /// PNM header; `max_value` is empty for PBM
+    fn header(&self, magic: &str, max_value: &str) -> Bytes {
+        format!("{}\n{} {}\n{}", magic, self.width, self.height, max_value).into_bytes()
+    }
+
+    fn encode_pbm(&self) -> Bytes {
+        let mut out = self.header("P4", "");
+        for row in self.pixels.chunks(self.width.max(1) as usize) {
+            out.extend(pack_row(row, 1, |color| u8::from(color != Color::White)));
+        }
+        out
+    }
+
+    fn encode_pgm(&self) -> Bytes {
+        let mut out = self.header("P5", "255\n");
+        out.extend(self.pixels.iter().map(|&color| gray(color)));
+        out
+    }
+
+    fn encode_ppm(&self) -> Bytes {
+        let mut out = self.header("P6", "255\n");
+        for &color in &self.pixels {
+            out.extend(rgb(color));
+        }
+        out
+    }
+
+    fn encode_png(&self) -> Bytes {
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend(self.width.to_be_bytes());
+        ihdr.extend(self.height.to_be_bytes());
+        // 2-bit palette indices, default compression/filter, no interlace
+        ihdr.extend([2, 3, 0, 0, 0]);
+
+        let palette: Vec<u8> = PALETTE.iter().flat_map(|&color| rgb(color)).collect();
+
+        let mut raw = Vec::new();
+        for row in self.pixels.chunks(self.width.max(1) as usize) {
+            raw.push(0); // filter: none
+            raw.extend(pack_row(row, 2, palette_index));
+        }
+
+        let mut out = PNG_SIGNATURE.to_vec();
+        png_chunk(&mut out, *b"IHDR", &ihdr);
+        png_chunk(&mut out, *b"PLTE", &palette);
+        png_chunk(&mut out, *b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, *b"IEND", &[]);
+        out
+    }
+
+    /// Decode a binary PBM (`P4`), PGM (`P5`) or PPM (`P6`) image
+    ///
+    /// Gray values are mapped to the nearest palette color and RGB values
+    /// through [`Color::from_rgb`], so files written by [`Snapshot::save`] and
+    /// simple edits of them round-trip.
+    pub fn decode_pnm(data: &[u8]) -> SnapshotResult<Self> {
+        let mut reader = PnmReader { data, pos: 0 };
+        let magic = reader.token()?;
+        let width = reader.number()?;
+        let height = reader.number()?;
+        let max_value = if magic == b"P4" { 1 } else { reader.number()? };
+        if max_value == 0 || max_value > 255 {
+            return Err(SnapshotError::InvalidImage("unsupported maximum value"));
+        }
+        // Exactly one whitespace byte separates the header from the raster
+        reader.pos += 1;
+        let raster = data.get(reader.pos..).unwrap_or_default();
+
+        let count = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or(SnapshotError::InvalidImage(TOO_LARGE))?;
+        let pixels = match magic {
+            b"P4" => decode_pbm(raster, width as usize, height as usize)?,
+            b"P5" => raster
+                .get(..count)
+                .ok_or(SnapshotError::InvalidImage("truncated raster"))?
+                .iter()
+                .map(|&value| from_gray(scale(value, max_value)))
+                .collect(),
+            b"P6" => raster
+                .get(
+                    ..count
+                        .checked_mul(3)
+                        .ok_or(SnapshotError::InvalidImage(TOO_LARGE))?,
+                )
+                .ok_or(SnapshotError::InvalidImage("truncated raster"))?
+                .chunks(3)
+                .map(|px| {
+                    Color::from_rgb(
+                        scale(px[0], max_value),
+                        scale(px[1], max_value),
+                        scale(px[2], max_value),
+                    )
+                })
+                .collect(),
+            _ => return Err(SnapshotError::InvalidImage("not a binary PBM/PGM/PPM file")),
+        };
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+/// Error detail for dimensions whose pixel count overflows `usize`
+const TOO_LARGE: &str = "image too large";
+
+/// PNG palette order; index = position
+const PALETTE: [Color; 3] = [Color::Black, Color::White, Color::Red];
+
+pub(crate) const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+fn palette_index(color: Color) -> u8 {
+    match color {
+        Color::Black => 0,
+        Color::White => 1,
+        Color::Red => 2,
+    }
+}
+
+fn gray(color: Color) -> u8 {
+    match color {
+        Color::Black => 0,
+        Color::White => 255,
+        Color::Red => 128,
+    }
+}
+
+fn rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Black => [0, 0, 0],
+        Color::White => [255, 255, 255],
+        Color::Red => [255, 0, 0],
+    }
+}
+
+fn from_gray(value: u8) -> Color {
+    match value {
+        0..=63 => Color::Black,
+        64..=191 => Color::Red,
+        _ => Color::White,
+    }
+}
+
+/// Scale a sample to the 0..=255 range
+fn scale(value: u8, max_value: u32) -> u8 {
+    ((value as u32 * 255) / max_value).min(255) as u8
+}
+
+/// Pack a row of pixels MSB-first at `bits` bits per pixel
+fn pack_row(row: &[Color], bits: usize, value: impl Fn(Color) -> u8) -> Bytes {
+    let per_byte = 8 / bits;
+    row.chunks(per_byte)
+        .map(|chunk| {
+            chunk.iter().enumerate().fold(0u8, |byte, (i, &color)| {
+                byte | (value(color) << (8 - bits * (i + 1)))
+            })
+        })
+        .collect()
+}
+
+#[allow(clippy::type_complexity)]
+fn decode_pbm(raster: &[u8], width: usize, height: usize) -> SnapshotResult<Vec<Color>> {
+    let stride = width.div_ceil(8);
+    let size = stride
+        .checked_mul(height)
+        .ok_or(SnapshotError::InvalidImage(TOO_LARGE))?;
+    let raster = raster
+        .get(..size)
+        .ok_or(SnapshotError::InvalidImage("truncated raster"))?;
+    let mut pixels = Vec::with_capacity(width * height);
+    for row in raster.chunks(stride.max(1)).take(height) {
+        pixels.extend((0..width).map(|x| {
+            if row[x / 8] & (0x80 >> (x % 8)) != 0 {
+                Color::Black
+            } else {
+                Color::White
+            }
+        }));
+    }
+    Ok(pixels)
+}
+
+/// Header tokenizer for PNM files
+pub(crate) struct PnmReader<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl<'a> PnmReader<'a> {
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&byte) = self.data.get(self.pos) {
+            if byte == b'#' {
+                self.pos = self.data[self.pos..]
+                    .iter()
+                    .position(|&b| b == b'\n')
+                    .map_or(self.data.len(), |offset| self.pos + offset);
+            } else if byte.is_ascii_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn token(&mut self) -> SnapshotResult<&'a [u8]> {
+        self.skip_whitespace_and_comments();
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(SnapshotError::InvalidImage("truncated header"));
+        }
+        Ok(&self.data[start..self.pos])
+    }
+
+    pub(crate) fn number(&mut self) -> SnapshotResult<u32> {
+        core::str::from_utf8(self.token()?)
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .ok_or(SnapshotError::InvalidImage("invalid header number"))
+    }
+}
+
+fn png_chunk(out: &mut Bytes, kind: [u8; 4], payload: &[u8]) {
+    out.extend((payload.len() as u32).to_be_bytes());
+    out.extend(kind);
+    out.extend(payload);
+    let mut crc = Crc32::new();
+    crc.update(&kind);
+    crc.update(payload);
+    out.extend(crc.finish().to_be_bytes());
+}
+
+/// Wrap data in a zlib stream of uncompressed (stored) deflate blocks
+fn zlib_stored(data: &[u8]) -> Bytes {
+    const MAX_BLOCK: usize = 0xFFFF;
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = u8::from(blocks.peek().is_none());
+        let len = block.len() as u16;
+        out.push(last);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+pub(crate) fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+/// CRC-32 (IEEE) as used by PNG chunks
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self(0xFFFF_FFFF)
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.0 ^= byte as u32;
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+
+    fn dims(rows: u16, cols: u16) -> TestResult<Dimensions> {
+        Ok(Dimensions::new(rows, cols)?)
+    }
+
+    fn sample() -> Snapshot {
+        let mut snapshot = Snapshot::new(10, 3, Color::White);
+        snapshot.set_pixel(0, 0, Color::Black);
+        snapshot.set_pixel(9, 1, Color::Red);
+        snapshot.set_pixel(4, 2, Color::Black);
+        snapshot
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(crc.finish(), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_from_buffers_decodes_colors() -> TestResult {
+        let mut black = [0xFF; 16];
+        let mut red = [0x00; 16];
+        black[0] = 0x7F; // (0, 0) black
+        red[3] = 0x01; // (15, 1) red
+        let snapshot = Snapshot::from_buffers(&black, &red, dims(8, 16)?, Rotation::Rotate0)?;
+        assert_eq!(snapshot.pixel(0, 0), Some(Color::Black));
+        assert_eq!(snapshot.pixel(1, 0), Some(Color::White));
+        assert_eq!(snapshot.pixel(15, 1), Some(Color::Red));
+        assert_eq!(snapshot.pixel(16, 0), None);
+
+        let short = Snapshot::from_buffers(&black[..4], &red, dims(8, 16)?, Rotation::Rotate0);
+        assert!(matches!(
+            short,
+            Err(SnapshotError::BufferTooSmall {
+                required: 16,
+                provided: 4
+            })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_buffers_honors_rotation() -> TestResult {
+        // Physical 8 rows x 16 cols; first physical pixel (0, 0) is black
+        let mut black = [0xFF; 16];
+        black[0] = 0x7F;
+        let red = [0x00; 16];
+        let expected = [
+            (Rotation::Rotate0, (16, 8), (0, 0)),
+            (Rotation::Rotate90, (8, 16), (0, 15)),
+            (Rotation::Rotate180, (16, 8), (15, 7)),
+            (Rotation::Rotate270, (8, 16), (7, 0)),
+        ];
+        for (rotation, (width, height), (x, y)) in expected {
+            let snapshot = Snapshot::from_buffers(&black, &red, dims(8, 16)?, rotation)?;
+            assert_eq!((snapshot.width(), snapshot.height()), (width, height));
+            let black_pixels: Vec<_> = snapshot
+                .pixels()
+                .iter()
+                .enumerate()
+                .filter(|(_, color)| **color == Color::Black)
+                .map(|(i, _)| (i as u32 % width, i as u32 / width))
+                .collect();
+            assert_eq!(black_pixels, [(x, y)], "{:?}", rotation);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_pnm_round_trip() -> TestResult {
+        let snapshot = sample();
+        for format in [ImageFormat::Pgm, ImageFormat::Ppm] {
+            let decoded = Snapshot::decode_pnm(&snapshot.encode(format))?;
+            assert!(decoded.diff(&snapshot).is_empty(), "{:?}", format);
+        }
+
+        // PBM cannot represent red
+        let pbm = snapshot.encode(ImageFormat::Pbm);
+        assert!(pbm.starts_with(b"P4\n10 3\n"));
+        let decoded = Snapshot::decode_pnm(&pbm)?;
+        assert_eq!(decoded.pixel(9, 1), Some(Color::Black));
+        assert_eq!(decoded.pixel(0, 0), Some(Color::Black));
+        assert_eq!(decoded.pixel(1, 0), Some(Color::White));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_pnm_with_comments() -> TestResult {
+        let data = b"P5\n# golden\n2 1\n255\n\x00\xFF";
+        let decoded = Snapshot::decode_pnm(data)?;
+        assert_eq!(decoded.pixels(), [Color::Black, Color::White]);
+        assert!(matches!(
+            Snapshot::decode_pnm(b"P6\n2 1\n255\n\x00"),
+            Err(SnapshotError::InvalidImage(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_pnm_rejects_huge_dimensions() {
+        for header in [
+            &b"P4\n4294967295 4294967295\n\x00"[..],
+            b"P5\n4294967295 4294967295\n255\n\x00",
+            b"P6\n4294967295 4294967295\n255\n\x00",
+        ] {
+            assert!(matches!(
+                Snapshot::decode_pnm(header),
+                Err(SnapshotError::InvalidImage(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_png_structure() {
+        let png = sample().encode(ImageFormat::Png);
+        assert_eq!(png[..8], PNG_SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 10, 0, 0, 0, 3]);
+        assert_eq!(png[24..26], [2, 3]);
+        // IEND chunk with its well-known CRC
+        assert_eq!(
+            png[png.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]
+        );
+    }
+
+    #[test]
+    fn test_zlib_stored_blocks() {
+        let data = vec![0xAB; 70_000];
+        let stream = zlib_stored(&data);
+        // header + two block headers + data + adler
+        assert_eq!(stream.len(), 2 + 5 * 2 + data.len() + 4);
+        assert_eq!(stream[2], 0);
+        assert_eq!(stream[2 + 5 + 0xFFFF], 1);
+    }
+
+    #[test]
+    fn test_diff_reports_pixels() {
+        let expected = sample();
+        let mut actual = sample();
+        actual.set_pixel(2, 1, Color::Red);
+        actual.set_pixel(5, 2, Color::Black);
+
+        let diff = actual.diff(&expected);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.pixels.len(), 2);
+        assert_eq!(
+            diff.pixels[0],
+            PixelDiff {
+                x: 2,
+                y: 1,
+                actual: Color::Red,
+                expected: Color::White,
+            }
+        );
+        assert_eq!(diff.bounding_box(), Some((2, 1, 4, 2)));
+        assert!(std::format!("{}", diff).starts_with("2 pixels differ within 4x2 at (2, 1)"));
+
+        let resized = Snapshot::new(3, 3, Color::White).diff(&expected);
+        assert!(!resized.is_empty());
+        assert!(resized.pixels.is_empty());
+    }
+
+    #[test]
+    fn test_golden_file() -> TestResult {
+        let path = std::env::temp_dir().join(format!("ssd1677-golden-{}.ppm", std::process::id()));
+        let snapshot = sample();
+        snapshot.save(&path)?;
+        snapshot.check_golden(&path)?;
+
+        let mut changed = sample();
+        changed.set_pixel(1, 1, Color::Black);
+        assert!(matches!(
+            changed.check_golden(&path),
+            Err(SnapshotError::Mismatch(_))
+        ));
+        std::fs::remove_file(&path)?;
+
+        assert!(matches!(
+            snapshot.save(path.with_extension("gif")),
+            Err(SnapshotError::UnknownFormat)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_emulator() -> TestResult {
+        use crate::config::Builder;
+        use crate::display::Display;
+        use embedded_hal::delay::DelayNs;
+
+        struct MockDelay;
+        impl DelayNs for MockDelay {
+            fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        let config = Builder::new()
+            .dimensions(dims(8, 16)?)
+            .data_entry_mode(0x03)
+            .build()?;
+        let mut display = Display::new(Emulator::from_config(&config), config);
+        display.reset(&mut MockDelay)?;
+        let mut black = [0xFF; 16];
+        black[0] = 0x7F;
+        display.update(&black, &[], &mut MockDelay)?;
+
+        let snapshot = Snapshot::from_emulator(display.interface(), Rotation::Rotate0);
+        let expected = Snapshot::from_buffers(&black, &[0; 16], dims(8, 16)?, Rotation::Rotate0)?;
+        assert!(snapshot.diff(&expected).is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,597 @@
+//! Multi-panel displays drawn as one
+//!
+//! [`TiledDisplay`] arranges several [`GraphicDisplay`]s, each driving its
+//! own panel, in a grid and implements
+//! [`DrawTarget`](embedded_graphics_core::draw_target::DrawTarget) over the
+//! whole area. Every panel keeps its own configuration, so panels mounted
+//! upside down or sideways are handled by their rotation and mirroring.
+//!
+//! Panels whose buffers changed since their last update are marked dirty,
+//! and only those are refreshed. With [`RefreshSchedule::Overlapped`] all dirty
+//! panels are written first and then waited for together, so a wall of
+//! panels takes about as long to refresh as a single one.
+//!
+//! ## Example
+//!
+//! ```rust,no_run
+//! use embedded_graphics::{
+//!     prelude::*,
+//!     primitives::{Circle, PrimitiveStyle},
+//! };
+//! use core::num::NonZeroUsize;
+//! use ssd1677::tiled::{RefreshSchedule, TiledDisplay};
+//! use ssd1677::{Color, GraphicDisplay, RefreshMode, Rotation};
+//! # use core::convert::Infallible;
+//! # use embedded_hal::delay::DelayNs;
+//! # use embedded_hal::digital::{InputPin, OutputPin};
+//! # use embedded_hal::spi::{Operation, SpiDevice};
+//! # use ssd1677::{Builder, Dimensions, Display, Interface};
+//! # struct MockSpi;
+//! # impl embedded_hal::spi::ErrorType for MockSpi { type Error = Infallible; }
+//! # impl SpiDevice for MockSpi {
+//! #     fn transaction(
+//! #         &mut self,
+//! #         _operations: &mut [Operation<'_, u8>],
+//! #     ) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # struct MockPin;
+//! # impl embedded_hal::digital::ErrorType for MockPin { type Error = Infallible; }
+//! # impl OutputPin for MockPin {
+//! #     fn set_low(&mut self) -> Result<(), Self::Error> { Ok(()) }
+//! #     fn set_high(&mut self) -> Result<(), Self::Error> { Ok(()) }
+//! # }
+//! # impl InputPin for MockPin {
+//! #     fn is_high(&mut self) -> Result<bool, Self::Error> { Ok(false) }
+//! #     fn is_low(&mut self) -> Result<bool, Self::Error> { Ok(true) }
+//! # }
+//! # struct MockDelay;
+//! # impl DelayNs for MockDelay { fn delay_ns(&mut self, _ns: u32) {} }
+//! # let dims = match Dimensions::new(480, 800) {
+//! #     Ok(dims) => dims,
+//! #     Err(_) => return,
+//! # };
+//! # let buffer_size = dims.buffer_size();
+//! # let mut delay = MockDelay;
+//! // Two rows of two panels; the bottom row is mounted upside down
+//! let panels = [0, 0, 180, 180].map(|degrees| {
+//!     let rotation = if degrees == 180 { Rotation::Rotate180 } else { Rotation::Rotate0 };
+//!     let config = Builder::new().dimensions(dims).rotation(rotation).build();
+//!     let interface = Interface::new(MockSpi, MockPin, MockPin, MockPin);
+//!     let display = Display::new(interface, config.expect("valid config"));
+//!     GraphicDisplay::new(display, vec![0u8; buffer_size], vec![0u8; buffer_size])
+//! });
+//! let Some(columns) = NonZeroUsize::new(2) else {
+//!     return;
+//! };
+//! let mut wall = TiledDisplay::new(panels, columns);
+//! wall.set_schedule(RefreshSchedule::Overlapped);
+//!
+//! // 1600x960 pixels, drawn across panel borders
+//! let _ = wall.clear(Color::White);
+//! let _ = Circle::new(Point::new(600, 280), 400)
+//!     .into_styled(PrimitiveStyle::with_stroke(Color::Black, 8))
+//!     .draw(&mut wall);
+//! let _ = wall.update(&mut delay);
+//!
+//! // Only the top-left panel is refreshed
+//! let _ = Circle::new(Point::new(100, 100), 50)
+//!     .into_styled(PrimitiveStyle::with_fill(Color::Red))
+//!     .draw(&mut wall);
+//! let _ = wall.update_with_mode(RefreshMode::Full, &mut delay);
+//! ```
+
+use core::convert::Infallible;
+use core::num::NonZeroUsize;
+use embedded_graphics_core::{
+    Pixel,
+    draw_target::DrawTarget,
+    geometry::{OriginDimensions, Point, Size},
+    image::GetPixel,
+    primitives::Rectangle,
+};
+use embedded_hal::delay::DelayNs;
+
+use crate::color::Color;
+use crate::display::{PendingUpdate, RefreshMode};
+use crate::error::Error;
+use crate::graphics::GraphicDisplay;
+use crate::interface::DisplayInterface;
+
+type TiledResult<I> = core::result::Result<(), Error<I>>;
+
+/// How [`TiledDisplay`] refreshes several dirty panels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RefreshSchedule {
+    /// Update one panel after the other, waiting for each refresh
+    #[default]
+    Sequential,
+    /// Start every refresh first, then wait for them in turn
+    ///
+    /// The panels refresh at the same time. The busy waits still happen one
+    /// at a time, but each only lasts as long as its panel has left.
+    Overlapped,
+}
+
+/// Grid of panels drawn as one display
+///
+/// Panels are placed row by row, `columns` to a row. Each column is as wide
+/// as its widest panel and each row as tall as its tallest, measured in the
+/// panel's drawing orientation; smaller panels sit in the top left corner of
+/// their cell, and the rest of the cell isn't drawn.
+///
+/// All panels share one interface and buffer type; with different SPI
+/// devices or pins per panel, use the HAL's type-erased variants.
+#[allow(clippy::type_complexity)] // arrays of panels
+pub struct TiledDisplay<I, B1, B2, const N: usize>
+where
+    I: DisplayInterface,
+    B1: AsMut<[u8]>,
+    B2: AsMut<[u8]>,
+{
+    panels: [GraphicDisplay<I, B1, B2>; N],
+    /// Drawing area of each panel within the grid
+    areas: [Rectangle; N],
+    /// Panels changed since their last update
+    dirty: [bool; N],
+    size: Size,
+    schedule: RefreshSchedule,
+}
+
+#[allow(clippy::type_complexity)] // arrays of panels
+impl<I, B1, B2, const N: usize> TiledDisplay<I, B1, B2, N>
+where
+    I: DisplayInterface,
+    B1: AsMut<[u8]>,
+    B2: AsMut<[u8]>,
+{
+    /// Arrange `panels` in rows of `columns`
+    ///
+    /// All panels start out dirty, so the first update shows the buffers on
+    /// every panel.
+    pub fn new(panels: [GraphicDisplay<I, B1, B2>; N], columns: NonZeroUsize) -> Self {
+        let columns = columns.get();
+        let sizes = panels.each_ref().map(OriginDimensions::size);
+        // Cell widths per column and heights per row
+        let mut widths = [0; N];
+        let mut heights = [0; N];
+        for (index, size) in sizes.iter().enumerate() {
+            let (column, row) = (index % columns, index / columns);
+            widths[column] = widths[column].max(size.width);
+            heights[row] = heights[row].max(size.height);
+        }
+        let areas = core::array::from_fn(|index| {
+            let (column, row) = (index % columns, index / columns);
+            let offset = Size::new(widths[..column].iter().sum(), heights[..row].iter().sum());
+            Rectangle::new(Point::zero() + offset, sizes[index])
+        });
+        Self {
+            panels,
+            areas,
+            dirty: [true; N],
+            size: Size::new(widths.iter().sum(), heights.iter().sum()),
+            schedule: RefreshSchedule::default(),
+        }
+    }
+
+    /// How dirty panels are refreshed
+    pub fn schedule(&self) -> RefreshSchedule {
+        self.schedule
+    }
+
+    /// Choose how dirty panels are refreshed
+    pub fn set_schedule(&mut self, schedule: RefreshSchedule) {
+        self.schedule = schedule;
+    }
+
+    /// Panel at `index`, in the order they were passed to
+    /// [`new`](TiledDisplay::new)
+    pub fn panel(&self, index: usize) -> Option<&GraphicDisplay<I, B1, B2>> {
+        self.panels.get(index)
+    }
+
+    /// Panel at `index`, mutably
+    ///
+    /// Marks the panel dirty, since it may be drawn to directly.
+    pub fn panel_mut(&mut self, index: usize) -> Option<&mut GraphicDisplay<I, B1, B2>> {
+        *self.dirty.get_mut(index)? = true;
+        self.panels.get_mut(index)
+    }
+
+    /// Area the panel at `index` covers, in drawing coordinates
+    pub fn panel_area(&self, index: usize) -> Option<Rectangle> {
+        self.areas.get(index).copied()
+    }
+
+    /// Whether the panel at `index` has changed since its last update
+    pub fn is_dirty(&self, index: usize) -> bool {
+        self.dirty.get(index).copied().unwrap_or(false)
+    }
+
+    /// Mark every panel dirty, e.g. after they were reset or cleared
+    pub fn invalidate(&mut self) {
+        self.dirty = [true; N];
+    }
+
+    /// Refresh the dirty panels with a full refresh
+    ///
+    /// # Errors
+    ///
+    /// See [`update_with_mode`](TiledDisplay::update_with_mode).
+    pub fn update<D: DelayNs>(&mut self, delay: &mut D) -> TiledResult<I> {
+        self.update_with_mode(RefreshMode::Full, delay)
+    }
+
+    /// Refresh the dirty panels using the given refresh mode
+    ///
+    /// Panels that update successfully are no longer dirty. A failing panel
+    /// doesn't stop the others from updating; it stays dirty, so the next
+    /// update tries again.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of any panel, e.g. [`Error::Interface`] if
+    /// there's a communication error.
+    pub fn update_with_mode<D: DelayNs>(
+        &mut self,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> TiledResult<I> {
+        match self.schedule {
+            RefreshSchedule::Sequential => self.update_sequentially(mode, delay),
+            RefreshSchedule::Overlapped => self.update_overlapped(mode, delay),
+        }
+    }
+
+    fn update_sequentially<D: DelayNs>(
+        &mut self,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> TiledResult<I> {
+        let mut result = Ok(());
+        for (panel, dirty) in self.panels.iter_mut().zip(&mut self.dirty) {
+            if !*dirty {
+                continue;
+            }
+            let updated = panel.update_with_mode(mode, delay);
+            *dirty = updated.is_err();
+            result = result.and(updated);
+        }
+        result
+    }
+
+    fn update_overlapped<D: DelayNs>(
+        &mut self,
+        mode: RefreshMode,
+        delay: &mut D,
+    ) -> TiledResult<I> {
+        let mut result = Ok(());
+        let mut pending: [Option<PendingUpdate>; N] = core::array::from_fn(|_| None);
+        for ((panel, dirty), pending) in self.panels.iter_mut().zip(&self.dirty).zip(&mut pending) {
+            if !*dirty {
+                continue;
+            }
+            match panel.begin_update_with_mode(mode) {
+                Ok(started) => *pending = Some(started),
+                Err(error) => result = result.and(Err(error)),
+            }
+        }
+        for ((panel, dirty), pending) in self.panels.iter_mut().zip(&mut self.dirty).zip(pending) {
+            let Some(pending) = pending else {
+                continue;
+            };
+            let finished = panel.finish_update(pending, delay);
+            *dirty = finished.is_err();
+            result = result.and(finished);
+        }
+        result
+    }
+
+    /// Take the TiledDisplay apart into its panels
+    pub fn into_panels(self) -> [GraphicDisplay<I, B1, B2>; N] {
+        self.panels
+    }
+
+    /// Index of the panel covering `point`
+    fn panel_at(&self, point: Point) -> Option<usize> {
+        self.areas.iter().position(|area| area.contains(point))
+    }
+}
+
+impl<I, B1, B2, const N: usize> DrawTarget for TiledDisplay<I, B1, B2, N>
+where
+    I: DisplayInterface,
+    B1: AsMut<[u8]>,
+    B2: AsMut<[u8]>,
+{
+    type Color = Color;
+    type Error = Infallible;
+
+    fn draw_iter<Iter>(&mut self, pixels: Iter) -> Result<(), Self::Error>
+    where
+        Iter: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            let Some(index) = self.panel_at(point) else {
+                continue;
+            };
+            let local = point - self.areas[index].top_left;
+            self.dirty[index] |= self.panels[index].draw_changed([Pixel(local, color)]);
+        }
+        Ok(())
+    }
+
+    fn fill_solid(&mut self, area: &Rectangle, color: Self::Color) -> Result<(), Self::Error> {
+        for ((panel, panel_area), dirty) in
+            self.panels.iter_mut().zip(&self.areas).zip(&mut self.dirty)
+        {
+            let clipped = area.intersection(panel_area);
+            if clipped.is_zero_sized() {
+                continue;
+            }
+            let local = Rectangle::new(clipped.top_left - panel_area.top_left, clipped.size);
+            *dirty |= panel.fill_changed(&local, color);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        for (panel, dirty) in self.panels.iter_mut().zip(&mut self.dirty) {
+            *dirty |= panel.clear_changed(color);
+        }
+        Ok(())
+    }
+}
+
+impl<I, B1, B2, const N: usize> GetPixel for TiledDisplay<I, B1, B2, N>
+where
+    I: DisplayInterface,
+    B1: AsMut<[u8]> + AsRef<[u8]>,
+    B2: AsMut<[u8]> + AsRef<[u8]>,
+{
+    type Color = Color;
+
+    fn pixel(&self, point: Point) -> Option<Self::Color> {
+        let index = self.panel_at(point)?;
+        self.panels[index].pixel(point - self.areas[index].top_left)
+    }
+}
+
+impl<I, B1, B2, const N: usize> OriginDimensions for TiledDisplay<I, B1, B2, N>
+where
+    I: DisplayInterface,
+    B1: AsMut<[u8]>,
+    B2: AsMut<[u8]>,
+{
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::config::{Builder, Dimensions, Rotation};
+    use crate::display::Display;
+    use crate::emulator::Emulator;
+    use crate::timing::{Timed, VirtualClock};
+    use alloc::vec::Vec;
+    use embedded_graphics::draw_target::DrawTargetExt;
+    use embedded_graphics::prelude::{Drawable, Primitive};
+    use embedded_graphics::primitives::{Circle, Line, PointsIter, PrimitiveStyle};
+
+    type Panel = GraphicDisplay<Timed<Emulator>, Vec<u8>, Vec<u8>>;
+    type Wall = TiledDisplay<Timed<Emulator>, Vec<u8>, Vec<u8>, 4>;
+    type Counts = [usize; 4];
+
+    const ROTATIONS: [Rotation; 4] = [
+        Rotation::Rotate0,
+        Rotation::Rotate90,
+        Rotation::Rotate180,
+        Rotation::Rotate270,
+    ];
+
+    /// Reset 24x16 panel on a shared clock
+    fn panel(rotation: Rotation, clock: &VirtualClock) -> TestResult<Panel> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 24)?)
+            .rotation(rotation)
+            .build()?;
+        let size = config.dimensions.buffer_size();
+        let timed = Timed::new(Emulator::from_config(&config)).clock_source(clock.clone());
+        let mut display = Display::new(timed, config);
+        display.reset(&mut clock.clone())?;
+        Ok(GraphicDisplay::new(
+            display,
+            alloc::vec![0xFF; size],
+            alloc::vec![0; size],
+        ))
+    }
+
+    fn columns(count: usize) -> TestResult<NonZeroUsize> {
+        Ok(NonZeroUsize::new(count).ok_or("no columns")?)
+    }
+
+    /// Two rows of two panels, one in each orientation
+    fn wall(clock: &VirtualClock) -> TestResult<Wall> {
+        let [first, second, third, fourth] = ROTATIONS.map(|rotation| panel(rotation, clock));
+        Ok(TiledDisplay::new(
+            [first?, second?, third?, fourth?],
+            columns(2)?,
+        ))
+    }
+
+    /// Shapes crossing every panel border
+    fn draw_scene<T: DrawTarget<Color = Color>>(target: &mut T) {
+        let _ = Circle::new(Point::new(6, 8), 30)
+            .into_styled(PrimitiveStyle::with_stroke(Color::Black, 3))
+            .draw(target);
+        let _ = Line::new(Point::new(0, 47), Point::new(39, 0))
+            .into_styled(PrimitiveStyle::with_stroke(Color::Red, 1))
+            .draw(target);
+        let _ = target.fill_solid(
+            &Rectangle::new(Point::new(18, 20), Size::new(12, 9)),
+            Color::Red,
+        );
+    }
+
+    fn emulator(wall: &Wall, index: usize) -> TestResult<&Emulator> {
+        let panel = wall.panel(index).ok_or("no such panel")?;
+        Ok(panel.display().interface().inner())
+    }
+
+    fn refresh_counts(wall: &Wall) -> TestResult<Counts> {
+        let mut counts = [0; 4];
+        for (index, count) in counts.iter_mut().enumerate() {
+            *count = emulator(wall, index)?.refresh_count();
+        }
+        Ok(counts)
+    }
+
+    #[test]
+    fn test_panels_are_placed_in_grid_cells() -> TestResult {
+        let wall = wall(&VirtualClock::new())?;
+        assert_eq!(wall.size(), Size::new(40, 48));
+        let areas = [
+            Rectangle::new(Point::new(0, 0), Size::new(24, 16)),
+            Rectangle::new(Point::new(24, 0), Size::new(16, 24)),
+            Rectangle::new(Point::new(0, 24), Size::new(24, 16)),
+            Rectangle::new(Point::new(24, 24), Size::new(16, 24)),
+        ];
+        for (index, area) in areas.into_iter().enumerate() {
+            assert_eq!(wall.panel_area(index), Some(area), "{index}");
+        }
+        assert_eq!(wall.panel_area(4), None);
+
+        let single_row = TiledDisplay::new(wall.into_panels(), columns(4)?);
+        assert_eq!(single_row.size(), Size::new(80, 24));
+        Ok(())
+    }
+
+    #[test]
+    fn test_drawing_matches_panels_drawn_separately() -> TestResult {
+        let clock = VirtualClock::new();
+        let mut wall = wall(&clock)?;
+        draw_scene(&mut wall);
+
+        for (index, rotation) in ROTATIONS.into_iter().enumerate() {
+            let area = wall.panel_area(index).ok_or("no such panel")?;
+            let mut expected = panel(rotation, &clock)?;
+            draw_scene(&mut expected.translated(Point::zero() - area.top_left));
+            let actual = wall.panel(index).ok_or("no such panel")?;
+            assert_eq!(actual.black_buffer(), expected.black_buffer(), "{index}");
+            assert_eq!(actual.red_buffer(), expected.red_buffer(), "{index}");
+        }
+
+        let bounds = Rectangle::new(Point::zero(), wall.size());
+        let drawn = bounds.points().filter(|point| wall.pixel(*point).is_some());
+        assert_eq!(drawn.count(), 4 * 24 * 16);
+        // Below the top left panel, in the cell of the taller one next to it
+        assert_eq!(wall.pixel(Point::new(5, 20)), None);
+        assert_eq!(wall.pixel(Point::new(20, 25)), Some(Color::Red));
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_refreshes_only_dirty_panels() -> TestResult {
+        let mut clock = VirtualClock::new();
+        let mut wall = wall(&clock)?;
+        let before = refresh_counts(&wall)?;
+        wall.update(&mut clock)?;
+        let after = refresh_counts(&wall)?;
+        assert!((0..4).all(|index| after[index] == before[index] + 1));
+        assert!((0..4).all(|index| !wall.is_dirty(index)));
+
+        wall.draw_iter([Pixel(Point::new(3, 5), Color::Black)])?;
+        let _ = wall.panel_mut(3).ok_or("no such panel")?;
+        assert_eq!(
+            (0..4).map(|index| wall.is_dirty(index)).collect::<Vec<_>>(),
+            [true, false, false, true]
+        );
+        wall.update(&mut clock)?;
+        let [first, second, third, fourth] = refresh_counts(&wall)?;
+        assert_eq!(
+            [first, second, third, fourth],
+            [after[0] + 1, after[1], after[2], after[3] + 1]
+        );
+        for index in [0, 3] {
+            let panel = wall.panel(index).ok_or("no such panel")?;
+            assert_eq!(emulator(&wall, index)?.visible_bw(), panel.black_buffer());
+        }
+
+        // Nothing drawn, nothing refreshed
+        wall.update(&mut clock)?;
+        assert_eq!(refresh_counts(&wall)?, [first, second, third, fourth]);
+        wall.invalidate();
+        assert!((0..4).all(|index| wall.is_dirty(index)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unchanged_pixels_leave_panels_clean() -> TestResult {
+        let mut clock = VirtualClock::new();
+        let mut wall = wall(&clock)?;
+        wall.update(&mut clock)?;
+
+        // The panels are white already
+        wall.draw_iter([Pixel(Point::new(3, 5), Color::White)])?;
+        wall.fill_solid(
+            &Rectangle::new(Point::new(10, 10), Size::new(20, 20)),
+            Color::White,
+        )?;
+        wall.clear(Color::White)?;
+        assert!((0..4).all(|index| !wall.is_dirty(index)));
+
+        wall.fill_solid(
+            &Rectangle::new(Point::new(0, 30), Size::new(4, 4)),
+            Color::Red,
+        )?;
+        assert_eq!(
+            (0..4).map(|index| wall.is_dirty(index)).collect::<Vec<_>>(),
+            [false, false, true, false]
+        );
+        wall.clear(Color::Black)?;
+        assert!((0..4).all(|index| wall.is_dirty(index)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_overlapped_updates_match_sequential_in_less_time() -> TestResult {
+        let mut elapsed = [0; 2];
+        let mut walls = [wall(&VirtualClock::new())?, wall(&VirtualClock::new())?];
+        walls[0].set_schedule(RefreshSchedule::Sequential);
+        walls[1].set_schedule(RefreshSchedule::Overlapped);
+        for (wall, elapsed) in walls.iter_mut().zip(&mut elapsed) {
+            let mut clock = wall
+                .panel(0)
+                .ok_or("no such panel")?
+                .display()
+                .interface()
+                .clock();
+            let start = clock.now_ms();
+            draw_scene(wall);
+            wall.update(&mut clock)?;
+            wall.fill_solid(
+                &Rectangle::new(Point::new(10, 0), Size::new(20, 48)),
+                Color::Black,
+            )?;
+            wall.update_with_mode(RefreshMode::Fast, &mut clock)?;
+            *elapsed = clock.now_ms() - start;
+        }
+
+        let [sequential, overlapped] = &walls;
+        for index in 0..4 {
+            let (expected, actual) = (emulator(sequential, index)?, emulator(overlapped, index)?);
+            assert_eq!(actual.visible_bw(), expected.visible_bw(), "{index}");
+            assert_eq!(actual.visible_red(), expected.visible_red(), "{index}");
+            assert_eq!(actual.bw_ram(), expected.bw_ram(), "{index}");
+            assert_eq!(actual.red_ram(), expected.red_ram(), "{index}");
+            assert_eq!(actual.refresh_count(), expected.refresh_count(), "{index}");
+        }
+        // Four panels refreshing together take as long as one
+        assert_eq!(elapsed[0], 4 * elapsed[1]);
+        Ok(())
+    }
+}
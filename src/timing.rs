@@ -0,0 +1,531 @@
+//! Simulated refresh timing for tests (requires `alloc` feature)
+//!
+//! Mock interfaces report "not busy" immediately, so code under test never
+//! sees how long a refresh really blocks. [`Timed`](crate::timing::Timed)
+//! wraps any [`DisplayInterface`] and makes each busy wait consume simulated
+//! time on a shared [`VirtualClock`](crate::timing::VirtualClock), according to
+//! the operation the controller was asked to perform:
+//!
+//! - a display update with a custom LUT takes the duration estimated from the
+//!   LUT's frame counts and frame rates (see
+//!   [`Waveform::estimated_duration_ms`](crate::waveform::Waveform::estimated_duration_ms))
+//! - a display update with the OTP waveform takes a fixed per-mode duration
+//! - clock/analog power steps, soft reset and RAM fills add fixed overheads
+//!
+//! All fixed figures live in [`TimingModel`](crate::timing::TimingModel) and
+//! can be tuned per panel.
+//!
+//! The clock is also a [`DelayNs`](embedded_hal::delay::DelayNs) and a trace
+//! [`Clock`](crate::trace::Clock), so the same virtual
+//! time can drive the delays of a UI loop and timestamp a
+//! [`Recorder`](crate::trace::Recorder).
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::emulator::Emulator;
+//! use ssd1677::timing::Timed;
+//! use ssd1677::{Builder, Dimensions, Display, RefreshMode};
+//!
+//! let config = Builder::new()
+//!     .dimensions(Dimensions::new(16, 16).unwrap())
+//!     .build()
+//!     .unwrap();
+//! let timed = Timed::new(Emulator::from_config(&config));
+//! let mut clock = timed.clock();
+//! let mut display = Display::new(timed, config);
+//! display.reset(&mut clock).unwrap();
+//!
+//! let start = clock.now_ms();
+//! let black = [0xFF; 32];
+//! display.update_with_mode(&black, &[], RefreshMode::Fast, &mut clock).unwrap();
+//! let elapsed = clock.now_ms() - start;
+//! assert!(elapsed >= 300 && elapsed < 1_000);
+//! ```
+
+use alloc::rc::Rc;
+use core::cell::Cell;
+use embedded_hal::delay::DelayNs;
+
+use crate::command::*;
+use crate::interface::DisplayInterface;
+use crate::lut::{LUT_SHORT_SIZE, LUT_SIZE};
+use crate::trace::Clock;
+use crate::waveform::Waveform;
+
+/// Shared simulated time in nanoseconds
+type Nanos = Rc<Cell<u64>>;
+
+/// Display Update Control 2 value after power-on reset
+const POR_CTRL2: u8 = 0xFF;
+
+/// Fixed durations used by [`Timed`], in milliseconds
+///
+/// The defaults are typical figures for SSD1677 panels; waveforms differ per
+/// panel and temperature, so measure your own and override them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimingModel {
+    /// Hardware reset pulse and recovery
+    pub hardware_reset_ms: u32,
+    /// BUSY time after a soft reset
+    pub soft_reset_ms: u32,
+    /// BUSY time after an auto write (RAM fill) command
+    pub ram_fill_ms: u32,
+    /// Enabling the analog supplies (booster ramp-up)
+    pub power_on_ms: u32,
+    /// Disabling the analog supplies
+    pub power_off_ms: u32,
+    /// Reading the temperature sensor
+    pub load_temperature_ms: u32,
+    /// Loading a waveform from OTP
+    pub load_lut_ms: u32,
+    /// Display update with the OTP waveform for display mode 1
+    pub otp_mode_1_ms: u32,
+    /// Display update with the OTP waveform for display mode 2
+    pub otp_mode_2_ms: u32,
+}
+
+impl Default for TimingModel {
+    fn default() -> Self {
+        Self {
+            hardware_reset_ms: 20,
+            soft_reset_ms: 10,
+            ram_fill_ms: 10,
+            power_on_ms: 20,
+            power_off_ms: 10,
+            load_temperature_ms: 5,
+            load_lut_ms: 5,
+            otp_mode_1_ms: 3_000,
+            otp_mode_2_ms: 1_500,
+        }
+    }
+}
+
+impl TimingModel {
+    /// Duration of a master activation for a Display Update Control 2 value
+    ///
+    /// `custom_lut` is the waveform in the LUT register if one was written
+    /// with `WRITE_LUT`; it is ignored when the sequence reloads the LUT from OTP.
+    pub fn activation_ms(&self, ctrl2: u8, custom_lut: Option<&Waveform>) -> u32 {
+        let step = |bit: u8, ms: u32| if ctrl2 & bit != 0 { ms } else { 0 };
+        let display = if ctrl2 & CTRL2_DISPLAY == 0 {
+            0
+        } else {
+            match custom_lut {
+                Some(waveform) if ctrl2 & CTRL2_LOAD_LUT == 0 => waveform.estimated_duration_ms(),
+                _ if ctrl2 & CTRL2_DISPLAY_MODE_2 != 0 => self.otp_mode_2_ms,
+                _ => self.otp_mode_1_ms,
+            }
+        };
+        step(CTRL2_ENABLE_ANALOG, self.power_on_ms)
+            + step(CTRL2_LOAD_TEMPERATURE, self.load_temperature_ms)
+            + step(CTRL2_LOAD_LUT, self.load_lut_ms)
+            + display
+            + step(CTRL2_DISABLE_ANALOG, self.power_off_ms)
+    }
+}
+
+/// Shared simulated time
+///
+/// Clones share the same time. Delays advance it instead of blocking, and
+/// [`Timed`] advances it on busy waits.
+#[derive(Clone, Debug, Default)]
+pub struct VirtualClock {
+    ns: Nanos,
+}
+
+impl VirtualClock {
+    /// Create a clock starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Elapsed simulated time in nanoseconds
+    pub fn now_ns(&self) -> u64 {
+        self.ns.get()
+    }
+
+    /// Elapsed simulated time in microseconds
+    pub fn now_us(&self) -> u64 {
+        self.now_ns() / 1_000
+    }
+
+    /// Elapsed simulated time in milliseconds
+    pub fn now_ms(&self) -> u64 {
+        self.now_ns() / 1_000_000
+    }
+
+    /// Advance the clock by `ns` nanoseconds
+    pub fn advance_ns(&self, ns: u64) {
+        self.ns.set(self.ns.get().saturating_add(ns));
+    }
+
+    /// Advance the clock by `ms` milliseconds
+    pub fn advance_ms(&self, ms: u32) {
+        self.advance_ns(ms as u64 * 1_000_000);
+    }
+}
+
+impl DelayNs for VirtualClock {
+    fn delay_ns(&mut self, ns: u32) {
+        self.advance_ns(ns as u64);
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now_us(&mut self) -> u64 {
+        VirtualClock::now_us(self)
+    }
+}
+
+/// Interface wrapper whose busy waits consume simulated time
+///
+/// Watches the command stream for the operations that set BUSY (soft reset,
+/// auto write, master activation) and, on the next busy wait, advances its
+/// [`VirtualClock`] to the end of the duration the [`TimingModel`] assigns to
+/// them. Time that passes in between counts towards it, so busy waits on
+/// several panels sharing one clock overlap like the real refreshes do.
+#[derive(Debug)]
+pub struct Timed<I> {
+    inner: I,
+    clock: VirtualClock,
+    model: TimingModel,
+    command: Option<u8>,
+    ctrl2: u8,
+    lut: [u8; LUT_SIZE],
+    lut_len: usize,
+    busy_until_ns: u64,
+    last_busy_ms: u32,
+}
+
+impl<I: DisplayInterface> Timed<I> {
+    /// Wrap an interface using the default [`TimingModel`]
+    pub fn new(inner: I) -> Self {
+        Self::with_model(inner, TimingModel::default())
+    }
+
+    /// Wrap an interface using the given timing figures
+    pub fn with_model(inner: I, model: TimingModel) -> Self {
+        Self {
+            inner,
+            clock: VirtualClock::new(),
+            model,
+            command: None,
+            ctrl2: POR_CTRL2,
+            lut: [0; LUT_SIZE],
+            lut_len: 0,
+            busy_until_ns: 0,
+            last_busy_ms: 0,
+        }
+    }
+
+    /// Use an existing clock, e.g. one shared with other simulated devices
+    pub fn clock_source(mut self, clock: VirtualClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Handle to the simulated time
+    pub fn clock(&self) -> VirtualClock {
+        self.clock.clone()
+    }
+
+    /// Timing figures in use
+    pub fn model(&self) -> &TimingModel {
+        &self.model
+    }
+
+    /// Duration of the most recent busy wait in milliseconds
+    pub fn last_busy_ms(&self) -> u32 {
+        self.last_busy_ms
+    }
+
+    /// Waveform in the LUT register, if a custom LUT was written
+    pub fn custom_lut(&self) -> Option<Waveform> {
+        if self.lut_len < LUT_SHORT_SIZE {
+            return None;
+        }
+        Some(Waveform::from_bytes(&self.lut))
+    }
+
+    /// Borrow the wrapped interface
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// Mutably borrow the wrapped interface
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Unwrap the interface
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    fn schedule(&mut self, ms: u32) {
+        let start = self.busy_until_ns.max(self.clock.now_ns());
+        self.busy_until_ns = start.saturating_add(ms as u64 * 1_000_000);
+    }
+
+    fn observe_command(&mut self, command: u8) {
+        self.command = Some(command);
+        match command {
+            SOFT_RESET => {
+                self.ctrl2 = POR_CTRL2;
+                self.lut_len = 0;
+                self.schedule(self.model.soft_reset_ms);
+            }
+            AUTO_WRITE_BW_RAM | AUTO_WRITE_RED_RAM => self.schedule(self.model.ram_fill_ms),
+            WRITE_LUT => self.lut_len = 0,
+            MASTER_ACTIVATION => self.activate(),
+            _ => {}
+        }
+    }
+
+    fn observe_data(&mut self, data: &[u8]) {
+        match self.command {
+            Some(DISPLAY_UPDATE_CTRL2) => {
+                if let Some(value) = data.last() {
+                    self.ctrl2 = *value;
+                }
+            }
+            Some(WRITE_LUT) => {
+                let end = (self.lut_len + data.len()).min(LUT_SIZE);
+                let count = end - self.lut_len;
+                self.lut[self.lut_len..end].copy_from_slice(&data[..count]);
+                self.lut_len = end;
+            }
+            _ => {}
+        }
+    }
+
+    fn activate(&mut self) {
+        let waveform = self.custom_lut();
+        let ms = self.model.activation_ms(self.ctrl2, waveform.as_ref());
+        if self.ctrl2 & CTRL2_LOAD_LUT != 0 {
+            // The OTP waveform replaces any custom LUT
+            self.lut_len = 0;
+        }
+        self.schedule(ms);
+    }
+}
+
+impl<I: DisplayInterface> DisplayInterface for Timed<I> {
+    type Error = I::Error;
+
+    fn send_command(&mut self, command: u8) -> Result<(), Self::Error> {
+        self.inner.send_command(command)?;
+        self.observe_command(command);
+        Ok(())
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.inner.send_data(data)?;
+        self.observe_data(data);
+        Ok(())
+    }
+
+    fn reset<D: DelayNs>(&mut self, delay: &mut D) {
+        self.inner.reset(delay);
+        self.command = None;
+        self.ctrl2 = POR_CTRL2;
+        self.lut_len = 0;
+        self.busy_until_ns = 0;
+        self.clock.advance_ms(self.model.hardware_reset_ms);
+    }
+
+    fn busy_wait<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Self::Error> {
+        let remaining_ns = self.busy_until_ns.saturating_sub(self.clock.now_ns());
+        self.last_busy_ms = u32::try_from(remaining_ns / 1_000_000).unwrap_or(u32::MAX);
+        self.clock.advance_ns(remaining_ns);
+        self.inner.busy_wait(delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::config::{Builder, Dimensions};
+    use crate::display::{Display, OtpMode, RefreshMode};
+    use crate::emulator::Emulator;
+    use crate::lut::{LUT_FAST, LUT_PARTIAL};
+    use crate::trace::{Event, Recorder};
+    use alloc::vec;
+
+    const BUFFER: usize = 16 * 16 / 8;
+
+    type TimedDisplay = Display<Timed<Emulator>>;
+    type ClockedDisplay = (TimedDisplay, VirtualClock);
+
+    fn timed_display() -> TestResult<ClockedDisplay> {
+        let clock = VirtualClock::new();
+        Ok((timed_display_on(&clock)?, clock))
+    }
+
+    fn timed_display_on(clock: &VirtualClock) -> TestResult<TimedDisplay> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .build()?;
+        let timed = Timed::new(Emulator::from_config(&config)).clock_source(clock.clone());
+        let mut display = Display::new(timed, config);
+        display.reset(&mut clock.clone())?;
+        Ok(display)
+    }
+
+    fn update_ms(display: &mut TimedDisplay, mode: RefreshMode) -> TestResult<u64> {
+        let mut clock = display.interface().clock();
+        let start = clock.now_ms();
+        display.update_with_mode(&[0xFF; BUFFER], &[], mode, &mut clock)?;
+        Ok(clock.now_ms() - start)
+    }
+
+    #[test]
+    fn test_reset_consumes_reset_and_fill_time() -> TestResult {
+        let (_display, clock) = timed_display()?;
+        let model = TimingModel::default();
+        let expected = model.hardware_reset_ms + model.soft_reset_ms + 2 * model.ram_fill_ms;
+        assert_eq!(clock.now_ms(), expected as u64);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_durations_follow_mode() -> TestResult {
+        let (mut display, _clock) = timed_display()?;
+        let model = TimingModel::default();
+
+        let fast = update_ms(&mut display, RefreshMode::Fast)?;
+        let fast_lut = Waveform::from_bytes(&LUT_FAST).estimated_duration_ms();
+        assert_eq!(
+            fast,
+            (fast_lut + model.power_on_ms + model.power_off_ms) as u64
+        );
+
+        let partial = update_ms(&mut display, RefreshMode::Partial)?;
+        let partial_lut = Waveform::from_bytes(&LUT_PARTIAL).estimated_duration_ms();
+        assert_eq!(
+            partial,
+            (partial_lut + model.power_on_ms + model.power_off_ms) as u64
+        );
+
+        // Full reloads the OTP waveform, so the custom LUT no longer applies
+        let full = update_ms(&mut display, RefreshMode::Full)?;
+        assert!(full >= model.otp_mode_1_ms as u64);
+
+        assert!((300..1_000).contains(&fast));
+        assert!((1_500..2_000).contains(&partial));
+        assert!(fast < partial && partial < full);
+        Ok(())
+    }
+
+    #[test]
+    fn test_busy_waits_overlap_on_a_shared_clock() -> TestResult {
+        let single = update_ms(&mut timed_display()?.0, RefreshMode::Fast)?;
+        let mut clock = VirtualClock::new();
+        let mut displays = [timed_display_on(&clock)?, timed_display_on(&clock)?];
+        let black = [0x0F; BUFFER];
+
+        let start = clock.now_ms();
+        let pending = displays
+            .each_mut()
+            .map(|display| display.begin_update_with_mode(&black, &[], RefreshMode::Fast));
+        for (display, pending) in displays.iter_mut().zip(pending) {
+            display.finish_update(pending?, &black, &mut clock)?;
+        }
+        assert_eq!(clock.now_ms() - start, single);
+
+        for display in &displays {
+            let emulator = display.interface().inner();
+            assert_eq!(emulator.refresh_count(), 1);
+            assert_eq!(emulator.bw_ram(), black);
+            // The BW-only fast refresh leaves RED RAM holding the new image
+            assert_eq!(emulator.red_ram(), black);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_otp_mode_2_uses_its_duration() -> TestResult {
+        let (mut display, _clock) = timed_display()?;
+        let model = TimingModel::default();
+        let mode_2 = update_ms(&mut display, RefreshMode::Otp(OtpMode::Mode2))?;
+        assert!(mode_2 >= model.otp_mode_2_ms as u64);
+        assert!(mode_2 < model.otp_mode_1_ms as u64);
+        assert_eq!(display.interface().last_busy_ms() as u64, mode_2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_lut_duration_from_frames() -> TestResult {
+        let (mut display, _clock) = timed_display()?;
+        let mut lut = LUT_FAST;
+        // Double the frame count of group 0
+        lut[50] *= 2;
+        let mut clock = display.interface().clock();
+        display.update_with_custom_lut(
+            &[0xFF; BUFFER],
+            &[],
+            RefreshMode::Fast,
+            &lut,
+            &mut clock,
+        )?;
+        let waveform = display
+            .interface()
+            .custom_lut()
+            .ok_or("no custom LUT loaded")?;
+        assert_eq!(waveform.to_bytes(), lut);
+
+        let expected = TimingModel::default().activation_ms(0xC7, Some(&waveform));
+        assert_eq!(display.interface().last_busy_ms(), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn test_ui_loop_paced_by_refreshes() -> TestResult {
+        let (mut display, mut clock) = timed_display()?;
+        let start = clock.now_ms();
+        let mut frames = 0;
+        // Budget of two seconds: fast updates plus a 100 ms input poll each
+        while clock.now_ms() - start < 2_000 {
+            display.update_with_mode(&[0x00; BUFFER], &[], RefreshMode::Fast, &mut clock)?;
+            clock.delay_ms(100);
+            frames += 1;
+        }
+        assert!(frames > 1 && frames <= 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_recorder_timestamps_from_virtual_clock() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .build()?;
+        let timed = Timed::new(Emulator::from_config(&config));
+        let mut clock = timed.clock();
+        let recorder = Recorder::with_clock(timed, clock.clone());
+        let mut display = Display::new(recorder, config);
+        display.reset(&mut clock)?;
+
+        let busy: vec::Vec<u64> = display
+            .interface()
+            .trace()
+            .entries()
+            .iter()
+            .filter_map(|entry| match entry.event {
+                Event::BusyWait { duration_us, .. } => Some(duration_us),
+                _ => None,
+            })
+            .collect();
+        let model = TimingModel::default();
+        assert_eq!(
+            busy,
+            [
+                model.soft_reset_ms as u64 * 1_000,
+                model.ram_fill_ms as u64 * 1_000,
+                model.ram_fill_ms as u64 * 1_000,
+            ]
+        );
+        Ok(())
+    }
+}
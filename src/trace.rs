@@ -0,0 +1,785 @@
+//! Command trace recording and decoding (requires `alloc` feature)
+//!
+//! [`Recorder`](crate::trace::Recorder) wraps any [`DisplayInterface`] and
+//! records every reset, command, data payload and busy wait with a timestamp
+//! before forwarding it. The resulting [`Trace`](crate::trace::Trace) can be
+//! pretty-printed with command names and decoded fields:
+//!
+//! ```text
+//! [       0 us] RESET
+//! [      10 us] SOFT_RESET
+//! [      20 us] BUSY 1500 us
+//! [    1520 us] DATA_ENTRY_MODE x=inc y=dec am=x (0x01)
+//! [    1530 us] SET_RAM_Y_RANGE start=479 end=0
+//! [    1540 us] WRITE_RAM_BW 48000 bytes
+//! ```
+//!
+//! and serialized to a line-based text format (see
+//! [`Trace::to_text`](crate::trace::Trace::to_text)) so traces from two
+//! driver versions can be stored and diffed.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::emulator::Emulator;
+//! use ssd1677::trace::Recorder;
+//! use ssd1677::{Builder, Dimensions, Display};
+//! # use embedded_hal::delay::DelayNs;
+//! # struct MockDelay;
+//! # impl DelayNs for MockDelay { fn delay_ns(&mut self, _ns: u32) {} }
+//! # let mut delay = MockDelay;
+//!
+//! let config = Builder::new()
+//!     .dimensions(Dimensions::new(16, 16).unwrap())
+//!     .build()
+//!     .unwrap();
+//! let recorder = Recorder::new(Emulator::from_config(&config));
+//! let mut display = Display::new(recorder, config);
+//! display.reset(&mut delay).unwrap();
+//!
+//! let trace = display.interface().trace();
+//! let listing = format!("{}", trace);
+//! assert!(listing.contains("SOFT_RESET"));
+//! assert!(listing.contains("DRIVER_OUTPUT_CONTROL mux=16"));
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write as _};
+use embedded_hal::delay::DelayNs;
+
+use crate::command::*;
+use crate::interface::DisplayInterface;
+
+type TraceResult<T> = core::result::Result<T, TraceParseError>;
+type Payload = Vec<u8>;
+type Ctrl2Step = (u8, &'static str);
+
+/// Number of data bytes shown for commands without a dedicated decoder
+const HEX_PREVIEW_BYTES: usize = 8;
+
+/// Source of trace timestamps in microseconds
+///
+/// Implemented for closures returning `u64`, so any monotonic timer can be
+/// passed to [`Recorder::with_clock`].
+pub trait Clock {
+    /// Current time in microseconds
+    fn now_us(&mut self) -> u64;
+}
+
+impl<F: FnMut() -> u64> Clock for F {
+    fn now_us(&mut self) -> u64 {
+        self()
+    }
+}
+
+/// Clock that always reads zero, for traces where only ordering matters
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoClock;
+
+impl Clock for NoClock {
+    fn now_us(&mut self) -> u64 {
+        0
+    }
+}
+
+/// Wall clock measuring microseconds since it was created
+#[cfg(any(test, feature = "std"))]
+#[derive(Clone, Copy, Debug)]
+pub struct StdClock(std::time::Instant);
+
+#[cfg(any(test, feature = "std"))]
+impl StdClock {
+    /// Start a clock at zero
+    pub fn new() -> Self {
+        Self(std::time::Instant::now())
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(any(test, feature = "std"))]
+impl Clock for StdClock {
+    fn now_us(&mut self) -> u64 {
+        self.0.elapsed().as_micros() as u64
+    }
+}
+
+/// A single interface operation
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// Hardware reset
+    Reset,
+    /// Command byte
+    Command(u8),
+    /// Data payload of one `send_data` call
+    Data(Payload),
+    /// Busy wait
+    BusyWait {
+        /// Time spent waiting in microseconds
+        duration_us: u64,
+        /// `false` if the wait returned an error (e.g. timeout)
+        completed: bool,
+    },
+}
+
+/// An [`Event`] with the time it started
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// Timestamp in microseconds
+    pub timestamp_us: u64,
+    /// The recorded operation
+    pub event: Event,
+}
+
+/// Errors that can occur while parsing a serialized trace
+#[derive(Debug, PartialEq)]
+pub enum TraceParseError {
+    /// A line could not be parsed
+    InvalidLine {
+        /// 1-based line number
+        line: usize,
+    },
+}
+
+impl fmt::Display for TraceParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLine { line } => write!(f, "Invalid trace line {}", line),
+        }
+    }
+}
+
+impl core::error::Error for TraceParseError {}
+
+/// A recorded sequence of interface operations
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    entries: Vec<Entry>,
+}
+
+impl Trace {
+    /// Create an empty trace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append an event
+    pub fn push(&mut self, timestamp_us: u64, event: Event) {
+        self.entries.push(Entry {
+            timestamp_us,
+            event,
+        });
+    }
+
+    /// Recorded entries in order
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Number of recorded entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all entries
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Command bytes in the order they were sent
+    pub fn commands(&self) -> impl Iterator<Item = u8> + '_ {
+        self.entries.iter().filter_map(|entry| match entry.event {
+            Event::Command(command) => Some(command),
+            _ => None,
+        })
+    }
+
+    /// Group entries into commands with their data
+    ///
+    /// Consecutive data payloads following a command are merged, so the
+    /// result does not depend on how the driver chunked its writes.
+    pub fn transactions(&self) -> Vec<Transaction> {
+        let mut transactions: Vec<Transaction> = Vec::new();
+        for entry in &self.entries {
+            let merged = match &entry.event {
+                Event::Data(data) => merge_data(&mut transactions, data),
+                _ => false,
+            };
+            if merged {
+                continue;
+            }
+            let kind = match &entry.event {
+                Event::Reset => TransactionKind::Reset,
+                Event::Command(command) => TransactionKind::Command {
+                    command: Some(*command),
+                    data: Vec::new(),
+                },
+                Event::Data(data) => TransactionKind::Command {
+                    command: None,
+                    data: data.clone(),
+                },
+                Event::BusyWait {
+                    duration_us,
+                    completed,
+                } => TransactionKind::BusyWait {
+                    duration_us: *duration_us,
+                    completed: *completed,
+                },
+            };
+            transactions.push(Transaction {
+                timestamp_us: entry.timestamp_us,
+                kind,
+            });
+        }
+        transactions
+    }
+
+    /// Index of the first transaction that differs from `other`, ignoring timestamps
+    ///
+    /// Busy wait durations are ignored as well; only whether each wait
+    /// completed is compared. Returns `None` if the traces match.
+    pub fn first_difference(&self, other: &Self) -> Option<usize> {
+        let ours = self.transactions();
+        let theirs = other.transactions();
+        let mismatch = ours
+            .iter()
+            .zip(&theirs)
+            .position(|(a, b)| !a.kind.same_operation(&b.kind));
+        mismatch.or_else(|| (ours.len() != theirs.len()).then(|| ours.len().min(theirs.len())))
+    }
+
+    /// Serialize to a line-based text format
+    ///
+    /// Each line is `<timestamp_us> <kind> [<args>]` where kind is `R`
+    /// (reset), `C <hex command>`, `D <hex bytes>` or `B <duration_us> ok|err`.
+    /// [`Trace::parse`] reads it back.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for entry in &self.entries {
+            let _ = write!(text, "{} ", entry.timestamp_us);
+            let _ = match &entry.event {
+                Event::Reset => writeln!(text, "R"),
+                Event::Command(command) => writeln!(text, "C {:02x}", command),
+                Event::Data(data) => writeln!(text, "D {}", hex(data)),
+                Event::BusyWait {
+                    duration_us,
+                    completed,
+                } => writeln!(
+                    text,
+                    "B {} {}",
+                    duration_us,
+                    if *completed { "ok" } else { "err" }
+                ),
+            };
+        }
+        text
+    }
+
+    /// Parse the format written by [`Trace::to_text`]
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> TraceResult<Self> {
+        let mut trace = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let entry = parse_line(line).ok_or(TraceParseError::InvalidLine { line: index + 1 })?;
+            trace.entries.push(entry);
+        }
+        Ok(trace)
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for transaction in self.transactions() {
+            writeln!(f, "{}", transaction)?;
+        }
+        Ok(())
+    }
+}
+
+/// Append data to the last transaction if it is a command
+fn merge_data(transactions: &mut [Transaction], data: &[u8]) -> bool {
+    match transactions.last_mut().map(|last| &mut last.kind) {
+        Some(TransactionKind::Command { data: merged, .. }) => {
+            merged.extend_from_slice(data);
+            true
+        }
+        _ => false,
+    }
+}
+
+fn parse_line(line: &str) -> Option<Entry> {
+    let mut parts = line.split_whitespace();
+    let timestamp_us = parts.next()?.parse().ok()?;
+    let event = match parts.next()? {
+        "R" => Event::Reset,
+        "C" => Event::Command(u8::from_str_radix(parts.next()?, 16).ok()?),
+        "D" => Event::Data(parse_hex(parts.next().unwrap_or_default())?),
+        "B" => Event::BusyWait {
+            duration_us: parts.next()?.parse().ok()?,
+            completed: match parts.next()? {
+                "ok" => true,
+                "err" => false,
+                _ => return None,
+            },
+        },
+        _ => return None,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Entry {
+        timestamp_us,
+        event,
+    })
+}
+
+fn hex(data: &[u8]) -> String {
+    let mut text = String::with_capacity(data.len() * 2);
+    for byte in data {
+        let _ = write!(text, "{:02x}", byte);
+    }
+    text
+}
+
+fn parse_hex(text: &str) -> Option<Payload> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| {
+            text.get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+        })
+        .collect()
+}
+
+/// What a [`Transaction`] did
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransactionKind {
+    /// Hardware reset
+    Reset,
+    /// A command and all data sent after it
+    Command {
+        /// Command byte, or `None` for data sent before any command
+        command: Option<u8>,
+        /// Merged data payload
+        data: Payload,
+    },
+    /// Busy wait
+    BusyWait {
+        /// Time spent waiting in microseconds
+        duration_us: u64,
+        /// `false` if the wait returned an error
+        completed: bool,
+    },
+}
+
+impl TransactionKind {
+    fn same_operation(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::BusyWait { completed: a, .. }, Self::BusyWait { completed: b, .. }) => a == b,
+            _ => self == other,
+        }
+    }
+}
+
+/// A decoded step of a [`Trace`]
+///
+/// The `Display` impl prints the command name and decoded fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transaction {
+    /// Timestamp of the first event in microseconds
+    pub timestamp_us: u64,
+    /// The decoded operation
+    pub kind: TransactionKind,
+}
+
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:>8} us] ", self.timestamp_us)?;
+        match &self.kind {
+            TransactionKind::Reset => write!(f, "RESET"),
+            TransactionKind::BusyWait {
+                duration_us,
+                completed,
+            } => {
+                write!(f, "BUSY {} us", duration_us)?;
+                if !completed {
+                    write!(f, " (failed)")?;
+                }
+                Ok(())
+            }
+            TransactionKind::Command { command, data } => {
+                match command {
+                    Some(command) => match command_name(*command) {
+                        Some(name) => write!(f, "{}", name)?,
+                        None => write!(f, "CMD 0x{:02X}", command)?,
+                    },
+                    None => write!(f, "DATA")?,
+                }
+                let fields = command.map_or_else(|| hex_preview(data), |c| describe(c, data));
+                if !fields.is_empty() {
+                    write!(f, " {}", fields)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Decode the fields of a command's data payload
+///
+/// Falls back to a hex dump for commands without a dedicated decoder or
+/// payloads of unexpected length.
+pub fn describe(command: u8, data: &[u8]) -> String {
+    let word = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+    match (command, data.len()) {
+        (DRIVER_OUTPUT_CONTROL, 3) => {
+            format!("mux={} scan=0x{:02X}", word(0) + 1, data[2])
+        }
+        (GATE_SCAN_START, 2) => format!("gate={}", word(0)),
+        (DATA_ENTRY_MODE, 1) => format!(
+            "x={} y={} am={} (0x{:02X})",
+            direction(data[0] & 0x01),
+            direction(data[0] & 0x02),
+            if data[0] & 0x04 != 0 { "y" } else { "x" },
+            data[0]
+        ),
+        (SET_RAM_X_RANGE | SET_RAM_Y_RANGE, 4) => {
+            format!("start={} end={}", word(0), word(2))
+        }
+        (SET_RAM_X_COUNTER | SET_RAM_Y_COUNTER, 2) => format!("value={}", word(0)),
+        (WRITE_RAM_BW | WRITE_RAM_RED | WRITE_LUT, len) => format!("{} bytes", len),
+        (AUTO_WRITE_BW_RAM | AUTO_WRITE_RED_RAM, 1) => format!("pattern=0x{:02X}", data[0]),
+        (DISPLAY_UPDATE_CTRL1, len) if len >= 1 => format!(
+            "red={} bw={} (0x{:02X})",
+            ram_option(data[0] >> 4),
+            ram_option(data[0] & 0x0F),
+            data[0]
+        ),
+        (DISPLAY_UPDATE_CTRL2, 1) => format!("{} (0x{:02X})", ctrl2_steps(data[0]), data[0]),
+        (WRITE_TEMP, 2) => {
+            let raw = i16::from_be_bytes([data[0], data[1]]) >> 4;
+            format!("{}/16 C", raw)
+        }
+        (DEEP_SLEEP, 1) => format!("mode=0x{:02X}", data[0]),
+        _ => hex_preview(data),
+    }
+}
+
+fn direction(bit: u8) -> &'static str {
+    if bit != 0 { "inc" } else { "dec" }
+}
+
+fn ram_option(option: u8) -> &'static str {
+    match option {
+        0x0 => "normal",
+        0x4 => "bypass",
+        0x8 => "inverse",
+        _ => "reserved",
+    }
+}
+
+fn ctrl2_steps(value: u8) -> String {
+    const STEPS: [Ctrl2Step; 8] = [
+        (CTRL2_ENABLE_CLOCK, "clock_on"),
+        (CTRL2_ENABLE_ANALOG, "analog_on"),
+        (CTRL2_LOAD_TEMPERATURE, "load_temp"),
+        (CTRL2_LOAD_LUT, "load_lut"),
+        (CTRL2_DISPLAY_MODE_2, "mode2"),
+        (CTRL2_DISPLAY, "display"),
+        (CTRL2_DISABLE_ANALOG, "analog_off"),
+        (CTRL2_DISABLE_CLOCK, "clock_off"),
+    ];
+    let steps: Vec<&str> = STEPS
+        .iter()
+        .filter(|(bit, _)| value & bit != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    if steps.is_empty() {
+        String::from("none")
+    } else {
+        steps.join("+")
+    }
+}
+
+fn hex_preview(data: &[u8]) -> String {
+    let mut text = String::new();
+    for (i, byte) in data.iter().take(HEX_PREVIEW_BYTES).enumerate() {
+        let separator = if i == 0 { "" } else { " " };
+        let _ = write!(text, "{}{:02X}", separator, byte);
+    }
+    if data.len() > HEX_PREVIEW_BYTES {
+        let _ = write!(text, " ... ({} bytes)", data.len());
+    }
+    text
+}
+
+/// [`DisplayInterface`] wrapper that records a [`Trace`]
+///
+/// Every operation is recorded, then forwarded to the wrapped interface.
+/// Errors from the wrapped interface are passed through unchanged.
+#[derive(Debug)]
+pub struct Recorder<I, C = NoClock> {
+    inner: I,
+    clock: C,
+    trace: Trace,
+}
+
+impl<I> Recorder<I, NoClock> {
+    /// Wrap an interface, recording without timestamps
+    pub fn new(inner: I) -> Self {
+        Self::with_clock(inner, NoClock)
+    }
+}
+
+impl<I, C: Clock> Recorder<I, C> {
+    /// Wrap an interface, timestamping events with `clock`
+    pub fn with_clock(inner: I, clock: C) -> Self {
+        Self {
+            inner,
+            clock,
+            trace: Trace::new(),
+        }
+    }
+
+    /// The trace recorded so far
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// Take the recorded trace, leaving an empty one
+    pub fn take_trace(&mut self) -> Trace {
+        core::mem::take(&mut self.trace)
+    }
+
+    /// Access the wrapped interface
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// Mutably access the wrapped interface (operations are not recorded)
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Unwrap into the wrapped interface and the recorded trace
+    pub fn into_parts(self) -> (I, Trace) {
+        (self.inner, self.trace)
+    }
+
+    fn record(&mut self, event: Event) {
+        let now = self.clock.now_us();
+        self.trace.push(now, event);
+    }
+}
+
+impl<I: DisplayInterface, C: Clock> DisplayInterface for Recorder<I, C> {
+    type Error = I::Error;
+
+    fn send_command(&mut self, command: u8) -> Result<(), Self::Error> {
+        self.record(Event::Command(command));
+        self.inner.send_command(command)
+    }
+
+    fn send_data(&mut self, data: &[u8]) -> Result<(), Self::Error> {
+        self.record(Event::Data(data.to_vec()));
+        self.inner.send_data(data)
+    }
+
+    fn reset<D: DelayNs>(&mut self, delay: &mut D) {
+        self.record(Event::Reset);
+        self.inner.reset(delay);
+    }
+
+    fn busy_wait<D: DelayNs>(&mut self, delay: &mut D) -> Result<(), Self::Error> {
+        let start = self.clock.now_us();
+        let result = self.inner.busy_wait(delay);
+        let duration_us = self.clock.now_us().saturating_sub(start);
+        self.trace.push(
+            start,
+            Event::BusyWait {
+                duration_us,
+                completed: result.is_ok(),
+            },
+        );
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TestResult;
+    use crate::config::{Builder, Dimensions};
+    use crate::display::{Display, RefreshMode};
+    use crate::emulator::Emulator;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use core::cell::Cell;
+
+    struct MockDelay;
+    impl DelayNs for MockDelay {
+        fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    fn recorded_update() -> TestResult<Trace> {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .build()?;
+        let mut display = Display::new(Recorder::new(Emulator::from_config(&config)), config);
+        display.reset(&mut MockDelay)?;
+        display.update_with_mode(&[0xFF; 32], &[], RefreshMode::Full, &mut MockDelay)?;
+        Ok(display.interface_mut().take_trace())
+    }
+
+    #[test]
+    fn test_recorder_forwards_and_records() -> TestResult {
+        let trace = recorded_update()?;
+        assert_eq!(trace.entries()[0].event, Event::Reset);
+        assert_eq!(trace.commands().next(), Some(SOFT_RESET));
+        assert!(trace.commands().any(|command| command == MASTER_ACTIVATION));
+        assert!(
+            trace
+                .entries()
+                .iter()
+                .any(|entry| entry.event == Event::Data(vec![0xFF; 32]))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_clock_timestamps_events() -> TestResult {
+        let ticks = Cell::new(0u64);
+        let clock = || {
+            ticks.set(ticks.get() + 5);
+            ticks.get()
+        };
+        let mut recorder = Recorder::with_clock(Emulator::new(Dimensions::new(8, 8)?), clock);
+        recorder.send_command(SOFT_RESET)?;
+        recorder.busy_wait(&mut MockDelay)?;
+        let entries = recorder.trace().entries();
+        assert_eq!(entries[0].timestamp_us, 5);
+        assert_eq!(
+            entries[1],
+            Entry {
+                timestamp_us: 10,
+                event: Event::BusyWait {
+                    duration_us: 5,
+                    completed: true,
+                },
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_pretty_print_decodes_fields() -> TestResult {
+        let listing = recorded_update()?.to_string();
+        assert!(listing.starts_with("[       0 us] RESET\n"));
+        assert!(listing.contains("] DATA_ENTRY_MODE x=inc y=dec am=x (0x01)\n"));
+        assert!(listing.contains("] SET_RAM_X_RANGE start=0 end=15\n"));
+        assert!(listing.contains("] SET_RAM_Y_RANGE start=15 end=0\n"));
+        assert!(listing.contains("] WRITE_RAM_BW 32 bytes\n"));
+        assert!(listing.contains("] DISPLAY_UPDATE_CTRL1 red=bypass bw=normal (0x40)\n"));
+        assert!(listing.contains("] BUSY 0 us\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pretty_print_decodes_scroll() -> TestResult {
+        let config = Builder::new()
+            .dimensions(Dimensions::new(16, 16)?)
+            .build()?;
+        let mut display = Display::new(Recorder::new(Emulator::from_config(&config)), config);
+        display.reset(&mut MockDelay)?;
+        display.interface_mut().take_trace();
+        display.set_scroll_offset(5)?;
+        let listing = display.interface_mut().take_trace().to_string();
+        assert_eq!(listing, "[       0 us] GATE_SCAN_START gate=5\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_describe_fallbacks() {
+        assert_eq!(
+            describe(DISPLAY_UPDATE_CTRL2, &[0xF7]),
+            "clock_on+analog_on+load_temp+load_lut+display+analog_off+clock_off (0xF7)"
+        );
+        assert_eq!(describe(WRITE_TEMP, &[0x19, 0x00]), "400/16 C");
+        assert_eq!(describe(0x7F, &[1, 2]), "01 02");
+        assert_eq!(
+            describe(SET_RAM_X_RANGE, &[0; 10]),
+            "00 00 00 00 00 00 00 00 ... (10 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_text_round_trip() -> TestResult {
+        let mut trace = recorded_update()?;
+        trace.push(
+            42,
+            Event::BusyWait {
+                duration_us: 7,
+                completed: false,
+            },
+        );
+        trace.push(43, Event::Data(Vec::new()));
+        let text = trace.to_text();
+        assert!(text.starts_with("0 R\n0 C 12\n"));
+        assert_eq!(Trace::parse(&text)?, trace);
+        assert_eq!(
+            Trace::parse("# comment\n\n0 C 12\n1 X\n"),
+            Err(TraceParseError::InvalidLine { line: 4 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_difference_ignores_timing_and_chunking() {
+        let mut a = Trace::new();
+        a.push(0, Event::Command(WRITE_RAM_BW));
+        a.push(1, Event::Data(vec![1, 2]));
+        a.push(2, Event::Data(vec![3]));
+        a.push(
+            3,
+            Event::BusyWait {
+                duration_us: 10,
+                completed: true,
+            },
+        );
+
+        let mut b = Trace::new();
+        b.push(100, Event::Command(WRITE_RAM_BW));
+        b.push(101, Event::Data(vec![1, 2, 3]));
+        b.push(
+            102,
+            Event::BusyWait {
+                duration_us: 99,
+                completed: true,
+            },
+        );
+        assert_eq!(a.first_difference(&b), None);
+
+        b.push(103, Event::Command(DEEP_SLEEP));
+        assert_eq!(a.first_difference(&b), Some(2));
+    }
+}
@@ -0,0 +1,676 @@
+//! Structured waveform model for SSD1677 LUTs
+//!
+//! [`Waveform`] is a typed view of the 112-byte table sent with
+//! [`WRITE_LUT`](crate::command::WRITE_LUT). It encodes to the exact byte layout
+//! the controller expects and decodes any existing table (such as
+//! [`LUT_FAST`](crate::lut::LUT_FAST)) back into its fields.
+//!
+//! ## Byte Layout
+//!
+//! | Offset | Size | Content |
+//! |--------|------|---------|
+//! | 0      | 50   | Voltage selections: 5 LUTs x 10 groups, 4 phases (A-D) per byte |
+//! | 50     | 50   | Timing: 10 groups x `[TP_A, TP_B, TP_C, TP_D, RP]` |
+//! | 100    | 5    | Frame rate (one byte per pair of groups) |
+//! | 105    | 5    | Voltages: VGH, VSH1, VSH2, VSL, VCOM |
+//! | 110    | 2    | Reserved |
+//!
+//! The first 105 bytes form the "short" LUT accepted by
+//! [`Display::load_lut_with_voltages`](crate::display::Display::load_lut_with_voltages),
+//! which programs the voltages through their own registers instead.
+//!
+//! Within a voltage selection byte, phase A occupies bits 7-6 and phase D bits 1-0.
+//! LUT0-LUT3 are selected per pixel by the `(RED, BW)` RAM bits; LUT4 drives VCOM.
+//!
+//! ## Analysis
+//!
+//! [`Waveform::analyze`] checks a waveform for structural problems ([`crate::waveform::LutIssue`])
+//! and estimates its frame count and refresh time, so hand-tuned LUTs can be
+//! vetted before they are sent to a panel.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssd1677::lut::LUT_FAST;
+//! use ssd1677::waveform::{VoltageSelect, Waveform};
+//!
+//! let mut waveform = Waveform::from_bytes(&LUT_FAST);
+//! assert_eq!(waveform.to_bytes(), LUT_FAST);
+//!
+//! // Drive LUT1 to VSH1 for the first phase of group 0
+//! waveform.transitions[1][0][0] = VoltageSelect::Vsh1;
+//! assert_eq!(waveform.to_bytes()[10], 0x40);
+//! ```
+
+use crate::lut::{LUT_SHORT_SIZE, LUT_SIZE};
+
+/// Number of phase groups in a waveform
+pub const LUT_GROUPS: usize = 10;
+/// Number of phases (A-D) in each group
+pub const LUT_PHASES: usize = 4;
+/// Number of pixel transition LUTs (LUT0-LUT3), excluding the VCOM LUT
+pub const LUT_TRANSITIONS: usize = 4;
+/// Number of frame rate bytes (each covers two groups)
+pub const LUT_FRAME_RATE_BYTES: usize = 5;
+
+const VS_OFFSET: usize = 0;
+const TIMING_OFFSET: usize = VS_OFFSET + (LUT_TRANSITIONS + 1) * LUT_GROUPS;
+const FRAME_RATE_OFFSET: usize = TIMING_OFFSET + LUT_GROUPS * (LUT_PHASES + 1);
+const VOLTAGE_OFFSET: usize = FRAME_RATE_OFFSET + LUT_FRAME_RATE_BYTES;
+const RESERVED_OFFSET: usize = VOLTAGE_OFFSET + 5;
+
+/// Voltage selected for a single phase
+///
+/// For LUT4 (VCOM) the codes `0b00`-`0b10` select DCVCOM, VSH1+DCVCOM and
+/// VSL+DCVCOM; `0b11` is not valid there (see [`LutIssue::IllegalVcomCode`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(u8)]
+pub enum VoltageSelect {
+    /// VSS (ground), no drive
+    #[default]
+    Vss = 0b00,
+    /// VSH1 (positive drive, typically towards white)
+    Vsh1 = 0b01,
+    /// VSL (negative drive, typically towards black)
+    Vsl = 0b10,
+    /// VSH2 (second positive level, typically for red)
+    Vsh2 = 0b11,
+}
+
+impl VoltageSelect {
+    /// Decode a 2-bit voltage code (upper bits are ignored)
+    pub fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Self::Vss,
+            0b01 => Self::Vsh1,
+            0b10 => Self::Vsl,
+            _ => Self::Vsh2,
+        }
+    }
+
+    /// Get the 2-bit voltage code
+    pub fn bits(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Voltage selections for phases A-D of one group
+pub type GroupVoltages = [VoltageSelect; LUT_PHASES];
+
+/// Voltage selections for every group of one LUT
+pub type Transition = [GroupVoltages; LUT_GROUPS];
+
+/// Frame counts for one phase group
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GroupTiming {
+    /// Number of frames for phases A-D (TP)
+    pub phases: [u8; LUT_PHASES],
+    /// Repeat count for the whole group (RP)
+    pub repeat: u8,
+}
+
+/// Driving voltage register values carried at the end of a full LUT
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LutVoltages {
+    /// Gate voltage (VGH), as written with [`GATE_VOLTAGE`](crate::command::GATE_VOLTAGE)
+    pub vgh: u8,
+    /// Source voltage VSH1
+    pub vsh1: u8,
+    /// Source voltage VSH2
+    pub vsh2: u8,
+    /// Source voltage VSL
+    pub vsl: u8,
+    /// VCOM, as written with [`WRITE_VCOM`](crate::command::WRITE_VCOM)
+    pub vcom: u8,
+}
+
+impl LutVoltages {
+    /// Source voltages in [`SOURCE_VOLTAGE`](crate::command::SOURCE_VOLTAGE) order
+    pub fn source(&self) -> [u8; 3] {
+        [self.vsh1, self.vsh2, self.vsl]
+    }
+}
+
+/// Typed SSD1677 waveform
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Waveform {
+    /// Voltage selections for LUT0-LUT3, indexed by the `(RED, BW)` RAM bits
+    pub transitions: [Transition; LUT_TRANSITIONS],
+    /// Voltage selections for LUT4 (VCOM)
+    ///
+    /// [`VoltageSelect::Vsh2`] has no meaning here; it is decoded and encoded
+    /// as-is so tables round-trip, and [`Waveform::analyze`] reports it.
+    pub vcom: Transition,
+    /// TP/RP timing for each group
+    pub timing: [GroupTiming; LUT_GROUPS],
+    /// Frame rate bytes (each covers two consecutive groups)
+    pub frame_rate: [u8; LUT_FRAME_RATE_BYTES],
+    /// Driving voltages
+    pub voltages: LutVoltages,
+    /// Trailing reserved bytes, preserved for round-tripping
+    pub reserved: [u8; 2],
+}
+
+impl Waveform {
+    /// Decode a full 112-byte LUT
+    pub fn from_bytes(lut: &[u8; LUT_SIZE]) -> Self {
+        let mut short = [0u8; LUT_SHORT_SIZE];
+        short.copy_from_slice(&lut[..LUT_SHORT_SIZE]);
+        let v = &lut[VOLTAGE_OFFSET..RESERVED_OFFSET];
+        let voltages = LutVoltages {
+            vgh: v[0],
+            vsh1: v[1],
+            vsh2: v[2],
+            vsl: v[3],
+            vcom: v[4],
+        };
+        let mut waveform = Self::from_short_bytes(&short, voltages);
+        waveform
+            .reserved
+            .copy_from_slice(&lut[RESERVED_OFFSET..LUT_SIZE]);
+        waveform
+    }
+
+    /// Decode a 105-byte short LUT, pairing it with separately supplied voltages
+    pub fn from_short_bytes(lut: &[u8; LUT_SHORT_SIZE], voltages: LutVoltages) -> Self {
+        let mut waveform = Self {
+            voltages,
+            ..Self::default()
+        };
+
+        for (lut_index, chunk) in lut[VS_OFFSET..TIMING_OFFSET]
+            .chunks_exact(LUT_GROUPS)
+            .enumerate()
+        {
+            let target = if lut_index < LUT_TRANSITIONS {
+                &mut waveform.transitions[lut_index]
+            } else {
+                &mut waveform.vcom
+            };
+            for (group, byte) in target.iter_mut().zip(chunk) {
+                *group = decode_group(*byte);
+            }
+        }
+
+        for (timing, chunk) in waveform
+            .timing
+            .iter_mut()
+            .zip(lut[TIMING_OFFSET..FRAME_RATE_OFFSET].chunks_exact(LUT_PHASES + 1))
+        {
+            timing.phases.copy_from_slice(&chunk[..LUT_PHASES]);
+            timing.repeat = chunk[LUT_PHASES];
+        }
+
+        waveform
+            .frame_rate
+            .copy_from_slice(&lut[FRAME_RATE_OFFSET..VOLTAGE_OFFSET]);
+
+        waveform
+    }
+
+    /// Encode to the full 112-byte layout expected by `WRITE_LUT`
+    pub fn to_bytes(&self) -> [u8; LUT_SIZE] {
+        let mut lut = [0u8; LUT_SIZE];
+        lut[..LUT_SHORT_SIZE].copy_from_slice(&self.to_short_bytes());
+        lut[VOLTAGE_OFFSET..RESERVED_OFFSET].copy_from_slice(&[
+            self.voltages.vgh,
+            self.voltages.vsh1,
+            self.voltages.vsh2,
+            self.voltages.vsl,
+            self.voltages.vcom,
+        ]);
+        lut[RESERVED_OFFSET..].copy_from_slice(&self.reserved);
+        lut
+    }
+
+    /// Encode to the 105-byte short layout (voltages omitted)
+    pub fn to_short_bytes(&self) -> [u8; LUT_SHORT_SIZE] {
+        let mut lut = [0u8; LUT_SHORT_SIZE];
+
+        let luts = self.transitions.iter().chain(core::iter::once(&self.vcom));
+        for (transition, chunk) in
+            luts.zip(lut[VS_OFFSET..TIMING_OFFSET].chunks_exact_mut(LUT_GROUPS))
+        {
+            for (byte, group) in chunk.iter_mut().zip(transition) {
+                *byte = encode_group(*group);
+            }
+        }
+
+        for (timing, chunk) in self
+            .timing
+            .iter()
+            .zip(lut[TIMING_OFFSET..FRAME_RATE_OFFSET].chunks_exact_mut(LUT_PHASES + 1))
+        {
+            chunk[..LUT_PHASES].copy_from_slice(&timing.phases);
+            chunk[LUT_PHASES] = timing.repeat;
+        }
+
+        lut[FRAME_RATE_OFFSET..].copy_from_slice(&self.frame_rate);
+        lut
+    }
+}
+
+/// Problem found while analyzing a waveform
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LutIssue {
+    /// The VCOM LUT uses code `0b11`, which is not defined for VCOM
+    IllegalVcomCode {
+        /// Group index
+        group: usize,
+        /// Phase index (0 = A)
+        phase: usize,
+    },
+    /// The waveform has no frames at all, so the update does nothing
+    NoFrames,
+    /// A LUT drives a voltage in a phase whose frame count (TP) is zero
+    UnusedPhase {
+        /// LUT index (0-3 for transitions, 4 for VCOM)
+        lut: usize,
+        /// Group index
+        group: usize,
+        /// Phase index (0 = A)
+        phase: usize,
+    },
+    /// A phase has frames but every LUT selects VSS, so it only adds delay
+    UndrivenPhase {
+        /// Group index
+        group: usize,
+        /// Phase index (0 = A)
+        phase: usize,
+    },
+    /// A group has a repeat count (RP) but no frames to repeat
+    RepeatWithoutFrames {
+        /// Group index
+        group: usize,
+        /// Repeat count
+        repeat: u8,
+    },
+}
+
+impl LutIssue {
+    /// Whether the issue makes the waveform unusable (as opposed to merely wasteful)
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::IllegalVcomCode { .. } | Self::NoFrames)
+    }
+}
+
+impl core::fmt::Display for LutIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::IllegalVcomCode { group, phase } => {
+                write!(f, "illegal VCOM code in group {group} phase {phase}")
+            }
+            Self::NoFrames => write!(f, "waveform has no frames"),
+            Self::UnusedPhase { lut, group, phase } => write!(
+                f,
+                "LUT{lut} drives group {group} phase {phase}, which has no frames"
+            ),
+            Self::UndrivenPhase { group, phase } => {
+                write!(
+                    f,
+                    "group {group} phase {phase} has frames but drives nothing"
+                )
+            }
+            Self::RepeatWithoutFrames { group, repeat } => {
+                write!(f, "group {group} repeats {repeat} times but has no frames")
+            }
+        }
+    }
+}
+
+/// Summary of a waveform analysis
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WaveformAnalysis {
+    /// Total number of frames across all groups, including repeats
+    pub total_frames: u32,
+    /// Estimated refresh time in milliseconds
+    pub duration_ms: u32,
+    /// Number of issues for which [`LutIssue::is_error`] is true
+    pub errors: usize,
+    /// Number of remaining (warning-level) issues
+    pub warnings: usize,
+    /// First error found, if any
+    pub first_error: Option<LutIssue>,
+}
+
+impl WaveformAnalysis {
+    /// Whether the waveform has no errors
+    pub fn is_valid(&self) -> bool {
+        self.errors == 0
+    }
+}
+
+impl Waveform {
+    /// Frame count of a group, including repeats (`RP + 1` passes)
+    pub fn group_frames(&self, group: usize) -> u32 {
+        let timing = &self.timing[group];
+        let frames: u32 = timing.phases.iter().map(|tp| *tp as u32).sum();
+        frames * (timing.repeat as u32 + 1)
+    }
+
+    /// Total frame count across all groups
+    pub fn total_frames(&self) -> u32 {
+        (0..LUT_GROUPS).map(|group| self.group_frames(group)).sum()
+    }
+
+    /// Frame rate of a group in millihertz
+    ///
+    /// Each frame rate byte covers two groups: the high nibble applies to the
+    /// even group and the low nibble to the odd one. A nibble `n` selects
+    /// `25 + 12.5 * n` Hz.
+    pub fn frame_rate_mhz(&self, group: usize) -> u32 {
+        let byte = self.frame_rate[group / 2];
+        let code = if group % 2 == 0 {
+            byte >> 4
+        } else {
+            byte & 0x0F
+        };
+        25_000 + 12_500 * code as u32
+    }
+
+    /// Estimated refresh time in milliseconds from the frame counts and frame rates
+    pub fn estimated_duration_ms(&self) -> u32 {
+        let micros: u64 = (0..LUT_GROUPS)
+            .map(|group| {
+                self.group_frames(group) as u64 * 1_000_000_000 / self.frame_rate_mhz(group) as u64
+            })
+            .sum();
+        micros.div_ceil(1_000) as u32
+    }
+
+    /// Report every structural issue to `f`, errors and warnings alike
+    pub fn for_each_issue<F: FnMut(LutIssue)>(&self, mut f: F) {
+        for (group, timing) in self.timing.iter().enumerate() {
+            for phase in 0..LUT_PHASES {
+                self.phase_issues(group, phase, &mut f);
+            }
+
+            if timing.repeat > 0 && timing.phases.iter().all(|tp| *tp == 0) {
+                f(LutIssue::RepeatWithoutFrames {
+                    group,
+                    repeat: timing.repeat,
+                });
+            }
+        }
+
+        if self.total_frames() == 0 {
+            f(LutIssue::NoFrames);
+        }
+    }
+
+    /// Report issues for a single phase of a group
+    fn phase_issues<F: FnMut(LutIssue)>(&self, group: usize, phase: usize, f: &mut F) {
+        let frames = self.timing[group].phases[phase];
+        if self.vcom[group][phase] == VoltageSelect::Vsh2 {
+            f(LutIssue::IllegalVcomCode { group, phase });
+        }
+
+        let luts = self.transitions.iter().chain(core::iter::once(&self.vcom));
+        let mut driven = false;
+        for (lut, transition) in luts.enumerate() {
+            if transition[group][phase] == VoltageSelect::Vss {
+                continue;
+            }
+            driven = true;
+            if frames == 0 {
+                f(LutIssue::UnusedPhase { lut, group, phase });
+            }
+        }
+
+        if frames > 0 && !driven {
+            f(LutIssue::UndrivenPhase { group, phase });
+        }
+    }
+
+    /// Validate the waveform, returning the first error found
+    pub fn validate(&self) -> Result<(), LutIssue> {
+        self.analyze().first_error.map_or(Ok(()), Err)
+    }
+
+    /// Run structural validation and estimate frame count and refresh time
+    pub fn analyze(&self) -> WaveformAnalysis {
+        let mut analysis = WaveformAnalysis {
+            total_frames: self.total_frames(),
+            duration_ms: self.estimated_duration_ms(),
+            ..WaveformAnalysis::default()
+        };
+        self.for_each_issue(|issue| {
+            if issue.is_error() {
+                analysis.errors += 1;
+                analysis.first_error.get_or_insert(issue);
+            } else {
+                analysis.warnings += 1;
+            }
+        });
+        analysis
+    }
+}
+
+impl From<&[u8; LUT_SIZE]> for Waveform {
+    fn from(lut: &[u8; LUT_SIZE]) -> Self {
+        Self::from_bytes(lut)
+    }
+}
+
+impl From<&Waveform> for [u8; LUT_SIZE] {
+    fn from(waveform: &Waveform) -> Self {
+        waveform.to_bytes()
+    }
+}
+
+/// Unpack one voltage selection byte (phase A in the MSBs)
+fn decode_group(byte: u8) -> GroupVoltages {
+    let mut group = [VoltageSelect::Vss; LUT_PHASES];
+    for (phase, voltage) in group.iter_mut().enumerate() {
+        *voltage = VoltageSelect::from_bits(byte >> (6 - 2 * phase));
+    }
+    group
+}
+
+/// Pack one group's voltage selections into a byte (phase A in the MSBs)
+fn encode_group(group: GroupVoltages) -> u8 {
+    group
+        .iter()
+        .enumerate()
+        .fold(0u8, |byte, (phase, voltage)| {
+            byte | (voltage.bits() << (6 - 2 * phase))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lut::{LUT_CLEANUP, LUT_FAST, LUT_GRAYSCALE, LUT_GRAYSCALE_REVERT, LUT_PARTIAL};
+
+    #[test]
+    fn test_builtin_luts_round_trip() {
+        for lut in [
+            &LUT_FAST,
+            &LUT_PARTIAL,
+            &LUT_CLEANUP,
+            &LUT_GRAYSCALE,
+            &LUT_GRAYSCALE_REVERT,
+        ] {
+            assert_eq!(&Waveform::from_bytes(lut).to_bytes(), lut);
+        }
+    }
+
+    #[test]
+    fn test_every_byte_position_round_trips() {
+        let mut lut = [0u8; LUT_SIZE];
+        for (i, byte) in lut.iter_mut().enumerate() {
+            *byte = (i as u8).wrapping_mul(37).wrapping_add(11);
+        }
+        assert_eq!(Waveform::from_bytes(&lut).to_bytes(), lut);
+    }
+
+    #[test]
+    fn test_decode_grayscale_fields() {
+        let waveform = Waveform::from_bytes(&LUT_GRAYSCALE);
+
+        // 0x54 = 01 01 01 00
+        assert_eq!(
+            waveform.transitions[1][0],
+            [
+                VoltageSelect::Vsh1,
+                VoltageSelect::Vsh1,
+                VoltageSelect::Vsh1,
+                VoltageSelect::Vss
+            ]
+        );
+        // 0xA2 = 10 10 00 10
+        assert_eq!(
+            waveform.transitions[3][0],
+            [
+                VoltageSelect::Vsl,
+                VoltageSelect::Vsl,
+                VoltageSelect::Vss,
+                VoltageSelect::Vsl
+            ]
+        );
+        assert_eq!(
+            waveform.timing[0],
+            GroupTiming {
+                phases: [1, 1, 1, 1],
+                repeat: 0
+            }
+        );
+        assert_eq!(waveform.timing[3], GroupTiming::default());
+        assert_eq!(waveform.frame_rate, [0x8F; LUT_FRAME_RATE_BYTES]);
+        assert_eq!(
+            waveform.voltages,
+            LutVoltages {
+                vgh: 0x17,
+                vsh1: 0x41,
+                vsh2: 0xA8,
+                vsl: 0x32,
+                vcom: 0x30,
+            }
+        );
+        assert_eq!(waveform.voltages.source(), [0x41, 0xA8, 0x32]);
+    }
+
+    #[test]
+    fn test_encode_places_fields_at_datasheet_offsets() {
+        let mut waveform = Waveform::default();
+        waveform.transitions[2][3] = [
+            VoltageSelect::Vsh2,
+            VoltageSelect::Vss,
+            VoltageSelect::Vsl,
+            VoltageSelect::Vsh1,
+        ];
+        waveform.vcom[9][3] = VoltageSelect::Vsh1;
+        waveform.timing[9] = GroupTiming {
+            phases: [2, 3, 4, 5],
+            repeat: 6,
+        };
+        waveform.frame_rate[4] = 0x44;
+        waveform.voltages.vcom = 0x3C;
+        waveform.reserved = [0xAA, 0x55];
+
+        let lut = waveform.to_bytes();
+        assert_eq!(lut[23], 0b1100_1001);
+        assert_eq!(lut[49], 0x01);
+        assert_eq!(&lut[95..100], &[2, 3, 4, 5, 6]);
+        assert_eq!(lut[104], 0x44);
+        assert_eq!(lut[109], 0x3C);
+        assert_eq!(&lut[110..], &[0xAA, 0x55]);
+        assert_eq!(waveform.to_short_bytes()[..], lut[..LUT_SHORT_SIZE]);
+    }
+
+    fn single_phase_waveform() -> Waveform {
+        let mut waveform = Waveform::default();
+        waveform.transitions[1][0][0] = VoltageSelect::Vsh1;
+        waveform.transitions[2][0][0] = VoltageSelect::Vsl;
+        waveform.timing[0].phases[0] = 10;
+        waveform
+    }
+
+    #[test]
+    fn test_analyze_clean_waveform() {
+        let analysis = single_phase_waveform().analyze();
+        assert!(analysis.is_valid());
+        assert_eq!(analysis.warnings, 0);
+        assert_eq!(analysis.total_frames, 10);
+        // 10 frames at 25 Hz
+        assert_eq!(analysis.duration_ms, 400);
+    }
+
+    #[test]
+    fn test_analyze_counts_repeats_and_frame_rate() {
+        let mut waveform = single_phase_waveform();
+        waveform.timing[0].repeat = 1;
+        waveform.timing[1].phases = [1, 1, 1, 1];
+        waveform.transitions[1][1] = [VoltageSelect::Vsh1; LUT_PHASES];
+        // Group 0: 50 Hz, group 1: 100 Hz
+        waveform.frame_rate[0] = 0x26;
+
+        assert_eq!(waveform.group_frames(0), 20);
+        assert_eq!(waveform.frame_rate_mhz(0), 50_000);
+        assert_eq!(waveform.frame_rate_mhz(1), 100_000);
+        assert_eq!(waveform.total_frames(), 24);
+        assert_eq!(waveform.estimated_duration_ms(), 440);
+    }
+
+    #[test]
+    fn test_empty_waveform_has_no_frames() {
+        assert_eq!(Waveform::default().validate(), Err(LutIssue::NoFrames));
+    }
+
+    #[test]
+    fn test_illegal_vcom_code_is_error() {
+        let mut waveform = single_phase_waveform();
+        waveform.vcom[0][0] = VoltageSelect::Vsh2;
+        assert_eq!(
+            waveform.validate(),
+            Err(LutIssue::IllegalVcomCode { group: 0, phase: 0 })
+        );
+    }
+
+    #[test]
+    fn test_structural_warnings() {
+        let mut waveform = single_phase_waveform();
+        waveform.transitions[3][2][1] = VoltageSelect::Vsl;
+        waveform.timing[0].phases[3] = 2;
+        waveform.timing[5].repeat = 3;
+
+        let mut issues = alloc::vec::Vec::new();
+        waveform.for_each_issue(|issue| issues.push(issue));
+        assert_eq!(
+            issues,
+            [
+                LutIssue::UndrivenPhase { group: 0, phase: 3 },
+                LutIssue::UnusedPhase {
+                    lut: 3,
+                    group: 2,
+                    phase: 1
+                },
+                LutIssue::RepeatWithoutFrames {
+                    group: 5,
+                    repeat: 3
+                },
+            ]
+        );
+
+        let analysis = waveform.analyze();
+        assert!(analysis.is_valid());
+        assert_eq!(analysis.warnings, 3);
+    }
+
+    #[test]
+    fn test_short_bytes_round_trip_with_voltages() {
+        let voltages = LutVoltages {
+            vgh: 0x17,
+            vsh1: 0x41,
+            vsh2: 0xA8,
+            vsl: 0x32,
+            vcom: 0x30,
+        };
+        let mut short = [0u8; LUT_SHORT_SIZE];
+        short.copy_from_slice(&LUT_PARTIAL[..LUT_SHORT_SIZE]);
+
+        let waveform = Waveform::from_short_bytes(&short, voltages);
+        assert_eq!(waveform.to_short_bytes(), short);
+        assert_eq!(waveform.voltages, voltages);
+    }
+}